@@ -0,0 +1,4 @@
+-- Add migration script here
+-- 토큰의 만료와 일회성 사용을 위해 생성 시각과 사용 시각을 기록한다.
+ALTER TABLE subscription_tokens ADD COLUMN created_at timestamptz NOT NULL DEFAULT now();
+ALTER TABLE subscription_tokens ADD COLUMN consumed_at timestamptz NULL;
@@ -0,0 +1,5 @@
+-- Add migration script here
+-- 평문으로 저장된 기존 토큰을 SHA-256 다이제스트(16진수)로 변환한다.
+-- 조회 시 입력된 토큰을 같은 방식으로 해싱하므로 이미 발송된 확인 링크도 계속 동작한다.
+UPDATE subscription_tokens
+    SET subscription_token = encode(sha256(convert_to(subscription_token, 'UTF8')), 'hex');
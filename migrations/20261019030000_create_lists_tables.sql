@@ -0,0 +1,28 @@
+-- Add migration script here
+-- 여러 뉴스레터(메일링 리스트)를 운영하기 위한 테이블
+CREATE TABLE lists (
+    list_id uuid NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (list_id)
+);
+
+-- 구독자와 리스트의 다대다 관계. 리스트마다 따로 구독을 확인한다.
+CREATE TABLE list_memberships (
+    list_id uuid NOT NULL
+        REFERENCES lists (list_id) ON DELETE CASCADE,
+    subscriber_id uuid NOT NULL
+        REFERENCES subscriptions (id) ON DELETE CASCADE,
+    status TEXT NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (list_id, subscriber_id)
+);
+
+-- 기존 구독자는 모두 기본 리스트의 멤버가 된다.
+INSERT INTO lists (list_id, slug, name)
+VALUES ('8b0f4c5e-2f5a-4a53-9c2d-6a3f7d8e9b10', 'newsletter', 'Newsletter');
+
+INSERT INTO list_memberships (list_id, subscriber_id, status)
+SELECT '8b0f4c5e-2f5a-4a53-9c2d-6a3f7d8e9b10', id, status
+FROM subscriptions;
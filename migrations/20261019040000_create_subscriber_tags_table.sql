@@ -0,0 +1,14 @@
+-- Add migration script here
+-- 구독자에게 붙이는 태그. 세그먼트로 발송 대상을 좁힐 때 사용한다.
+CREATE TABLE subscriber_tags (
+    subscriber_id uuid NOT NULL
+        REFERENCES subscriptions (id) ON DELETE CASCADE,
+    tag TEXT NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (subscriber_id, tag)
+);
+
+CREATE INDEX subscriber_tags_tag_idx ON subscriber_tags (tag);
+
+-- 세그먼트에서 비교할 수 있는 구독자별 사용자 정의 필드
+ALTER TABLE subscriptions ADD COLUMN custom_fields JSONB NOT NULL DEFAULT '{}'::jsonb;
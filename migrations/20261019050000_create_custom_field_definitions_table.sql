@@ -0,0 +1,13 @@
+-- Add migration script here
+-- 관리자가 정의하는 구독자 사용자 정의 필드. 값은 subscriptions.custom_fields(JSONB)에 'key'로 저장된다.
+CREATE TABLE custom_field_definitions (
+    key TEXT NOT NULL,
+    label TEXT NOT NULL,
+    -- 'text', 'number', 'boolean', 'choice'
+    field_type TEXT NOT NULL,
+    required BOOLEAN NOT NULL DEFAULT false,
+    -- 'choice' 타입에서 허용하는 값 목록
+    choices TEXT[] NOT NULL DEFAULT '{}',
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (key)
+);
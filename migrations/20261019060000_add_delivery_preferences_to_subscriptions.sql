@@ -0,0 +1,6 @@
+-- Add migration script here
+-- 구독 설정 페이지에서 구독자가 직접 바꾸는 발송 설정
+-- delivery_frequency: 'immediate'(이슈마다 발송) 또는 'weekly'(주간 다이제스트)
+ALTER TABLE subscriptions ADD COLUMN delivery_frequency TEXT NOT NULL DEFAULT 'immediate';
+-- 이 시각까지는 이슈를 발송하지 않는다.
+ALTER TABLE subscriptions ADD COLUMN paused_until timestamptz NULL;
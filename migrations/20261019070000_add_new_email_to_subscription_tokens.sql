@@ -0,0 +1,3 @@
+-- Add migration script here
+-- 이메일 변경 요청의 확인 토큰. 값이 있으면 확인 시 구독자의 이메일을 이 주소로 바꾼다.
+ALTER TABLE subscription_tokens ADD COLUMN new_email TEXT NULL;
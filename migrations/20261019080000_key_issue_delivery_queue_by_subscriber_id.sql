@@ -0,0 +1,20 @@
+-- Add migration script here
+-- 발송 큐가 이메일 문자열 대신 구독자 ID를 참조하도록 바꾼다.
+-- 워커는 발송 시점에 구독자의 현재 상태와 주소를 다시 읽는다.
+ALTER TABLE issue_delivery_queue ADD COLUMN subscriber_id uuid NULL;
+
+UPDATE issue_delivery_queue q
+SET subscriber_id = s.id
+FROM subscriptions s
+WHERE s.email = q.subscriber_email;
+
+-- 더 이상 존재하지 않는 구독자에 대한 작업은 버린다.
+DELETE FROM issue_delivery_queue WHERE subscriber_id IS NULL;
+
+ALTER TABLE issue_delivery_queue DROP CONSTRAINT issue_delivery_queue_pkey;
+ALTER TABLE issue_delivery_queue DROP COLUMN subscriber_email;
+ALTER TABLE issue_delivery_queue ALTER COLUMN subscriber_id SET NOT NULL;
+ALTER TABLE issue_delivery_queue
+    ADD CONSTRAINT issue_delivery_queue_subscriber_id_fkey
+    FOREIGN KEY (subscriber_id) REFERENCES subscriptions (id) ON DELETE CASCADE;
+ALTER TABLE issue_delivery_queue ADD PRIMARY KEY (newsletter_issue_id, subscriber_id);
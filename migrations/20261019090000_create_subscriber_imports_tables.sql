@@ -0,0 +1,36 @@
+-- Add migration script here
+-- 가져오기로 추가된 구독자의 출처와 구독 동의 시점
+ALTER TABLE subscriptions ADD COLUMN source TEXT NULL;
+ALTER TABLE subscriptions ADD COLUMN consented_at timestamptz NULL;
+
+-- 관리자가 업로드한 CSV 가져오기 작업
+-- mode: 'confirmed' (확인된 구독자로 바로 추가) 또는 'confirmation_email' (확인 이메일 발송)
+-- status: 'draft' (검증 결과 확인 중), 'queued' (백그라운드 작업 대기/진행 중), 'completed'
+CREATE TABLE subscriber_imports (
+    import_id uuid NOT NULL,
+    list_id uuid NOT NULL
+        REFERENCES lists (list_id) ON DELETE CASCADE,
+    mode TEXT NOT NULL,
+    source TEXT NOT NULL,
+    status TEXT NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    completed_at timestamptz NULL,
+    PRIMARY KEY (import_id)
+);
+
+-- CSV의 각 행과 처리 결과
+-- outcome: 'pending', 'invalid', 'duplicate', 'existing', 'imported', 'failed'
+CREATE TABLE subscriber_import_rows (
+    import_id uuid NOT NULL
+        REFERENCES subscriber_imports (import_id) ON DELETE CASCADE,
+    line INT NOT NULL,
+    email TEXT NOT NULL,
+    name TEXT NOT NULL,
+    consented_at timestamptz NULL,
+    outcome TEXT NOT NULL,
+    error TEXT NULL,
+    PRIMARY KEY (import_id, line)
+);
+
+CREATE INDEX subscriber_import_rows_pending_idx
+    ON subscriber_import_rows (import_id) WHERE outcome = 'pending';
@@ -0,0 +1,11 @@
+-- Add migration script here
+-- 구독을 확인한 시점. 내보내기에 포함된다.
+ALTER TABLE subscriptions ADD COLUMN confirmed_at timestamptz NULL;
+
+-- 기존 구독자는 확인 토큰을 사용한 시점으로 채운다.
+UPDATE subscriptions s
+SET confirmed_at = (
+    SELECT MIN(t.consumed_at) FROM subscription_tokens t
+    WHERE t.subscriber_id = s.id AND t.new_email IS NULL
+)
+WHERE s.status = 'confirmed';
@@ -0,0 +1,9 @@
+-- Add migration script here
+-- 더 이상 추가하거나 발송하면 안 되는 주소. 주소 자체는 저장하지 않고 정규화한 주소의 SHA-256 다이제스트만 저장한다.
+-- reason: 'erasure' (개인정보 삭제 요청)
+CREATE TABLE suppressed_emails (
+    email_hash TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (email_hash)
+);
@@ -0,0 +1,32 @@
+-- Add migration script here
+-- 구독자가 언제, 어떻게 구독에 동의했는지 증명하기 위한 추가 전용 이벤트 기록.
+-- 구독자 행이 삭제되어도 기록은 남도록 외래 키를 두지 않는다.
+-- event_type: 'subscribed', 'confirmation_email_sent', 'confirmed', 'unsubscribed', 'bounced',
+--             'preferences_changed', 'erased'
+-- source: 'form', 'api', 'email_link', 'import', 'admin', 'system'
+CREATE TABLE subscription_events (
+    event_id uuid NOT NULL,
+    subscriber_id uuid NOT NULL,
+    event_type TEXT NOT NULL,
+    source TEXT NOT NULL,
+    ip_address TEXT NULL,
+    user_agent TEXT NULL,
+    details JSONB NOT NULL DEFAULT '{}',
+    -- 한 트랜잭션에서 여러 이벤트를 기록해도 순서가 유지되도록 now() 대신 clock_timestamp()를 사용한다.
+    occurred_at timestamptz NOT NULL DEFAULT clock_timestamp(),
+    PRIMARY KEY (event_id)
+);
+
+CREATE INDEX subscription_events_subscriber_id_idx
+    ON subscription_events (subscriber_id, occurred_at);
+
+-- 기록은 수정할 수 없다. 삭제는 개인정보 삭제 요청을 처리할 때만 한다.
+CREATE FUNCTION reject_subscription_event_update() RETURNS trigger AS $$
+BEGIN
+    RAISE EXCEPTION 'subscription_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER subscription_events_append_only
+    BEFORE UPDATE ON subscription_events
+    FOR EACH ROW EXECUTE FUNCTION reject_subscription_event_update();
@@ -0,0 +1,13 @@
+-- Add migration script here
+-- 20261019 추가 / Postmark 반송 및 스팸 신고 웹훅 처리
+-- 구독자 상태에 'bounced'(반송)와 'complained'(스팸 신고)가 추가된다.
+-- 억제 목록의 reason에 'hard_bounce', 'spam_complaint', 'soft_bounce_limit'이 추가된다.
+ALTER TABLE subscriptions ADD COLUMN soft_bounce_count INT NOT NULL DEFAULT 0;
+
+-- Postmark는 같은 웹훅을 다시 보낼 수 있다. 이미 처리한 이벤트의 ID를 기록해서 한 번만 처리한다.
+CREATE TABLE processed_webhook_events (
+    provider TEXT NOT NULL,
+    event_id TEXT NOT NULL,
+    received_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (provider, event_id)
+);
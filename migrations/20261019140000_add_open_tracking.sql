@@ -0,0 +1,20 @@
+-- Add migration script here
+-- 20261019 추가 / 이슈 열람 추적
+-- 이슈별로 추적 여부를 선택하고, 구독자는 구독 설정 페이지에서 추적을 거부할 수 있다.
+ALTER TABLE newsletter_issues ADD COLUMN track_opens BOOLEAN NOT NULL DEFAULT FALSE;
+-- 발행 시점에 발송 큐에 적재한 구독자 수. 열람률의 분모로 사용한다.
+ALTER TABLE newsletter_issues ADD COLUMN recipient_count INT NOT NULL DEFAULT 0;
+ALTER TABLE subscriptions ADD COLUMN tracking_opt_out BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- 구독자마다 이슈를 처음 연 시각과 연 횟수. User-Agent 원문은 저장하지 않고 분류만 저장한다.
+CREATE TABLE issue_opens (
+    newsletter_issue_id uuid NOT NULL
+        REFERENCES newsletter_issues (newsletter_issue_id) ON DELETE CASCADE,
+    subscriber_id uuid NOT NULL
+        REFERENCES subscriptions (id) ON DELETE CASCADE,
+    first_opened_at timestamptz NOT NULL DEFAULT now(),
+    last_opened_at timestamptz NOT NULL DEFAULT now(),
+    open_count INT NOT NULL DEFAULT 1,
+    user_agent_class TEXT NOT NULL,
+    PRIMARY KEY (newsletter_issue_id, subscriber_id)
+);
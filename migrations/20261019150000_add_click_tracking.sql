@@ -0,0 +1,24 @@
+-- Add migration script here
+-- 20261019 추가 / 이슈 링크 클릭 추적
+-- 발송 워커가 이슈 본문의 링크를 등록하고 서명된 '/r/{token}' 리다이렉트로 바꾼다.
+-- 토큰에는 원래 URL 대신 링크 ID만 들어가므로 임의의 주소로 리다이렉트하는 데 쓸 수 없다.
+CREATE TABLE issue_links (
+    link_id uuid PRIMARY KEY,
+    newsletter_issue_id uuid NOT NULL
+        REFERENCES newsletter_issues (newsletter_issue_id) ON DELETE CASCADE,
+    url TEXT NOT NULL,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    UNIQUE (newsletter_issue_id, url)
+);
+
+-- 구독자마다 링크를 처음 클릭한 시각과 클릭 횟수
+CREATE TABLE link_clicks (
+    link_id uuid NOT NULL
+        REFERENCES issue_links (link_id) ON DELETE CASCADE,
+    subscriber_id uuid NOT NULL
+        REFERENCES subscriptions (id) ON DELETE CASCADE,
+    first_clicked_at timestamptz NOT NULL DEFAULT now(),
+    last_clicked_at timestamptz NOT NULL DEFAULT now(),
+    click_count INT NOT NULL DEFAULT 1,
+    PRIMARY KEY (link_id, subscriber_id)
+);
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- 20261019 추가 / 이슈별 UTM 파라미터 설정. NULL이면 파라미터를 붙이지 않는다.
+ALTER TABLE newsletter_issues ADD COLUMN utm_campaign TEXT;
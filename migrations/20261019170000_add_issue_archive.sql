@@ -0,0 +1,9 @@
+-- Add migration script here
+-- 20261019 추가 / 발행한 이슈의 웹 아카이브
+-- 'public'은 누구나, 'subscribers'는 이메일의 서명된 링크로만 볼 수 있고, 'hidden'은 웹에 보여주지 않는다.
+-- 기존 이슈는 공개 여부를 정한 적이 없으므로 'hidden'으로 두고, 관리자가 이슈 보고서에서 바꿀 수 있다.
+ALTER TABLE newsletter_issues ADD COLUMN visibility TEXT NOT NULL DEFAULT 'hidden';
+ALTER TABLE newsletter_issues ADD COLUMN slug TEXT;
+UPDATE newsletter_issues SET slug = newsletter_issue_id::text;
+ALTER TABLE newsletter_issues ALTER COLUMN slug SET NOT NULL;
+ALTER TABLE newsletter_issues ADD CONSTRAINT newsletter_issues_slug_key UNIQUE (slug);
@@ -0,0 +1,6 @@
+-- Add migration script here
+-- 20261019 추가 / 피드와 아카이브를 발행 시각 순으로 정렬하기 위해 텍스트로 저장하던 발행 시각을 timestamptz로 바꾼다.
+-- 기존 값은 모두 'now()'를 텍스트로 저장한 것이므로 그대로 변환된다.
+ALTER TABLE newsletter_issues
+    ALTER COLUMN published_at TYPE timestamptz USING published_at::timestamptz,
+    ALTER COLUMN published_at SET DEFAULT now();
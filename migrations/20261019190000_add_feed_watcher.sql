@@ -0,0 +1,35 @@
+-- Add migration script here
+-- 20261019 추가 / 초안과 예약 이슈
+-- 'published'는 발송 큐에 적재된 이슈, 'draft'는 관리자가 발송할 때까지 보류하고,
+-- 'scheduled'는 'scheduled_for'가 지나면 피드 감시 작업이 발송한다. 기존 이슈는 모두 발송되었다.
+ALTER TABLE newsletter_issues ADD COLUMN status TEXT NOT NULL DEFAULT 'published';
+ALTER TABLE newsletter_issues ADD COLUMN scheduled_for timestamptz;
+
+-- 감시하는 피드. 처음 읽었을 때 이미 있던 글은 기준선으로 기록만 하고 발송하지 않는다.
+CREATE TABLE watched_feeds (
+    feed_url TEXT NOT NULL,
+    -- 피드의 제목. 다이제스트 템플릿의 '{{ feed.title }}'
+    title TEXT NOT NULL,
+    last_checked_at timestamptz NOT NULL,
+    -- 다이제스트를 마지막으로 보낸 시각. 아직 보낸 적이 없으면 처음 읽은 시각이다.
+    last_digest_at timestamptz NOT NULL,
+    PRIMARY KEY (feed_url)
+);
+
+-- 이미 본 글. 같은 글로 이슈를 두 번 만들지 않는다.
+CREATE TABLE watched_feed_items (
+    feed_url TEXT NOT NULL
+        REFERENCES watched_feeds (feed_url) ON DELETE CASCADE,
+    item_id TEXT NOT NULL,
+    title TEXT NOT NULL,
+    link TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    -- 한 번에 여러 글을 기록해도 다이제스트에 발견한 순서대로 넣을 수 있도록 'clock_timestamp()'를 사용한다.
+    first_seen_at timestamptz NOT NULL DEFAULT clock_timestamp(),
+    -- 다음 다이제스트에 넣을 글
+    pending_digest BOOLEAN NOT NULL DEFAULT FALSE,
+    -- 이 글을 담은 이슈. 기준선으로 기록한 글과 다이제스트를 기다리는 글은 NULL이다.
+    newsletter_issue_id uuid
+        REFERENCES newsletter_issues (newsletter_issue_id) ON DELETE SET NULL,
+    PRIMARY KEY (feed_url, item_id)
+);
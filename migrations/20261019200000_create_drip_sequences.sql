@@ -0,0 +1,47 @@
+-- Add migration script here
+-- 20261019 추가 / 구독을 확인한 구독자에게 정해진 간격으로 보내는 환영 이메일 시퀀스
+-- 시퀀스의 리스트 구독을 확인하면 등록된다. 비활성화한 시퀀스에는 새로 등록하지 않고 단계도 보내지 않는다.
+CREATE TABLE drip_sequences (
+    sequence_id uuid NOT NULL,
+    name TEXT NOT NULL,
+    list_id uuid NOT NULL
+        REFERENCES lists (list_id) ON DELETE CASCADE,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (sequence_id)
+);
+
+-- 단계의 내용은 발송 워커가 그대로 보낼 수 있도록 'sequence' 상태의 이슈로 저장한다.
+CREATE TABLE drip_steps (
+    step_id uuid NOT NULL,
+    sequence_id uuid NOT NULL
+        REFERENCES drip_sequences (sequence_id) ON DELETE CASCADE,
+    -- 등록한 날로부터 며칠 뒤에 보낼지
+    delay_days INT NOT NULL CHECK (delay_days >= 0),
+    newsletter_issue_id uuid NOT NULL
+        REFERENCES newsletter_issues (newsletter_issue_id) ON DELETE CASCADE,
+    created_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (step_id)
+);
+
+CREATE TABLE drip_enrollments (
+    sequence_id uuid NOT NULL
+        REFERENCES drip_sequences (sequence_id) ON DELETE CASCADE,
+    subscriber_id uuid NOT NULL
+        REFERENCES subscriptions (id) ON DELETE CASCADE,
+    enrolled_at timestamptz NOT NULL DEFAULT now(),
+    -- 구독 해지, 일시 중지, 리스트 탈퇴로 남은 단계를 보내지 않게 된 시각과 이유
+    stopped_at timestamptz,
+    stop_reason TEXT,
+    PRIMARY KEY (sequence_id, subscriber_id)
+);
+
+-- 발송 큐에 적재한 단계. 같은 단계를 두 번 보내지 않는다.
+CREATE TABLE drip_deliveries (
+    step_id uuid NOT NULL
+        REFERENCES drip_steps (step_id) ON DELETE CASCADE,
+    subscriber_id uuid NOT NULL
+        REFERENCES subscriptions (id) ON DELETE CASCADE,
+    enqueued_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (step_id, subscriber_id)
+);
@@ -0,0 +1,16 @@
+-- Add migration script here
+-- 20261019 추가 / 'delivery_frequency'가 'weekly'인 구독자에게 보낼 이슈를 모아두었다가 주간 다이제스트 한 통으로 보낸다.
+CREATE TABLE digest_queue (
+    newsletter_issue_id uuid NOT NULL
+        REFERENCES newsletter_issues (newsletter_issue_id) ON DELETE CASCADE,
+    subscriber_id uuid NOT NULL
+        REFERENCES subscriptions (id) ON DELETE CASCADE,
+    queued_at timestamptz NOT NULL DEFAULT now(),
+    PRIMARY KEY (newsletter_issue_id, subscriber_id)
+);
+
+-- 다음 다이제스트는 마지막 다이제스트로부터 주기가 지난 뒤에 보낸다.
+ALTER TABLE subscriptions ADD COLUMN last_digest_sent_at timestamptz;
+
+-- 이슈 보고서에 표시한다. 'recipient_count'는 이슈를 따로 받는 구독자 수로 열람률의 분모이다.
+ALTER TABLE newsletter_issues ADD COLUMN digest_recipient_count INT NOT NULL DEFAULT 0;
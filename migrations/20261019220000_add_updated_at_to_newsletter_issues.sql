@@ -0,0 +1,21 @@
+-- Add migration script here
+-- 20261019 추가 / 피드의 Last-Modified가 이슈를 숨기거나 수정한 것도 반영하도록 이슈가 바뀐 시각을 기록한다.
+-- 발송 통계(recipient_count 등)만 바뀐 경우에는 피드가 바뀌지 않으므로 갱신하지 않는다.
+ALTER TABLE newsletter_issues ADD COLUMN updated_at timestamptz NOT NULL DEFAULT now();
+UPDATE newsletter_issues SET updated_at = published_at;
+
+CREATE FUNCTION touch_newsletter_issue() RETURNS trigger AS $$
+BEGIN
+    IF (NEW.title, NEW.text_content, NEW.html_content, NEW.slug, NEW.visibility, NEW.status, NEW.published_at)
+        IS DISTINCT FROM
+        (OLD.title, OLD.text_content, OLD.html_content, OLD.slug, OLD.visibility, OLD.status, OLD.published_at)
+    THEN
+        NEW.updated_at = now();
+    END IF;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER newsletter_issues_touch_updated_at
+    BEFORE UPDATE ON newsletter_issues
+    FOR EACH ROW EXECUTE FUNCTION touch_newsletter_issue();
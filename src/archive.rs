@@ -0,0 +1,203 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use crate::email_templates::{personalize_issue, title_slug, IssueContent, SubscriberProfile};
+
+//20261019 추가 / 발행한 이슈의 웹 아카이브.
+//이슈마다 '/archive/{slug}' 주소와 공개 범위를 가지며, 이슈 이메일의 '브라우저에서 보기' 링크가 이 주소를 가리킨다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Visibility {
+    //아카이브 목록에 보이고 누구나 볼 수 있다.
+    Public,
+    //목록에 보이지 않고, 이메일에 들어 있는 서명된 링크로만 볼 수 있다.
+    Subscribers,
+    //웹에 보여주지 않는다.
+    Hidden
+}
+
+impl Visibility {
+    pub const ALL: [Visibility; 3] = [Visibility::Public, Visibility::Subscribers, Visibility::Hidden];
+
+    pub fn parse(s: &str) -> Result<Self, String> {
+        match s {
+            "public" => Ok(Visibility::Public),
+            "subscribers" => Ok(Visibility::Subscribers),
+            "hidden" => Ok(Visibility::Hidden),
+            other => Err(format!("{} is not a valid visibility.", other))
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Subscribers => "subscribers",
+            Visibility::Hidden => "hidden"
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Visibility::Public => "Public",
+            Visibility::Subscribers => "Subscribers only",
+            Visibility::Hidden => "Hidden"
+        }
+    }
+}
+
+//제목으로 만든 이름이 이미 쓰이고 있으면 '-2', '-3'...을 붙인다.
+#[tracing::instrument(skip(transaction))]
+pub async fn unique_issue_slug(
+    transaction: &mut Transaction<'_, Postgres>,
+    title: &str
+) -> Result<String, sqlx::Error> {
+    let base = match title_slug(title) {
+        slug if slug.is_empty() => "issue".to_string(),
+        slug => slug
+    };
+    let mut slug = base.clone();
+    for suffix in 2.. {
+        let taken = sqlx::query_scalar!(
+            r#"SELECT EXISTS (SELECT 1 FROM newsletter_issues WHERE slug = $1) AS "taken!""#,
+            slug
+        )
+        .fetch_one(&mut *transaction)
+        .await?;
+        if !taken {
+            break;
+        }
+        slug = format!("{}-{}", base, suffix);
+    }
+    Ok(slug)
+}
+
+pub struct ArchivedIssue {
+    pub newsletter_issue_id: Uuid,
+    pub slug: String,
+    pub title: String,
+    pub text_content: String,
+    pub html_content: String,
+    //20261019 수정 / 텍스트에서 timestamptz로 바뀌었다.
+    pub published_at: DateTime<Utc>,
+    pub visibility: String
+}
+
+impl ArchivedIssue {
+    //웹에 보여줄 제목과 HTML 본문. 구독자를 알면 이메일과 같이 개인화하고,
+    //모르면 이름 대신 'reader'를 넣고 사용자 정의 필드는 비워 둔다.
+    pub fn render(&self, profile: Option<SubscriberProfile>) -> (String, String) {
+        let profile = profile.unwrap_or(SubscriberProfile {
+            name: "reader".into(),
+            custom_fields: serde_json::json!({})
+        });
+        let content = IssueContent {
+            title: &self.title,
+            html_content: &self.html_content,
+            text_content: &self.text_content
+        };
+        match personalize_issue(&content, &profile) {
+            Ok(rendered) => (rendered.subject, rendered.html_body),
+            //개인화 이전에 발행된 이슈는 그대로 보여준다.
+            Err(_) => (self.title.clone(), self.html_content.clone())
+        }
+    }
+}
+
+//20261019 수정 / 아직 발송하지 않은 초안과 예약 이슈는 웹에 보여주지 않는다.
+#[tracing::instrument(skip(pool))]
+pub async fn get_archived_issue(
+    pool: &PgPool,
+    slug: &str
+) -> Result<Option<ArchivedIssue>, sqlx::Error> {
+    sqlx::query_as!(
+        ArchivedIssue,
+        r#"
+        SELECT newsletter_issue_id, slug, title, text_content, html_content, published_at, visibility
+        FROM newsletter_issues
+        WHERE slug = $1 AND status = 'published'
+        "#,
+        slug
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+pub struct PublicIssue {
+    pub slug: String,
+    pub title: String,
+    pub published_at: DateTime<Utc>
+}
+
+//최근에 발행한 공개 이슈부터 반환한다.
+#[tracing::instrument(skip(pool))]
+pub async fn list_public_issues(pool: &PgPool) -> Result<Vec<PublicIssue>, sqlx::Error> {
+    sqlx::query_as!(
+        PublicIssue,
+        r#"
+        SELECT slug, title, published_at
+        FROM newsletter_issues
+        WHERE visibility = 'public' AND status = 'published'
+        ORDER BY published_at DESC
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+//20261019 추가 / 피드에 넣을 최근 공개 이슈
+#[tracing::instrument(skip(pool))]
+pub async fn recent_public_issues(
+    pool: &PgPool,
+    limit: i64
+) -> Result<Vec<ArchivedIssue>, sqlx::Error> {
+    sqlx::query_as!(
+        ArchivedIssue,
+        r#"
+        SELECT newsletter_issue_id, slug, title, text_content, html_content, published_at, visibility
+        FROM newsletter_issues
+        WHERE visibility = 'public' AND status = 'published'
+        ORDER BY published_at DESC
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
+
+//20261019 추가 / 가장 최근에 이슈가 추가되거나 바뀐 시각. 피드에서 빠진 이슈의 변경도 포함한다.
+#[tracing::instrument(skip(pool))]
+pub async fn issues_last_updated(pool: &PgPool) -> Result<Option<DateTime<Utc>>, sqlx::Error> {
+    let row = sqlx::query!(r#"SELECT max(updated_at) AS last_updated FROM newsletter_issues"#)
+        .fetch_one(pool)
+        .await?;
+    Ok(row.last_updated)
+}
+
+#[tracing::instrument(skip(pool))]
+pub async fn set_visibility(
+    pool: &PgPool,
+    issue_id: Uuid,
+    visibility: Visibility
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"UPDATE newsletter_issues SET visibility = $2 WHERE newsletter_issue_id = $1"#,
+        issue_id,
+        visibility.as_str()
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Visibility;
+
+    #[test]
+    fn visibilities_round_trip() {
+        for visibility in Visibility::ALL {
+            assert_eq!(Visibility::parse(visibility.as_str()), Ok(visibility));
+        }
+        assert!(Visibility::parse("everyone").is_err());
+    }
+}
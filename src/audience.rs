@@ -0,0 +1,134 @@
+use crate::segment::Segment;
+use sqlx::{PgPool, Postgres, QueryBuilder, Row, Transaction};
+use crate::routes::DEFAULT_LIST_SLUG;
+use uuid::Uuid;
+
+//20261019 추가 / 이슈를 받을 구독자를 고르는 조건.
+//발송 큐 적재와 수신자 수 미리보기가 같은 조건을 사용하도록 한 곳에 모아둔다.
+//'query'에는 'FROM subscriptions s WHERE ...'가 이어서 추가된다.
+pub fn push_recipients(
+    query: &mut QueryBuilder<'_, Postgres>,
+    list_ids: &[Uuid],
+    segment: Option<&Segment>
+) {
+    //여러 리스트에 속한 구독자도 한 번만 선택되도록 JOIN 대신 EXISTS를 사용한다.
+    query.push(
+        " FROM subscriptions s \
+        WHERE s.status = 'confirmed' \
+        AND EXISTS (\
+            SELECT 1 FROM list_memberships m \
+            WHERE m.subscriber_id = s.id AND m.status = 'confirmed' AND m.list_id = ANY("
+    );
+    query.push_bind(list_ids.to_vec());
+    query.push("))");
+    //구독 설정 페이지에서 발송을 일시 중지한 구독자는 제외한다.
+    //20261019 수정 / 'delivery_frequency'는 발송 큐에 적재할 때 나눈다. 수신자 수 미리보기에는 둘 다 포함한다.
+    query.push(" AND (s.paused_until IS NULL OR s.paused_until <= now())");
+    //20261019 추가 / 억제 목록의 주소는 상태와 관계없이 제외한다. 'hash_email'과 같은 방식으로 정규화한다.
+    query.push(
+        " AND NOT EXISTS (\
+            SELECT 1 FROM suppressed_emails e \
+            WHERE e.email_hash = encode(sha256(convert_to(lower(trim(s.email)), 'UTF8')), 'hex')\
+        )"
+    );
+    if let Some(segment) = segment {
+        query.push(" AND (");
+        segment.push_sql(query);
+        query.push(")");
+    }
+}
+
+#[tracing::instrument(skip(pool))]
+pub async fn count_recipients(
+    pool: &PgPool,
+    list_ids: &[Uuid],
+    segment: Option<&Segment>
+) -> Result<i64, sqlx::Error> {
+    let mut query = QueryBuilder::new("SELECT COUNT(*)");
+    push_recipients(&mut query, list_ids, segment);
+    let row = query.build().fetch_one(pool).await?;
+    row.try_get(0)
+}
+
+//20261019 수정 / 발행 폼 외에 보류된 이슈의 발송과 피드 다이제스트에서도 사용하도록 옮겼다.
+//20261019 수정 / 주간 다이제스트를 선택한 구독자는 발송 큐 대신 다이제스트 큐에 적재한다.
+#[tracing::instrument(skip_all)]
+pub async fn enqueue_delivery_tasks(
+    transaction: &mut Transaction<'_, Postgres>,
+    newsletter_issue_id: Uuid,
+    //20261019 추가 / 선택된 리스트의 확인된 멤버에게만 발송한다. 여러 리스트에 속한 구독자도 한 번만 받는다.
+    list_ids: &[Uuid],
+    //20261019 추가 / 세그먼트가 있으면 그 조건을 만족하는 구독자에게만 발송한다.
+    segment: Option<&Segment>
+) -> Result<(), sqlx::Error> {
+    let mut query = QueryBuilder::new(
+        "INSERT INTO issue_delivery_queue (newsletter_issue_id, subscriber_id) SELECT "
+    );
+    query.push_bind(newsletter_issue_id);
+    //20261019 수정 / 주소는 발송 시점에 다시 읽으므로 구독자 ID만 적재한다.
+    query.push(", s.id");
+    push_recipients(&mut query, list_ids, segment);
+    query.push(" AND s.delivery_frequency <> 'weekly'");
+    let recipient_count = query.build().execute(&mut *transaction).await?.rows_affected();
+    let mut query = QueryBuilder::new(
+        "INSERT INTO digest_queue (newsletter_issue_id, subscriber_id) SELECT "
+    );
+    query.push_bind(newsletter_issue_id);
+    query.push(", s.id");
+    push_recipients(&mut query, list_ids, segment);
+    query.push(" AND s.delivery_frequency = 'weekly'");
+    let digest_recipient_count = query.build().execute(&mut *transaction).await?.rows_affected();
+    //20261019 추가 / 이슈 보고서의 열람률 분모
+    sqlx::query!(
+        r#"
+        UPDATE newsletter_issues SET recipient_count = $2, digest_recipient_count = $3
+        WHERE newsletter_issue_id = $1
+        "#,
+        newsletter_issue_id,
+        recipient_count as i32,
+        digest_recipient_count as i32
+    )
+    .execute(transaction)
+    .await?;
+    Ok(())
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn get_default_list_ids(
+    //20261019 수정 / 수신자 수 미리보기에서는 트랜잭션 없이 조회한다.
+    executor: impl sqlx::PgExecutor<'_>
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT list_id FROM lists WHERE slug = $1"#,
+        DEFAULT_LIST_SLUG
+    )
+    .fetch_optional(executor)
+    .await?;
+    Ok(row.into_iter().map(|r| r.list_id).collect())
+}
+
+//20261019 추가 / 초안이나 예약 이슈를 발행하고 기본 리스트로 발송한다.
+//이미 발행된 이슈이면 아무것도 하지 않고 'false'를 반환한다.
+#[tracing::instrument(skip(transaction))]
+pub async fn send_held_issue(
+    transaction: &mut Transaction<'_, Postgres>,
+    newsletter_issue_id: Uuid
+) -> Result<bool, sqlx::Error> {
+    let released = sqlx::query!(
+        r#"
+        UPDATE newsletter_issues
+        SET status = 'published', published_at = now(), scheduled_for = NULL
+        WHERE newsletter_issue_id = $1 AND status IN ('draft', 'scheduled')
+        "#,
+        newsletter_issue_id
+    )
+    .execute(&mut *transaction)
+    .await?
+    .rows_affected();
+    if released == 0 {
+        return Ok(false);
+    }
+    let list_ids = get_default_list_ids(&mut *transaction).await?;
+    enqueue_delivery_tasks(transaction, newsletter_issue_id, &list_ids, None).await?;
+    Ok(true)
+}
@@ -32,7 +32,7 @@ pub async fn validate_credentials(
             .to_string()
     ); 
 
-    if let Some((stored_user_id, stored_password_hash)) = get_stored_credentials(&credentials.username, &pool).await? {
+    if let Some((stored_user_id, stored_password_hash)) = get_stored_credentials(&credentials.username, pool).await? {
         user_id = Some(stored_user_id);
         expected_password_hash = stored_password_hash;
     }
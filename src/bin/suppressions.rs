@@ -0,0 +1,62 @@
+use anyhow::Context;
+use zero2prod::configuration::get_configuration;
+use zero2prod::startup::get_connection_pool;
+use zero2prod::suppression::{get_suppression, list_suppressions, remove_suppression, suppress_email, MANUAL_REASONS};
+
+//20261019 추가 / 억제 목록 관리 CLI. 애플리케이션과 같은 구성(APP_ENVIRONMENT, APP_*)을 읽는다.
+const USAGE: &str = "Usage:
+    suppressions list
+    suppressions check <email>
+    suppressions add <email> <reason>
+    suppressions remove <email>";
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let configuration = get_configuration().context("Failed to read configuration.")?;
+    let pool = get_connection_pool(&configuration.database);
+    match args.as_slice() {
+        ["list"] => {
+            for suppression in list_suppressions(&pool, i64::MAX).await? {
+                println!(
+                    "{}\t{}\t{}",
+                    suppression.created_at.to_rfc3339(),
+                    suppression.reason,
+                    suppression.email_hash
+                );
+            }
+        }
+        ["check", email] => match get_suppression(&pool, email).await? {
+            Some(suppression) => println!(
+                "{} is suppressed ({}) since {}.",
+                email,
+                suppression.reason,
+                suppression.created_at.to_rfc3339()
+            ),
+            None => println!("{} is not suppressed.", email)
+        },
+        ["add", email, reason] => {
+            if !MANUAL_REASONS.contains(reason) {
+                anyhow::bail!("{} is not a valid reason. Use one of: {}", reason, MANUAL_REASONS.join(", "));
+            }
+            if suppress_email(&pool, email, reason).await? {
+                println!("{} has been suppressed.", email);
+            } else {
+                println!("{} was already suppressed.", email);
+            }
+        }
+        ["remove", email] => {
+            if remove_suppression(&pool, email).await? {
+                println!("The suppression of {} has been lifted.", email);
+            } else {
+                println!("{} was not suppressed.", email);
+            }
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            std::process::exit(2);
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,135 @@
+use sqlx::PgPool;
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+use crate::suppression::suppress_email;
+
+//20261019 추가 / 이메일 서비스가 알려준 반송과 스팸 신고를 처리한다.
+//영구적인 반송과 스팸 신고는 즉시 구독자 상태를 바꾸고 주소를 억제한다.
+//일시적인 반송은 횟수만 세고, 한도에 도달하면 영구적인 반송과 같이 처리한다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BounceKind {
+    Hard,
+    Soft,
+    Complaint
+}
+
+impl BounceKind {
+    //Postmark의 'RecordType'과 'Type'으로 분류한다. 자동 응답처럼 주소에 문제가 없는 알림은 'None'이다.
+    pub fn from_postmark(record_type: &str, bounce_type: Option<&str>) -> Option<Self> {
+        if record_type == "SpamComplaint" {
+            return Some(BounceKind::Complaint);
+        }
+        if record_type != "Bounce" {
+            return None;
+        }
+        match bounce_type? {
+            "HardBounce" | "BadEmailAddress" | "ManuallyDeactivated" => Some(BounceKind::Hard),
+            "SpamComplaint" => Some(BounceKind::Complaint),
+            "SoftBounce" | "Transient" | "DnsError" | "InboxFull" => Some(BounceKind::Soft),
+            _ => None
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum BounceOutcome {
+    //이미 처리한 이벤트
+    Duplicate,
+    Suppressed,
+    //한도에 도달하지 않은 일시적인 반송
+    SoftBounceCounted
+}
+
+#[tracing::instrument(skip(pool, email))]
+pub async fn process_bounce(
+    pool: &PgPool,
+    provider: &str,
+    event_id: &str,
+    email: &str,
+    kind: BounceKind,
+    details: serde_json::Value,
+    soft_bounce_limit: u32
+) -> Result<BounceOutcome, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    let inserted = sqlx::query!(
+        r#"
+        INSERT INTO processed_webhook_events (provider, event_id)
+        VALUES ($1, $2)
+        ON CONFLICT (provider, event_id) DO NOTHING
+        "#,
+        provider,
+        event_id
+    )
+    .execute(&mut transaction)
+    .await?
+    .rows_affected();
+    if inserted == 0 {
+        transaction.commit().await?;
+        return Ok(BounceOutcome::Duplicate);
+    }
+    let subscriber = sqlx::query!(
+        r#"SELECT id, soft_bounce_count FROM subscriptions WHERE lower(email) = lower($1) FOR UPDATE"#,
+        email.trim()
+    )
+    .fetch_optional(&mut transaction)
+    .await?;
+    let (status, reason) = match kind {
+        BounceKind::Hard => ("bounced", "hard_bounce"),
+        BounceKind::Complaint => ("complained", "spam_complaint"),
+        BounceKind::Soft => {
+            let soft_bounce_count = match &subscriber {
+                Some(subscriber) => {
+                    sqlx::query!(
+                        r#"UPDATE subscriptions SET soft_bounce_count = soft_bounce_count + 1 WHERE id = $1"#,
+                        subscriber.id
+                    )
+                    .execute(&mut transaction)
+                    .await?;
+                    subscriber.soft_bounce_count + 1
+                }
+                //구독자가 아닌 주소는 횟수를 셀 곳이 없다.
+                None => 0
+            };
+            if (soft_bounce_count as u32) < soft_bounce_limit {
+                transaction.commit().await?;
+                return Ok(BounceOutcome::SoftBounceCounted);
+            }
+            ("bounced", "soft_bounce_limit")
+        }
+    };
+    if let Some(subscriber) = subscriber {
+        sqlx::query!(
+            r#"UPDATE subscriptions SET status = $2 WHERE id = $1"#,
+            subscriber.id,
+            status
+        )
+        .execute(&mut transaction)
+        .await?;
+        let event_type = if kind == BounceKind::Complaint { EventType::Complained } else { EventType::Bounced };
+        record_event(
+            &mut transaction,
+            subscriber.id,
+            event_type,
+            &EventContext::without_request(EventSource::System),
+            details
+        )
+        .await?;
+    }
+    suppress_email(&mut transaction, email, reason).await?;
+    transaction.commit().await?;
+    Ok(BounceOutcome::Suppressed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BounceKind;
+
+    #[test]
+    fn postmark_bounce_types_are_classified() {
+        assert_eq!(BounceKind::from_postmark("Bounce", Some("HardBounce")), Some(BounceKind::Hard));
+        assert_eq!(BounceKind::from_postmark("Bounce", Some("SoftBounce")), Some(BounceKind::Soft));
+        assert_eq!(BounceKind::from_postmark("SpamComplaint", Some("SpamComplaint")), Some(BounceKind::Complaint));
+        assert_eq!(BounceKind::from_postmark("Bounce", Some("AutoResponder")), None);
+        assert_eq!(BounceKind::from_postmark("Bounce", None), None);
+        assert_eq!(BounceKind::from_postmark("Delivery", None), None);
+    }
+}
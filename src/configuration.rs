@@ -13,7 +13,19 @@ pub struct Settings {
     //20250204 새 필드 추가
     pub email_client: EmailClientSettings,
     //20250226 새 필드 추가 / 레디스를 위한 독립적인 설정 구조체를 만들지 않음. 우선 uri 이상의 무건가 필요한지 확인 uri는 비밀번호를 포함할 수 있으므로 시크릿으로 표시한다.
-    pub redis_uri: Secret<String>
+    pub redis_uri: Secret<String>,
+    //20261019 추가 / 구독 확인 토큰의 만료 및 정리 주기 설정
+    pub subscriptions: SubscriptionSettings,
+    //20261019 추가 / 이메일 서비스(Postmark)가 보내는 웹훅의 인증과 반송 처리 설정
+    pub webhooks: WebhookSettings,
+    //20261019 추가 / 이슈 링크에 붙이는 UTM 파라미터
+    pub utm: UtmSettings,
+    //20261019 추가 / 공개 이슈의 RSS, Atom 피드
+    pub feeds: FeedSettings,
+    //20261019 추가 / 블로그 피드의 새 글로 이슈를 만드는 작업
+    pub feed_watcher: FeedWatcherSettings,
+    //20261019 추가 / 주간 다이제스트를 선택한 구독자에게 보내는 작업
+    pub digests: DigestSettings
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -24,7 +36,9 @@ pub struct ApplicationSettings {
     //20250211 추가
     pub base_url: String,
     //20250225 추가 / HMAC이 사용한 시크릿을 애플리케이션 상테에 주입
-    pub hmac_secret: Secret<String>
+    pub hmac_secret: Secret<String>,
+    //20261019 추가 / 구독 확인 이메일 등의 템플릿을 읽어올 디렉터리
+    pub email_templates_directory: String
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -49,6 +63,103 @@ pub struct EmailClientSettings {
     pub timeout_milliseconds: u64
 }
 
+//20261019 추가
+#[derive(serde::Deserialize, Clone)]
+pub struct SubscriptionSettings {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub confirmation_token_ttl_hours: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub unconfirmed_retention_days: u32,
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub import_confirmation_emails_per_minute: u32
+}
+
+//20261019 추가 / Postmark 웹훅 URL에 넣는 Basic 인증 정보
+#[derive(serde::Deserialize, Clone)]
+pub struct WebhookSettings {
+    pub username: String,
+    pub password: Secret<String>,
+    //이 횟수만큼 일시적인 반송이 쌓이면 주소를 억제한다.
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub soft_bounce_limit: u32
+}
+
+//20261019 추가 / 캠페인 이름은 이슈마다 정한다.
+#[derive(serde::Deserialize, Clone)]
+pub struct UtmSettings {
+    pub source: String,
+    pub medium: String,
+    //이 도메인(하위 도메인 포함)의 링크에는 붙이지 않는다.
+    #[serde(default)]
+    pub excluded_domains: Vec<String>
+}
+
+//20261019 추가
+#[derive(serde::Deserialize, Clone)]
+pub struct FeedSettings {
+    pub title: String,
+    pub description: String,
+    //피드에 넣는 최근 이슈 수
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub max_entries: u32
+}
+
+//20261019 추가
+#[derive(serde::Deserialize, Clone)]
+pub struct FeedWatcherSettings {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub poll_interval_seconds: u64,
+    #[serde(default)]
+    pub feeds: Vec<WatchedFeedSettings>
+}
+
+//새 글을 발견했을 때 할 일
+#[derive(serde::Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedMode {
+    //글마다 초안 이슈를 만든다. 관리자가 이슈 보고서에서 발송한다.
+    Draft,
+    //글마다 'send_delay_minutes' 뒤에 발송되는 예약 이슈를 만든다.
+    Scheduled,
+    //'digest_interval_hours'마다 그동안 발견한 글을 한 이슈로 묶어 발송한다.
+    Digest
+}
+
+//템플릿이 비어 있으면 기본 템플릿을 사용한다. 글마다 만드는 이슈에는 '{{ item.title }}', '{{ item.link }}',
+//'{{ item.summary }}', '{{ feed.title }}'를, 다이제스트에는 '{{ feed.title }}', '{{ items }}', '{{ item_count }}'를 쓸 수 있다.
+#[derive(serde::Deserialize, Clone)]
+pub struct WatchedFeedSettings {
+    pub url: String,
+    pub mode: FeedMode,
+    #[serde(default, deserialize_with = "deserialize_number_from_string")]
+    pub send_delay_minutes: u32,
+    #[serde(default = "default_digest_interval_hours", deserialize_with = "deserialize_number_from_string")]
+    pub digest_interval_hours: u32,
+    //웹 아카이브 공개 범위. 비어 있으면 공개한다.
+    #[serde(default)]
+    pub visibility: String,
+    #[serde(default)]
+    pub title_template: Option<String>,
+    #[serde(default)]
+    pub html_template: Option<String>,
+    #[serde(default)]
+    pub text_template: Option<String>
+}
+
+//20261019 추가
+#[derive(serde::Deserialize, Clone)]
+pub struct DigestSettings {
+    #[serde(deserialize_with = "deserialize_number_from_string")]
+    pub poll_interval_seconds: u64,
+    //구독자마다 마지막 다이제스트로부터 이 시간이 지나야 다음 다이제스트를 보낸다.
+    #[serde(default = "default_digest_interval_hours", deserialize_with = "deserialize_number_from_string")]
+    pub interval_hours: u32
+}
+
+fn default_digest_interval_hours() -> u32 {
+    24 * 7
+}
+
 pub fn get_configuration() -> Result<Settings, config::ConfigError> {
     let base_path = std::env::current_dir()
         .expect("Failed to determine the current directory");
@@ -139,7 +250,7 @@ impl DatabaseSettings {
         PgConnectOptions::new()
             .host(&self.host)
             .username(&self.username)
-            .password(&self.password.expose_secret())
+            .password(self.password.expose_secret())
             .port(self.port)
             .ssl_mode(ssl_mode)
     }
@@ -172,4 +283,43 @@ impl EmailClientSettings {
             timeout
         )
     }
+}
+
+//20261019 추가
+impl SubscriptionSettings {
+    pub fn confirmation_token_ttl(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.confirmation_token_ttl_hours.into())
+    }
+    pub fn unconfirmed_retention(&self) -> chrono::Duration {
+        chrono::Duration::days(self.unconfirmed_retention_days.into())
+    }
+    //가져오기 워커가 확인 이메일 사이에 쉬는 시간
+    pub fn import_email_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(60) / self.import_confirmation_emails_per_minute.max(1)
+    }
+}
+
+//20261019 추가
+impl FeedWatcherSettings {
+    pub fn poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.poll_interval_seconds)
+    }
+}
+
+impl WatchedFeedSettings {
+    pub fn send_delay(&self) -> chrono::Duration {
+        chrono::Duration::minutes(self.send_delay_minutes.into())
+    }
+    pub fn digest_interval(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.digest_interval_hours.into())
+    }
+}
+
+impl DigestSettings {
+    pub fn poll_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.poll_interval_seconds)
+    }
+    pub fn interval(&self) -> chrono::Duration {
+        chrono::Duration::hours(self.interval_hours.into())
+    }
 }
\ No newline at end of file
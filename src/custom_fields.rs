@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use serde_json::{Map, Value};
+
+//20261019 추가 / 관리자가 정의하는 구독자 사용자 정의 필드.
+//값은 subscriptions.custom_fields(JSONB)에 저장되고, 세그먼트('field.<key>')와 개인화('{{ fields.<key> }}')에 사용된다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldType {
+    Text,
+    Number,
+    Boolean,
+    Choice
+}
+
+impl FieldType {
+    pub fn parse(s: &str) -> Result<FieldType, String> {
+        match s {
+            "text" => Ok(FieldType::Text),
+            "number" => Ok(FieldType::Number),
+            "boolean" => Ok(FieldType::Boolean),
+            "choice" => Ok(FieldType::Choice),
+            other => Err(format!("{} is not a valid field type.", other))
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FieldType::Text => "text",
+            FieldType::Number => "number",
+            FieldType::Boolean => "boolean",
+            FieldType::Choice => "choice"
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldDefinition {
+    pub key: String,
+    pub label: String,
+    pub field_type: FieldType,
+    pub required: bool,
+    pub choices: Vec<String>
+}
+
+const MAX_TEXT_LENGTH: usize = 256;
+
+//필드 키는 세그먼트와 템플릿에서 그대로 쓰이므로 소문자, 숫자, '_'만 허용한다.
+pub fn parse_field_key(s: &str) -> Result<String, String> {
+    let s = s.trim();
+    let is_valid = !s.is_empty()
+        && s.len() <= 64
+        && s.starts_with(|c: char| c.is_ascii_lowercase())
+        && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if is_valid {
+        Ok(s.to_string())
+    } else {
+        Err(format!("{} is not a valid field key.", s))
+    }
+}
+
+impl FieldDefinition {
+    fn parse_value(&self, raw: &str) -> Result<Value, String> {
+        match self.field_type {
+            FieldType::Text => {
+                if raw.chars().count() > MAX_TEXT_LENGTH {
+                    return Err(format!("{} must be at most {} characters long.", self.label, MAX_TEXT_LENGTH));
+                }
+                Ok(Value::String(raw.to_string()))
+            }
+            FieldType::Number => raw
+                .parse::<f64>()
+                .ok()
+                .and_then(serde_json::Number::from_f64)
+                .map(Value::Number)
+                .ok_or_else(|| format!("{} must be a number.", self.label)),
+            FieldType::Boolean => match raw.to_lowercase().as_str() {
+                "true" | "on" | "yes" | "1" => Ok(Value::Bool(true)),
+                "false" | "off" | "no" | "0" => Ok(Value::Bool(false)),
+                _ => Err(format!("{} must be yes or no.", self.label))
+            },
+            FieldType::Choice => {
+                if self.choices.iter().any(|c| c == raw) {
+                    Ok(Value::String(raw.to_string()))
+                } else {
+                    Err(format!("{} must be one of: {}.", self.label, self.choices.join(", ")))
+                }
+            }
+        }
+    }
+}
+
+//제출된 값을 정의에 맞게 검증하고 JSONB에 저장할 객체로 변환한다.
+//정의되지 않은 키는 무시하고, 빈 값은 입력하지 않은 것으로 취급한다.
+pub fn validate_custom_fields(
+    definitions: &[FieldDefinition],
+    submitted: &HashMap<String, String>
+) -> Result<Map<String, Value>, String> {
+    let mut values = Map::new();
+    for definition in definitions {
+        let raw = submitted
+            .get(&definition.key)
+            .map(|v| v.trim())
+            .unwrap_or_default();
+        if raw.is_empty() {
+            if definition.required {
+                return Err(format!("{} is required.", definition.label));
+            }
+            continue;
+        }
+        values.insert(definition.key.clone(), definition.parse_value(raw)?);
+    }
+    Ok(values)
+}
+
+//폼과 이메일에 표시할 문자열 값
+pub fn display_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Null => String::new(),
+        other => other.to_string()
+    }
+}
+
+#[tracing::instrument(skip_all)]
+pub async fn get_field_definitions(
+    executor: impl sqlx::PgExecutor<'_>
+) -> Result<Vec<FieldDefinition>, anyhow::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT key, label, field_type, required, choices
+        FROM custom_field_definitions
+        ORDER BY created_at
+        "#
+    )
+    .fetch_all(executor)
+    .await?;
+    rows.into_iter()
+        .map(|r| {
+            Ok(FieldDefinition {
+                field_type: FieldType::parse(&r.field_type).map_err(anyhow::Error::msg)?,
+                key: r.key,
+                label: r.label,
+                required: r.required,
+                choices: r.choices
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_field_key, validate_custom_fields, FieldDefinition, FieldType};
+    use claim::{assert_err, assert_ok};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn definitions() -> Vec<FieldDefinition> {
+        vec![
+            FieldDefinition {
+                key: "company".into(),
+                label: "Company".into(),
+                field_type: FieldType::Text,
+                required: true,
+                choices: vec![]
+            },
+            FieldDefinition {
+                key: "age".into(),
+                label: "Age".into(),
+                field_type: FieldType::Number,
+                required: false,
+                choices: vec![]
+            },
+            FieldDefinition {
+                key: "language".into(),
+                label: "Preferred language".into(),
+                field_type: FieldType::Choice,
+                required: false,
+                choices: vec!["en".into(), "ko".into()]
+            }
+        ]
+    }
+
+    fn submitted(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+    }
+
+    #[test]
+    fn valid_values_are_converted_to_json() {
+        let values = validate_custom_fields(
+            &definitions(),
+            &submitted(&[("company", "Acme"), ("age", "42"), ("language", "ko"), ("unknown", "x")])
+        )
+        .unwrap();
+        assert_eq!(
+            serde_json::Value::Object(values),
+            json!({"company": "Acme", "age": 42.0, "language": "ko"})
+        );
+    }
+
+    #[test]
+    fn a_missing_required_field_is_rejected() {
+        assert_err!(validate_custom_fields(&definitions(), &submitted(&[("age", "42")])));
+    }
+
+    #[test]
+    fn empty_optional_fields_are_omitted() {
+        let values = validate_custom_fields(&definitions(), &submitted(&[("company", "Acme"), ("age", " ")])).unwrap();
+        assert!(!values.contains_key("age"));
+    }
+
+    #[test]
+    fn values_of_the_wrong_type_are_rejected() {
+        assert_err!(validate_custom_fields(&definitions(), &submitted(&[("company", "Acme"), ("age", "old")])));
+        assert_err!(validate_custom_fields(&definitions(), &submitted(&[("company", "Acme"), ("language", "fr")])));
+    }
+
+    #[test]
+    fn field_keys_must_be_identifiers() {
+        assert_ok!(parse_field_key("preferred_language"));
+        assert_err!(parse_field_key("Preferred Language"));
+        assert_err!(parse_field_key("1st"));
+    }
+}
@@ -0,0 +1,273 @@
+use crate::configuration::{DigestSettings, Settings};
+use crate::domain::SubscriberEmail;
+use crate::email_client::{EmailClient, SendEmailError};
+use crate::email_templates::RenderedEmail;
+use crate::issue_delivery_worker::{append_footer, get_issue, get_recipient, render_issue};
+use crate::signed_links::LinkSigner;
+use crate::startup::get_connection_pool;
+use crate::utm::UtmTagger;
+use chrono::{DateTime, Duration, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+//20261019 추가 / 주간 다이제스트.
+//'delivery_frequency'가 'weekly'인 구독자에게 발행된 이슈는 다이제스트 큐에 쌓이고,
+//마지막 다이제스트(처음이면 가장 오래 기다린 이슈)로부터 주기가 지나면 한 이메일로 묶어 보낸다.
+
+//이슈마다 개인화한 이메일과 웹 아카이브 링크를 발행 순서대로 받아 한 이메일로 합친다.
+pub fn compose_digest(sections: &[(RenderedEmail, Option<String>)]) -> RenderedEmail {
+    let subject = match sections {
+        [(first, _)] => format!("Weekly digest: {}", first.subject),
+        [(first, _), rest @ ..] => format!("Weekly digest: {} and {} more", first.subject, rest.len()),
+        [] => "Weekly digest".to_string()
+    };
+    let mut html_sections = Vec::new();
+    let mut text_sections = Vec::new();
+    for (email, archive_link) in sections {
+        let mut html = format!("<h2>{}</h2>\n{}", htmlescape::encode_minimal(&email.subject), email.html_body);
+        let mut text = format!("{}\n\n{}", email.subject, email.text_body);
+        if let Some(archive_link) = archive_link {
+            html.push_str(&format!(
+                r#"<p><a href="{}">View this issue in your browser</a></p>"#,
+                htmlescape::encode_minimal(archive_link)
+            ));
+            text.push_str(&format!("\n\nView this issue in your browser: {}", archive_link));
+        }
+        html_sections.push(html);
+        text_sections.push(text);
+    }
+    RenderedEmail {
+        subject,
+        html_body: html_sections.join("\n<hr>\n"),
+        text_body: text_sections.join("\n\n----------\n\n")
+    }
+}
+
+//일시 중지한 구독자의 다이제스트는 일시 중지가 끝날 때까지 보류한다.
+#[tracing::instrument(skip(pool))]
+async fn get_due_subscribers(
+    pool: &PgPool,
+    interval: Duration,
+    now: DateTime<Utc>
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"
+        SELECT s.id
+        FROM subscriptions s
+        WHERE EXISTS (SELECT 1 FROM digest_queue d WHERE d.subscriber_id = s.id)
+          AND (s.paused_until IS NULL OR s.paused_until <= $2)
+          AND COALESCE(
+                s.last_digest_sent_at,
+                (SELECT MIN(d.queued_at) FROM digest_queue d WHERE d.subscriber_id = s.id)
+              ) <= $1
+        "#,
+        now - interval,
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|r| r.id).collect())
+}
+
+//다른 작업이 같은 구독자의 다이제스트를 보내고 있으면 'false'를 반환한다.
+#[tracing::instrument(skip(pool, email_client, link_signer, utm_tagger))]
+async fn send_digest(
+    pool: &PgPool,
+    email_client: &EmailClient,
+    link_signer: &LinkSigner,
+    utm_tagger: &UtmTagger,
+    subscriber_id: Uuid,
+    now: DateTime<Utc>
+) -> Result<bool, anyhow::Error> {
+    let mut transaction = pool.begin().await?;
+    let issue_ids: Vec<Uuid> = sqlx::query!(
+        r#"
+        SELECT d.newsletter_issue_id
+        FROM digest_queue d
+        JOIN newsletter_issues i ON i.newsletter_issue_id = d.newsletter_issue_id
+        WHERE d.subscriber_id = $1
+        ORDER BY i.published_at
+        FOR UPDATE OF d
+        SKIP LOCKED
+        "#,
+        subscriber_id
+    )
+    .fetch_all(&mut transaction)
+    .await?
+    .into_iter()
+    .map(|r| r.newsletter_issue_id)
+    .collect();
+    if issue_ids.is_empty() {
+        return Ok(false);
+    }
+    //발송 워커와 마찬가지로 발송 시점의 구독 상태와 주소를 확인한다.
+    match get_recipient(pool, subscriber_id).await? {
+        Some(recipient) if recipient.status == "confirmed" => {
+            match SubscriberEmail::parse(recipient.email.clone()) {
+                Ok(email) => {
+                    let mut sections = Vec::new();
+                    for issue_id in &issue_ids {
+                        let issue = get_issue(pool, *issue_id).await?;
+                        sections.push(render_issue(
+                            pool, link_signer, utm_tagger, *issue_id, &issue, subscriber_id, &recipient
+                        ).await?);
+                    }
+                    //열람 추적은 이슈 단위이므로 다이제스트에는 추적 이미지를 넣지 않는다.
+                    let rendered = append_footer(
+                        compose_digest(&sections),
+                        &link_signer.preferences_link(subscriber_id),
+                        None
+                    );
+                    match email_client
+                        .send_email(&email, &rendered.subject, &rendered.html_body, &rendered.text_body)
+                        .await {
+                            Ok(()) => {}
+                            Err(SendEmailError::Suppressed) => {
+                                tracing::info!("Skipping a suppressed subscriber.");
+                            }
+                            Err(e) => {
+                                tracing::error!(
+                                    error.cause_chain = ?e,
+                                    error.message = %e,
+                                    "Failed to deliver a digest to a confirmed subscriber. Skipping."
+                                );
+                            }
+                        }
+                }
+                Err(e) => {
+                    tracing::error!(
+                        error.cause_chain = ?e,
+                        error.message = %e,
+                        "Skipping a confirmed subscriber. Their stored contact details are invalid"
+                    );
+                }
+            }
+        }
+        _ => {
+            //이슈가 적재된 뒤 구독을 해지했다.
+            tracing::info!("Skipping a subscriber that is no longer confirmed");
+        }
+    }
+    sqlx::query!(
+        r#"DELETE FROM digest_queue WHERE subscriber_id = $1 AND newsletter_issue_id = ANY($2)"#,
+        subscriber_id,
+        &issue_ids
+    )
+    .execute(&mut transaction)
+    .await?;
+    sqlx::query!(
+        r#"UPDATE subscriptions SET last_digest_sent_at = $2 WHERE id = $1"#,
+        subscriber_id,
+        now
+    )
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(true)
+}
+
+//한 구독자의 실패가 다른 구독자의 다이제스트를 막지 않는다. 보낸 다이제스트 수를 반환한다.
+#[tracing::instrument(name = "Send due digests", skip(pool, email_client, link_signer, utm_tagger))]
+pub async fn send_due_digests(
+    pool: &PgPool,
+    email_client: &EmailClient,
+    link_signer: &LinkSigner,
+    utm_tagger: &UtmTagger,
+    interval: Duration,
+    now: DateTime<Utc>
+) -> Result<u64, anyhow::Error> {
+    let mut sent = 0;
+    for subscriber_id in get_due_subscribers(pool, interval, now).await? {
+        match send_digest(pool, email_client, link_signer, utm_tagger, subscriber_id, now).await {
+            Ok(true) => sent += 1,
+            Ok(false) => {}
+            Err(e) => {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    %subscriber_id,
+                    "Failed to send a digest"
+                );
+            }
+        }
+    }
+    Ok(sent)
+}
+
+async fn digest_loop(
+    pool: PgPool,
+    email_client: EmailClient,
+    link_signer: LinkSigner,
+    utm_tagger: UtmTagger,
+    settings: DigestSettings
+) -> Result<(), anyhow::Error> {
+    loop {
+        if let Err(e) = send_due_digests(
+            &pool, &email_client, &link_signer, &utm_tagger, settings.interval(), Utc::now()
+        ).await {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to send digests"
+            );
+        }
+        tokio::time::sleep(settings.poll_interval()).await;
+    }
+}
+
+pub async fn run_digest_sender_until_stopped(
+    configuration: Settings
+) -> Result<(), anyhow::Error> {
+    let connection_pool = get_connection_pool(&configuration.database);
+    let email_client = configuration.email_client.client().with_suppression_list(connection_pool.clone());
+    let utm_tagger = UtmTagger::new(&configuration.utm, &configuration.application.base_url);
+    let link_signer = LinkSigner::new(
+        configuration.application.base_url,
+        configuration.application.hmac_secret
+    );
+    digest_loop(connection_pool, email_client, link_signer, utm_tagger, configuration.digests).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::compose_digest;
+    use crate::email_templates::RenderedEmail;
+
+    fn email(title: &str) -> RenderedEmail {
+        RenderedEmail {
+            subject: title.to_string(),
+            html_body: format!("<p>{} body</p>", title),
+            text_body: format!("{} body", title)
+        }
+    }
+
+    #[test]
+    fn a_single_issue_digest_is_titled_after_the_issue() {
+        let digest = compose_digest(&[(email("Fish & chips"), None)]);
+        assert_eq!(digest.subject, "Weekly digest: Fish & chips");
+        assert_eq!(digest.html_body, "<h2>Fish &amp; chips</h2>\n<p>Fish & chips body</p>");
+        assert_eq!(digest.text_body, "Fish & chips\n\nFish & chips body");
+    }
+
+    #[test]
+    fn issues_are_combined_in_order_with_their_archive_links() {
+        let digest = compose_digest(&[
+            (email("First"), Some("https://example.com/archive/first".to_string())),
+            (email("Second"), None),
+            (email("Third"), None)
+        ]);
+        assert_eq!(digest.subject, "Weekly digest: First and 2 more");
+        assert_eq!(
+            digest.html_body,
+            "<h2>First</h2>\n<p>First body</p>\
+            <p><a href=\"https://example.com/archive/first\">View this issue in your browser</a></p>\n<hr>\n\
+            <h2>Second</h2>\n<p>Second body</p>\n<hr>\n\
+            <h2>Third</h2>\n<p>Third body</p>"
+        );
+        assert_eq!(
+            digest.text_body,
+            "First\n\nFirst body\n\nView this issue in your browser: https://example.com/archive/first\
+            \n\n----------\n\nSecond\n\nSecond body\n\n----------\n\nThird\n\nThird body"
+        );
+    }
+}
@@ -0,0 +1,52 @@
+//20261019 추가 / 구독 폼과 URL에서 리스트를 가리키는 식별자. 소문자, 숫자, '-'만 허용한다.
+#[derive(Debug)]
+pub struct ListSlug(String);
+
+impl ListSlug {
+    pub fn parse(s: String) -> Result<ListSlug, String> {
+        let s = s.trim().to_string();
+        let is_valid = !s.is_empty()
+            && s.len() <= 64
+            && !s.starts_with('-')
+            && !s.ends_with('-')
+            && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+        if is_valid {
+            Ok(Self(s))
+        } else {
+            Err(format!("{} is not a valid list slug.", s))
+        }
+    }
+}
+
+impl AsRef<str> for ListSlug {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ListSlug;
+    use claim::{assert_err, assert_ok};
+
+    #[test]
+    fn a_lowercase_slug_with_dashes_is_valid() {
+        assert_ok!(ListSlug::parse("weekly-digest-2".to_string()));
+    }
+
+    #[test]
+    fn an_empty_slug_is_rejected() {
+        assert_err!(ListSlug::parse(" ".to_string()));
+    }
+
+    #[test]
+    fn uppercase_letters_and_spaces_are_rejected() {
+        assert_err!(ListSlug::parse("Weekly".to_string()));
+        assert_err!(ListSlug::parse("weekly digest".to_string()));
+    }
+
+    #[test]
+    fn a_slug_starting_with_a_dash_is_rejected() {
+        assert_err!(ListSlug::parse("-weekly".to_string()));
+    }
+}
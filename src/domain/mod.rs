@@ -1,7 +1,12 @@
 mod subscriber_name;
 mod subscriber_email;
 mod new_subscriber;
+//20261019 추가
+mod list_slug;
+mod subscriber_tag;
 
 pub use subscriber_name::SubscriberName;
 pub use new_subscriber::NewSubscriber;
-pub use subscriber_email::SubscriberEmail;
\ No newline at end of file
+pub use subscriber_email::SubscriberEmail;
+pub use list_slug::ListSlug;
+pub use subscriber_tag::SubscriberTag;
\ No newline at end of file
@@ -0,0 +1,44 @@
+//20261019 추가 / 구독자에게 붙이는 태그. 소문자, 숫자, '-', '_'만 허용한다.
+#[derive(Debug)]
+pub struct SubscriberTag(String);
+
+impl SubscriberTag {
+    pub fn parse(s: String) -> Result<SubscriberTag, String> {
+        let s = s.trim().to_string();
+        let is_valid = !s.is_empty()
+            && s.len() <= 64
+            && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if is_valid {
+            Ok(Self(s))
+        } else {
+            Err(format!("{} is not a valid tag.", s))
+        }
+    }
+}
+
+impl AsRef<str> for SubscriberTag {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SubscriberTag;
+    use claim::{assert_err, assert_ok};
+
+    #[test]
+    fn a_lowercase_tag_with_underscores_is_valid() {
+        assert_ok!(SubscriberTag::parse("early_adopter-2".to_string()));
+    }
+
+    #[test]
+    fn an_empty_tag_is_rejected() {
+        assert_err!(SubscriberTag::parse("".to_string()));
+    }
+
+    #[test]
+    fn spaces_are_rejected() {
+        assert_err!(SubscriberTag::parse("beta tester".to_string()));
+    }
+}
@@ -0,0 +1,199 @@
+use crate::configuration::Settings;
+use crate::startup::get_connection_pool;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::time::Duration;
+use uuid::Uuid;
+
+//20261019 추가 / 환영 이메일 시퀀스.
+//구독을 확인하면 그 리스트의 활성 시퀀스에 등록되고, 스케줄러가 때가 된 단계를 발송 큐에 넣으면 발송 워커가 보낸다.
+//다시 확인해도 처음부터 다시 시작하지 않는다.
+#[tracing::instrument(skip(transaction))]
+pub async fn enroll_subscriber(
+    transaction: &mut Transaction<'_, Postgres>,
+    subscriber_id: Uuid
+) -> Result<u64, sqlx::Error> {
+    let enrolled = sqlx::query!(
+        r#"
+        INSERT INTO drip_enrollments (sequence_id, subscriber_id)
+        SELECT q.sequence_id, $1
+        FROM drip_sequences q
+        WHERE q.active
+          AND EXISTS (
+            SELECT 1 FROM list_memberships m
+            WHERE m.subscriber_id = $1 AND m.list_id = q.list_id AND m.status = 'confirmed'
+          )
+        ON CONFLICT (sequence_id, subscriber_id) DO NOTHING
+        "#,
+        subscriber_id
+    )
+    .execute(transaction)
+    .await?
+    .rows_affected();
+    Ok(enrolled)
+}
+
+//남은 단계가 있는 등록 중에서 구독을 해지했거나, 일시 중지했거나, 시퀀스의 리스트에서 빠진 구독자를 멈춘다.
+//멈춘 시퀀스는 다시 구독하거나 일시 중지가 끝나도 이어서 보내지 않는다.
+#[tracing::instrument(skip(pool))]
+pub async fn stop_ineligible_enrollments(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let stopped = sqlx::query!(
+        r#"
+        UPDATE drip_enrollments e
+        SET stopped_at = now(),
+            stop_reason = CASE
+                WHEN s.status <> 'confirmed' THEN 'unsubscribed'
+                WHEN s.paused_until > now() THEN 'paused'
+                ELSE 'left_list'
+            END
+        FROM subscriptions s, drip_sequences q
+        WHERE e.subscriber_id = s.id
+          AND e.sequence_id = q.sequence_id
+          AND e.stopped_at IS NULL
+          AND (
+            s.status <> 'confirmed'
+            OR s.paused_until > now()
+            OR NOT EXISTS (
+                SELECT 1 FROM list_memberships m
+                WHERE m.subscriber_id = s.id AND m.list_id = q.list_id AND m.status = 'confirmed'
+            )
+          )
+          AND EXISTS (
+            SELECT 1 FROM drip_steps st
+            WHERE st.sequence_id = e.sequence_id
+              AND NOT EXISTS (
+                SELECT 1 FROM drip_deliveries d
+                WHERE d.step_id = st.step_id AND d.subscriber_id = e.subscriber_id
+              )
+          )
+        "#
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+    Ok(stopped)
+}
+
+//20261019 추가 / 시퀀스 단계의 이슈이면 구독자가 아직 시퀀스의 리스트에 속해 있는지 확인한다.
+//시퀀스 단계가 아닌 이슈는 항상 'true'다. 발송 워커가 큐에 적재된 뒤의 변경을 반영하기 위해 사용한다.
+#[tracing::instrument(skip(pool))]
+pub async fn is_in_sequence_list(
+    pool: &PgPool,
+    newsletter_issue_id: Uuid,
+    subscriber_id: Uuid
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT NOT EXISTS (
+            SELECT 1 FROM drip_steps st
+            JOIN drip_sequences q ON q.sequence_id = st.sequence_id
+            WHERE st.newsletter_issue_id = $1
+              AND NOT EXISTS (
+                SELECT 1 FROM list_memberships m
+                WHERE m.subscriber_id = $2 AND m.list_id = q.list_id AND m.status = 'confirmed'
+              )
+        ) AS "in_list!"
+        "#,
+        newsletter_issue_id,
+        subscriber_id
+    )
+    .fetch_one(pool)
+    .await?;
+    Ok(row.in_list)
+}
+
+//등록한 날로부터 단계의 지연 일수가 지난 단계를 발송 큐에 넣는다.
+//나중에 추가한 단계는 그 날짜에 아직 이르지 않은 구독자에게만 보낸다. 이미 지난 구독자에게 한꺼번에 보내지 않는다.
+#[tracing::instrument(skip(pool))]
+pub async fn enqueue_due_steps(pool: &PgPool, now: DateTime<Utc>) -> Result<u64, sqlx::Error> {
+    let mut transaction = pool.begin().await?;
+    let due = sqlx::query!(
+        r#"
+        SELECT st.step_id, st.newsletter_issue_id, e.subscriber_id
+        FROM drip_enrollments e
+        JOIN drip_sequences q ON q.sequence_id = e.sequence_id
+        JOIN drip_steps st ON st.sequence_id = e.sequence_id
+        WHERE q.active
+          AND e.stopped_at IS NULL
+          AND e.enrolled_at + make_interval(days => st.delay_days) <= $1
+          AND e.enrolled_at + make_interval(days => st.delay_days) >= st.created_at
+          AND NOT EXISTS (
+            SELECT 1 FROM drip_deliveries d
+            WHERE d.step_id = st.step_id AND d.subscriber_id = e.subscriber_id
+          )
+        ORDER BY e.enrolled_at, st.delay_days
+        "#,
+        now
+    )
+    .fetch_all(&mut transaction)
+    .await?;
+    let mut enqueued = 0;
+    for step in due {
+        //다른 스케줄러가 먼저 넣었으면 건너뛴다.
+        let recorded = sqlx::query!(
+            r#"
+            INSERT INTO drip_deliveries (step_id, subscriber_id) VALUES ($1, $2)
+            ON CONFLICT (step_id, subscriber_id) DO NOTHING
+            "#,
+            step.step_id,
+            step.subscriber_id
+        )
+        .execute(&mut transaction)
+        .await?
+        .rows_affected();
+        if recorded == 0 {
+            continue;
+        }
+        sqlx::query!(
+            r#"
+            INSERT INTO issue_delivery_queue (newsletter_issue_id, subscriber_id) VALUES ($1, $2)
+            ON CONFLICT (newsletter_issue_id, subscriber_id) DO NOTHING
+            "#,
+            step.newsletter_issue_id,
+            step.subscriber_id
+        )
+        .execute(&mut transaction)
+        .await?;
+        //이슈 보고서의 열람률 분모
+        sqlx::query!(
+            r#"UPDATE newsletter_issues SET recipient_count = recipient_count + 1 WHERE newsletter_issue_id = $1"#,
+            step.newsletter_issue_id
+        )
+        .execute(&mut transaction)
+        .await?;
+        enqueued += 1;
+    }
+    transaction.commit().await?;
+    Ok(enqueued)
+}
+
+//먼저 멈출 등록을 정리해야 일시 중지한 구독자에게 단계가 발송되지 않는다.
+#[tracing::instrument(name = "Run the drip scheduler", skip(pool))]
+pub async fn run_drip_scheduler_once(pool: &PgPool, now: DateTime<Utc>) -> Result<(), anyhow::Error> {
+    let stopped = stop_ineligible_enrollments(pool).await?;
+    let enqueued = enqueue_due_steps(pool, now).await?;
+    if stopped > 0 || enqueued > 0 {
+        tracing::info!(stopped, enqueued, "Processed drip sequences");
+    }
+    Ok(())
+}
+
+async fn scheduler_loop(pool: PgPool) -> Result<(), anyhow::Error> {
+    loop {
+        if let Err(e) = run_drip_scheduler_once(&pool, Utc::now()).await {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to process drip sequences"
+            );
+        }
+        tokio::time::sleep(Duration::from_secs(60)).await;
+    }
+}
+
+pub async fn run_drip_scheduler_until_stopped(
+    configuration: Settings
+) -> Result<(), anyhow::Error> {
+    let connection_pool = get_connection_pool(&configuration.database);
+    scheduler_loop(connection_pool).await
+}
@@ -1,13 +1,28 @@
 use crate::domain::SubscriberEmail;
+use crate::suppression::is_suppressed;
 use reqwest::Client;
 use secrecy::{ExposeSecret, Secret};
+use sqlx::PgPool;
 
 pub struct EmailClient {
     http_client: Client,
     base_url: String,
     sender: SubscriberEmail,
     //우발적인 로깅을 원치 않는다.
-    authorization_token: Secret<String>
+    authorization_token: Secret<String>,
+    //20261019 추가 / 설정되어 있으면 발송 직전에 억제 목록을 확인한다.
+    suppression_list: Option<PgPool>
+}
+
+//20261019 추가
+#[derive(thiserror::Error, Debug)]
+pub enum SendEmailError {
+    #[error("The recipient is on the suppression list.")]
+    Suppressed,
+    #[error("Failed to check the suppression list.")]
+    SuppressionCheckFailed(#[source] sqlx::Error),
+    #[error(transparent)]
+    RequestFailed(#[from] reqwest::Error)
 }
 
 impl EmailClient {
@@ -26,24 +41,39 @@ impl EmailClient {
             http_client, 
             base_url, 
             sender,
-            authorization_token
+            authorization_token,
+            suppression_list: None
         }
     }
 
+    //20261019 추가 / 모든 발송 경로가 억제 목록을 지키도록 발송 직전에 한 곳에서 확인한다.
+    pub fn with_suppression_list(mut self, pool: PgPool) -> Self {
+        self.suppression_list = Some(pool);
+        self
+    }
+
     pub async fn send_email(
         &self,
         recipient: &SubscriberEmail,
         subject: &str,
         html_content: &str,
         text_content: &str
-    ) -> Result<(), reqwest::Error> {
+    ) -> Result<(), SendEmailError> {
+        if let Some(pool) = &self.suppression_list {
+            if is_suppressed(pool, recipient.as_ref())
+                .await
+                .map_err(SendEmailError::SuppressionCheckFailed)?
+            {
+                return Err(SendEmailError::Suppressed);
+            }
+        }
         //'base_url'의 타입을 'String'에서 'reqwest::Url'로 변경하면 'reqwest::Url::join'을 사용하면 더 나은 구현을 할 수 있다 (이 부분은 나의 연습)
         let url = format!("{}/email", self.base_url);
         //요청 바디를 구조체로 인코딩 가능 -> 202502025 .to_owned()를 더 이상 사용하지 않음 (클론된 문자열을 저장하기 위해 많은 신규 메모리 할당은 낭비)
         let request_body = SendEmailRequest {
             from: self.sender.as_ref(),
             to: recipient.as_ref(),
-            subject: subject,
+            subject,
             html_body: html_content,
             text_body: text_content
         };
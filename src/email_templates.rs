@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+//20261019 추가 / 템플릿 디렉터리에서 읽어오는 이메일 템플릿.
+//'{{ 이름 }}' 형태의 플레이스홀더를 값으로 치환하는 것 외의 기능은 제공하지 않는다.
+#[derive(thiserror::Error, Debug)]
+pub enum TemplateError {
+    #[error("Failed to read the template file {0}")]
+    Io(String, #[source] std::io::Error),
+    #[error("{0}: unclosed placeholder starting at byte {1}")]
+    UnclosedPlaceholder(String, usize),
+    #[error("{0}: unknown placeholder '{{{{{1}}}}}'")]
+    UnknownPlaceholder(String, String),
+    #[error("{0}: the template must contain the '{{{{{1}}}}}' placeholder")]
+    MissingPlaceholder(String, String)
+}
+
+#[derive(Debug, Clone)]
+enum Segment {
+    Literal(String),
+    Placeholder(String)
+}
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    segments: Vec<Segment>
+}
+
+impl Template {
+    //'name'은 오류 메시지에서 어떤 템플릿이 잘못되었는지 알려주기 위해서만 사용한다.
+    pub fn parse(name: &str, source: &str, allowed_placeholders: &[&str]) -> Result<Self, TemplateError> {
+        Self::parse_with(name, source, |placeholder| allowed_placeholders.contains(&placeholder))
+    }
+
+    //20261019 추가 / 허용할 플레이스홀더를 목록 대신 함수로 판단한다. ('fields.<key>'처럼 이름이 정해져 있지 않은 경우)
+    pub fn parse_with(
+        name: &str,
+        source: &str,
+        is_allowed: impl Fn(&str) -> bool
+    ) -> Result<Self, TemplateError> {
+        let mut segments = Vec::new();
+        let mut rest = source;
+        let mut offset = 0;
+        while let Some(start) = rest.find("{{") {
+            if start > 0 {
+                segments.push(Segment::Literal(rest[..start].to_string()));
+            }
+            let end = rest[start..]
+                .find("}}")
+                .ok_or_else(|| TemplateError::UnclosedPlaceholder(name.into(), offset + start))?;
+            let placeholder = rest[start + 2..start + end].trim();
+            if !is_allowed(placeholder) {
+                return Err(TemplateError::UnknownPlaceholder(name.into(), placeholder.into()));
+            }
+            segments.push(Segment::Placeholder(placeholder.to_string()));
+            offset += start + end + 2;
+            rest = &rest[start + end + 2..];
+        }
+        if !rest.is_empty() {
+            segments.push(Segment::Literal(rest.to_string()));
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn contains(&self, placeholder: &str) -> bool {
+        self.segments
+            .iter()
+            .any(|s| matches!(s, Segment::Placeholder(p) if p == placeholder))
+    }
+
+    //플레이스홀더를 값으로 치환한다. 'escape'는 HTML 템플릿에서 값을 이스케이프할 때 사용한다.
+    pub fn render(&self, values: &HashMap<&str, String>, escape: fn(&str) -> String) -> String {
+        let mut rendered = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => rendered.push_str(literal),
+                Segment::Placeholder(placeholder) => {
+                    let value = values.get(placeholder.as_str()).map(String::as_str).unwrap_or_default();
+                    rendered.push_str(&escape(value));
+                }
+            }
+        }
+        rendered
+    }
+}
+
+fn no_escape(s: &str) -> String {
+    s.to_string()
+}
+
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html_body: String,
+    pub text_body: String
+}
+
+//구독 확인 이메일 템플릿. 애플리케이션 시작 시 로드하고 검증해서, 잘못된 템플릿이면 바로 실패한다.
+#[derive(Debug, Clone)]
+pub struct ConfirmationEmailTemplate {
+    subject: Template,
+    html_body: Template,
+    text_body: Template
+}
+
+impl ConfirmationEmailTemplate {
+    const PLACEHOLDERS: &'static [&'static str] = &["confirmation_link", "subscriber_name"];
+
+    pub fn parse(subject: &str, html_body: &str, text_body: &str) -> Result<Self, TemplateError> {
+        let subject = Template::parse("subject.txt", subject, Self::PLACEHOLDERS)?;
+        let html_body = Template::parse("body.html", html_body, Self::PLACEHOLDERS)?;
+        let text_body = Template::parse("body.txt", text_body, Self::PLACEHOLDERS)?;
+        //확인 링크가 없는 이메일은 구독자가 구독을 확인할 방법이 없다.
+        for (name, template) in [("body.html", &html_body), ("body.txt", &text_body)] {
+            if !template.contains("confirmation_link") {
+                return Err(TemplateError::MissingPlaceholder(name.into(), "confirmation_link".into()));
+            }
+        }
+        Ok(Self { subject, html_body, text_body })
+    }
+
+    //'directory' 안의 subject.txt, body.html, body.txt를 읽는다.
+    pub fn load(directory: &Path) -> Result<Self, TemplateError> {
+        let read = |file_name: &str| {
+            let path = directory.join(file_name);
+            std::fs::read_to_string(&path)
+                .map_err(|e| TemplateError::Io(path.display().to_string(), e))
+        };
+        Self::parse(
+            read("subject.txt")?.trim(),
+            &read("body.html")?,
+            &read("body.txt")?
+        )
+    }
+
+    pub fn render(&self, confirmation_link: &str, subscriber_name: &str) -> RenderedEmail {
+        let values = HashMap::from([
+            ("confirmation_link", confirmation_link.to_string()),
+            ("subscriber_name", subscriber_name.to_string())
+        ]);
+        RenderedEmail {
+            subject: self.subject.render(&values, no_escape),
+            html_body: self.html_body.render(&values, htmlescape::encode_minimal),
+            text_body: self.text_body.render(&values, no_escape)
+        }
+    }
+}
+
+//20261019 추가 / 이메일 변경 확인 이메일. 구독 확인 이메일과 같은 플레이스홀더를 사용한다.
+#[derive(Debug, Clone)]
+pub struct EmailChangeTemplate(pub ConfirmationEmailTemplate);
+
+//20261019 추가 / 뉴스레터 이슈 개인화.
+//이슈의 제목과 본문에는 '{{ subscriber_name }}'과 '{{ fields.<key> }}' 플레이스홀더를 쓸 수 있다.
+pub struct SubscriberProfile {
+    pub name: String,
+    pub custom_fields: serde_json::Value
+}
+
+pub struct IssueContent<'a> {
+    pub title: &'a str,
+    pub html_content: &'a str,
+    pub text_content: &'a str
+}
+
+fn is_issue_placeholder(placeholder: &str, is_known_field: impl Fn(&str) -> bool) -> bool {
+    placeholder == "subscriber_name"
+        || placeholder.strip_prefix("fields.").map(is_known_field).unwrap_or(false)
+}
+
+//발행 시점에 정의되지 않은 필드를 참조하는 이슈를 거부한다.
+pub fn validate_issue_content(content: &IssueContent, field_keys: &[String]) -> Result<(), TemplateError> {
+    let is_allowed = |p: &str| is_issue_placeholder(p, |key| field_keys.iter().any(|k| k == key));
+    Template::parse_with("title", content.title, is_allowed)?;
+    Template::parse_with("html_content", content.html_content, is_allowed)?;
+    Template::parse_with("text_content", content.text_content, is_allowed)?;
+    Ok(())
+}
+
+//발송 시점에는 필드 정의가 삭제되었을 수 있으므로 모든 'fields.*'를 허용하고, 값이 없으면 빈 문자열로 치환한다.
+pub fn personalize_issue(content: &IssueContent, profile: &SubscriberProfile) -> Result<RenderedEmail, TemplateError> {
+    let is_allowed = |p: &str| is_issue_placeholder(p, |_| true);
+    let field_values: Vec<(String, String)> = profile
+        .custom_fields
+        .as_object()
+        .map(|fields| {
+            fields
+                .iter()
+                .map(|(key, value)| (format!("fields.{}", key), crate::custom_fields::display_value(value)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let mut values: HashMap<&str, String> = field_values
+        .iter()
+        .map(|(key, value)| (key.as_str(), value.clone()))
+        .collect();
+    values.insert("subscriber_name", profile.name.clone());
+    Ok(RenderedEmail {
+        subject: Template::parse_with("title", content.title, is_allowed)?.render(&values, no_escape),
+        html_body: Template::parse_with("html_content", content.html_content, is_allowed)?
+            .render(&values, htmlescape::encode_minimal),
+        text_body: Template::parse_with("text_content", content.text_content, is_allowed)?.render(&values, no_escape)
+    })
+}
+
+//20261019 추가 / 이슈 제목으로 만든 URL용 이름. 개인화 플레이스홀더는 제외하고, 글자와 숫자 외에는 '-'로 바꾼다.
+//제목에 글자나 숫자가 없으면 빈 문자열이다.
+pub fn title_slug(title: &str) -> String {
+    let mut text = String::new();
+    let mut rest = title;
+    while let Some(start) = rest.find("{{") {
+        text.push_str(&rest[..start]);
+        rest = match rest[start..].find("}}") {
+            Some(end) => &rest[start + end + 2..],
+            None => ""
+        };
+    }
+    text.push_str(rest);
+    let mut slug = String::new();
+    for c in text.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    slug.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{personalize_issue, validate_issue_content, ConfirmationEmailTemplate, IssueContent, SubscriberProfile};
+    use claim::{assert_err, assert_ok};
+
+    #[test]
+    fn a_template_without_the_confirmation_link_is_rejected() {
+        let template = ConfirmationEmailTemplate::parse(
+            "Welcome!",
+            "<p>Hello {{ subscriber_name }}</p>",
+            "Visit {{confirmation_link}}"
+        );
+        assert_err!(template);
+    }
+
+    #[test]
+    fn an_unknown_placeholder_is_rejected() {
+        let template = ConfirmationEmailTemplate::parse(
+            "Welcome {{ subscriber_email }}!",
+            "<a href=\"{{confirmation_link}}\">here</a>",
+            "Visit {{confirmation_link}}"
+        );
+        assert_err!(template);
+    }
+
+    #[test]
+    fn an_unclosed_placeholder_is_rejected() {
+        let template = ConfirmationEmailTemplate::parse(
+            "Welcome!",
+            "<a href=\"{{confirmation_link}}\">here</a> {{ subscriber_name",
+            "Visit {{confirmation_link}}"
+        );
+        assert_err!(template);
+    }
+
+    #[test]
+    fn the_bundled_templates_are_valid() {
+        assert_ok!(ConfirmationEmailTemplate::load(
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("templates/confirmation_email")
+        ));
+    }
+
+    #[test]
+    fn the_bundled_email_change_templates_are_valid() {
+        assert_ok!(ConfirmationEmailTemplate::load(
+            &std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("templates/email_change")
+        ));
+    }
+
+    #[test]
+    fn placeholders_are_rendered_and_html_escaped() {
+        let template = ConfirmationEmailTemplate::parse(
+            "Welcome {{subscriber_name}}!",
+            "<p>Hi {{ subscriber_name }}</p><a href=\"{{confirmation_link}}\">here</a>",
+            "Hi {{ subscriber_name }}, visit {{confirmation_link}}"
+        )
+        .unwrap();
+
+        let email = template.render("http://127.0.0.1/confirm?token=abc", "Tom & Jerry");
+
+        assert_eq!(email.subject, "Welcome Tom & Jerry!");
+        assert_eq!(
+            email.html_body,
+            "<p>Hi Tom &amp; Jerry</p><a href=\"http://127.0.0.1/confirm?token=abc\">here</a>"
+        );
+        assert_eq!(email.text_body, "Hi Tom & Jerry, visit http://127.0.0.1/confirm?token=abc");
+    }
+
+    #[test]
+    fn issues_can_only_reference_defined_fields() {
+        let content = IssueContent {
+            title: "Hi {{ subscriber_name }}",
+            html_content: "<p>{{ fields.company }}</p>",
+            text_content: "{{ fields.compnay }}"
+        };
+        assert_err!(validate_issue_content(&content, &["company".to_string()]));
+    }
+
+    #[test]
+    fn issues_are_personalized_with_the_subscriber_profile() {
+        let content = IssueContent {
+            title: "News for {{ subscriber_name }}",
+            html_content: "<p>Hello {{ fields.company }}, aged {{ fields.age }}{{ fields.country }}</p>",
+            text_content: "Hello {{ fields.company }}"
+        };
+        let profile = SubscriberProfile {
+            name: "Ursula".into(),
+            custom_fields: serde_json::json!({"company": "A & B", "age": 42})
+        };
+
+        let email = personalize_issue(&content, &profile).unwrap();
+
+        assert_eq!(email.subject, "News for Ursula");
+        assert_eq!(email.html_body, "<p>Hello A &amp; B, aged 42</p>");
+        assert_eq!(email.text_body, "Hello A & B");
+    }
+}
@@ -0,0 +1,570 @@
+use crate::archive::{unique_issue_slug, Visibility};
+use crate::audience::{enqueue_delivery_tasks, get_default_list_ids, send_held_issue};
+use crate::configuration::{FeedMode, FeedWatcherSettings, Settings, WatchedFeedSettings};
+use crate::email_templates::{Template, TemplateError};
+use crate::startup::get_connection_pool;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+use std::fmt::Write;
+use uuid::Uuid;
+
+//20261019 추가 / 블로그의 RSS, Atom 피드를 주기적으로 읽어 새 글로 이슈를 만든다.
+//처음 읽은 피드에 이미 있던 글은 기준선으로 기록만 하므로, 감시를 시작하기 전의 글은 발송되지 않는다.
+pub struct FeedItem {
+    pub id: String,
+    pub title: String,
+    pub link: String,
+    //HTML 태그를 제거한 요약
+    pub summary: String
+}
+
+pub struct ParsedFeed {
+    pub title: String,
+    //피드에 나온 순서 (보통 최근 글부터)
+    pub items: Vec<FeedItem>
+}
+
+pub fn parse_feed(body: &[u8]) -> Result<ParsedFeed, anyhow::Error> {
+    let feed = feed_rs::parser::parse(body).context("Failed to parse the feed")?;
+    let items = feed
+        .entries
+        .into_iter()
+        .map(|entry| {
+            let summary = entry
+                .summary
+                .map(|summary| summary.content)
+                .or_else(|| entry.content.and_then(|content| content.body))
+                .unwrap_or_default();
+            FeedItem {
+                id: entry.id,
+                title: entry.title.map(|title| title.content.trim().to_string()).unwrap_or_default(),
+                //20261019 수정 / 'javascript:' 같은 링크가 이슈의 href에 들어가지 않도록 http, https 링크만 사용한다.
+                link: entry.links.into_iter().map(|link| link.href).find(|href| is_web_link(href)).unwrap_or_default(),
+                summary: plain_text(&summary)
+            }
+        })
+        .collect();
+    Ok(ParsedFeed {
+        title: feed.title.map(|title| title.content.trim().to_string()).unwrap_or_default(),
+        items
+    })
+}
+
+fn is_web_link(href: &str) -> bool {
+    reqwest::Url::parse(href).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+}
+
+//요약은 대부분 HTML이다. 이메일에 블로그의 마크업을 그대로 넣지 않도록 태그를 제거하고 공백을 정리한다.
+fn plain_text(html: &str) -> String {
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    let text = htmlescape::decode_html(&text).unwrap_or(text);
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+//20261019 추가 / 피드 응답을 메모리로 읽는 한도. CSV 가져오기의 업로드 한도와 같다.
+const MAX_FEED_SIZE: usize = 32 * 1024 * 1024;
+
+const ITEM_PLACEHOLDERS: &[&str] = &["item.title", "item.link", "item.summary", "feed.title"];
+const DIGEST_PLACEHOLDERS: &[&str] = &["feed.title", "items", "item_count"];
+
+const ITEM_TITLE: &str = "{{ item.title }}";
+const ITEM_HTML: &str = r#"<h1>{{ item.title }}</h1>
+<p>{{ item.summary }}</p>
+<p><a href="{{ item.link }}">Read more</a></p>"#;
+const ITEM_TEXT: &str = "{{ item.title }}\n\n{{ item.summary }}\n\nRead more: {{ item.link }}";
+const DIGEST_TITLE: &str = "New from {{ feed.title }}";
+const DIGEST_HTML: &str = "<h1>New from {{ feed.title }}</h1>\n{{ items }}";
+const DIGEST_TEXT: &str = "New from {{ feed.title }}\n\n{{ items }}";
+
+//'{{ items }}'는 이미 이스케이프한 HTML 목록이므로 값을 미리 이스케이프하고 그대로 치환한다.
+fn as_is(s: &str) -> String {
+    s.to_string()
+}
+
+struct RenderedIssue {
+    title: String,
+    html_content: String,
+    text_content: String
+}
+
+pub struct WatchedFeed {
+    settings: WatchedFeedSettings,
+    visibility: Visibility,
+    title: Template,
+    html: Template,
+    text: Template
+}
+
+impl WatchedFeed {
+    //템플릿과 공개 범위가 잘못되었으면 작업을 시작하기 전에 실패한다.
+    pub fn new(settings: WatchedFeedSettings) -> Result<Self, anyhow::Error> {
+        let visibility = match settings.visibility.as_str() {
+            "" => Visibility::Public,
+            visibility => Visibility::parse(visibility).map_err(anyhow::Error::msg)?
+        };
+        let (placeholders, defaults) = match settings.mode {
+            FeedMode::Digest => (DIGEST_PLACEHOLDERS, [DIGEST_TITLE, DIGEST_HTML, DIGEST_TEXT]),
+            FeedMode::Draft | FeedMode::Scheduled => (ITEM_PLACEHOLDERS, [ITEM_TITLE, ITEM_HTML, ITEM_TEXT])
+        };
+        let parse = |name: &str, source: &Option<String>, default: &str| -> Result<Template, TemplateError> {
+            Template::parse(name, source.as_deref().unwrap_or(default), placeholders)
+        };
+        let title = parse("title_template", &settings.title_template, defaults[0])?;
+        let html = parse("html_template", &settings.html_template, defaults[1])?;
+        let text = parse("text_template", &settings.text_template, defaults[2])?;
+        Ok(Self { settings, visibility, title, html, text })
+    }
+
+    fn feed_title<'a>(&'a self, title: &'a str) -> &'a str {
+        if title.is_empty() {
+            &self.settings.url
+        } else {
+            title
+        }
+    }
+
+    fn render_item(&self, feed_title: &str, item: &FeedItem) -> RenderedIssue {
+        let values = HashMap::from([
+            ("item.title", item.title.clone()),
+            ("item.link", item.link.clone()),
+            ("item.summary", item.summary.clone()),
+            ("feed.title", self.feed_title(feed_title).to_string())
+        ]);
+        RenderedIssue {
+            title: self.title.render(&values, as_is),
+            html_content: self.html.render(&values, htmlescape::encode_minimal),
+            text_content: self.text.render(&values, as_is)
+        }
+    }
+
+    fn render_digest(&self, feed_title: &str, items: &[FeedItem]) -> RenderedIssue {
+        let feed_title = self.feed_title(feed_title);
+        let mut items_html = String::from("<ul>\n");
+        let mut items_text = String::new();
+        for item in items {
+            write!(
+                items_html,
+                r#"<li><a href="{}">{}</a>"#,
+                htmlescape::encode_minimal(&item.link),
+                htmlescape::encode_minimal(&item.title)
+            ).unwrap();
+            if !item.summary.is_empty() {
+                write!(items_html, "<br>{}", htmlescape::encode_minimal(&item.summary)).unwrap();
+            }
+            items_html.push_str("</li>\n");
+            writeln!(items_text, "- {}\n  {}", item.title, item.link).unwrap();
+            if !item.summary.is_empty() {
+                writeln!(items_text, "  {}", item.summary).unwrap();
+            }
+        }
+        items_html.push_str("</ul>");
+        let item_count = items.len().to_string();
+        let text_values = HashMap::from([
+            ("feed.title", feed_title.to_string()),
+            ("items", items_text.trim_end().to_string()),
+            ("item_count", item_count.clone())
+        ]);
+        let html_values = HashMap::from([
+            ("feed.title", htmlescape::encode_minimal(feed_title)),
+            ("items", items_html),
+            ("item_count", item_count)
+        ]);
+        RenderedIssue {
+            title: self.title.render(&text_values, as_is),
+            html_content: self.html.render(&html_values, as_is),
+            text_content: self.text.render(&text_values, as_is)
+        }
+    }
+}
+
+pub struct FeedWatcher {
+    feeds: Vec<WatchedFeed>,
+    http_client: reqwest::Client
+}
+
+impl FeedWatcher {
+    pub fn new(settings: &FeedWatcherSettings) -> Result<Self, anyhow::Error> {
+        let feeds = settings
+            .feeds
+            .iter()
+            .map(|feed| WatchedFeed::new(feed.clone()).with_context(|| format!("Invalid watched feed {}", feed.url)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let http_client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(10))
+            .build()?;
+        Ok(Self { feeds, http_client })
+    }
+
+    //모든 피드를 한 번씩 읽고, 주기가 된 다이제스트와 시각이 지난 예약 이슈를 발송한다.
+    //한 피드를 읽지 못해도 나머지 피드는 계속 처리한다.
+    #[tracing::instrument(name = "Check watched feeds", skip_all)]
+    pub async fn run_once(&self, pool: &PgPool, now: DateTime<Utc>) -> Result<(), anyhow::Error> {
+        for feed in &self.feeds {
+            if let Err(e) = self.check_feed(pool, feed, now).await {
+                tracing::error!(
+                    error.cause_chain = ?e,
+                    error.message = %e,
+                    feed_url = %feed.settings.url,
+                    "Failed to check a watched feed"
+                );
+                continue;
+            }
+            //20261019 수정 / 다이제스트 발송에 실패해도 나머지 피드는 계속 처리한다.
+            if feed.settings.mode == FeedMode::Digest {
+                if let Err(e) = send_due_digest(pool, feed, now).await {
+                    tracing::error!(
+                        error.cause_chain = ?e,
+                        error.message = %e,
+                        feed_url = %feed.settings.url,
+                        "Failed to send a feed digest"
+                    );
+                }
+            }
+        }
+        send_due_issues(pool, now).await?;
+        Ok(())
+    }
+
+    //20261019 추가 / 응답을 조각 단위로 읽어 한도를 넘으면 중단한다.
+    async fn read_feed(&self, url: &str) -> Result<Vec<u8>, anyhow::Error> {
+        let mut response = self.http_client.get(url).send().await?.error_for_status()?;
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > MAX_FEED_SIZE {
+                anyhow::bail!("The feed is larger than {} bytes", MAX_FEED_SIZE);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    #[tracing::instrument(skip_all, fields(feed_url = %feed.settings.url))]
+    async fn check_feed(&self, pool: &PgPool, feed: &WatchedFeed, now: DateTime<Utc>) -> Result<(), anyhow::Error> {
+        let body = self.read_feed(&feed.settings.url).await?;
+        let parsed = parse_feed(&body)?;
+        let mut transaction = pool.begin().await?;
+        let first_check = sqlx::query!(
+            r#"
+            INSERT INTO watched_feeds (feed_url, title, last_checked_at, last_digest_at)
+            VALUES ($1, $2, $3, $3)
+            ON CONFLICT (feed_url) DO NOTHING
+            "#,
+            feed.settings.url,
+            parsed.title,
+            now
+        )
+        .execute(&mut transaction)
+        .await?
+        .rows_affected()
+            == 1;
+        if !first_check {
+            sqlx::query!(
+                r#"UPDATE watched_feeds SET title = $2, last_checked_at = $3 WHERE feed_url = $1"#,
+                feed.settings.url,
+                parsed.title,
+                now
+            )
+            .execute(&mut transaction)
+            .await?;
+        }
+        //오래된 글부터 기록해서 이슈와 다이제스트가 글이 올라온 순서를 따르도록 한다.
+        let mut new_items = 0;
+        for item in parsed.items.iter().rev() {
+            let pending_digest = !first_check && feed.settings.mode == FeedMode::Digest;
+            let inserted = sqlx::query!(
+                r#"
+                INSERT INTO watched_feed_items (feed_url, item_id, title, link, summary, pending_digest)
+                VALUES ($1, $2, $3, $4, $5, $6)
+                ON CONFLICT (feed_url, item_id) DO NOTHING
+                "#,
+                feed.settings.url,
+                item.id,
+                item.title,
+                item.link,
+                item.summary,
+                pending_digest
+            )
+            .execute(&mut transaction)
+            .await?
+            .rows_affected();
+            if inserted == 0 || first_check {
+                continue;
+            }
+            new_items += 1;
+            let (status, scheduled_for) = match feed.settings.mode {
+                FeedMode::Digest => continue,
+                FeedMode::Draft => ("draft", None),
+                FeedMode::Scheduled => ("scheduled", Some(now + feed.settings.send_delay()))
+            };
+            let issue_id = insert_issue(
+                &mut transaction,
+                &feed.render_item(&parsed.title, item),
+                feed.visibility,
+                status,
+                scheduled_for
+            )
+            .await?;
+            sqlx::query!(
+                r#"UPDATE watched_feed_items SET newsletter_issue_id = $3 WHERE feed_url = $1 AND item_id = $2"#,
+                feed.settings.url,
+                item.id,
+                issue_id
+            )
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await?;
+        tracing::info!(first_check, new_items, "Checked a watched feed");
+        Ok(())
+    }
+}
+
+#[tracing::instrument(skip_all)]
+async fn insert_issue(
+    transaction: &mut Transaction<'_, Postgres>,
+    issue: &RenderedIssue,
+    visibility: Visibility,
+    status: &str,
+    scheduled_for: Option<DateTime<Utc>>
+) -> Result<Uuid, sqlx::Error> {
+    let newsletter_issue_id = Uuid::new_v4();
+    let slug = unique_issue_slug(&mut *transaction, &issue.title).await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO newsletter_issues (
+            newsletter_issue_id, title, text_content, html_content, published_at, slug, visibility,
+            status, scheduled_for
+        )
+        VALUES ($1, $2, $3, $4, now(), $5, $6, $7, $8)
+        "#,
+        newsletter_issue_id,
+        issue.title,
+        issue.text_content,
+        issue.html_content,
+        slug,
+        visibility.as_str(),
+        status,
+        scheduled_for
+    )
+    .execute(transaction)
+    .await?;
+    Ok(newsletter_issue_id)
+}
+
+//마지막 다이제스트 이후 주기가 지났고 기다리는 글이 있으면 한 이슈로 묶어 기본 리스트로 발송한다.
+#[tracing::instrument(skip_all, fields(feed_url = %feed.settings.url))]
+async fn send_due_digest(pool: &PgPool, feed: &WatchedFeed, now: DateTime<Utc>) -> Result<Option<Uuid>, anyhow::Error> {
+    let mut transaction = pool.begin().await?;
+    let state = sqlx::query!(
+        r#"SELECT title, last_digest_at FROM watched_feeds WHERE feed_url = $1 FOR UPDATE"#,
+        feed.settings.url
+    )
+    .fetch_optional(&mut transaction)
+    .await?;
+    let state = match state {
+        Some(state) if state.last_digest_at + feed.settings.digest_interval() <= now => state,
+        _ => return Ok(None)
+    };
+    let items: Vec<FeedItem> = sqlx::query_as!(
+        FeedItem,
+        r#"
+        SELECT item_id AS id, title, link, summary
+        FROM watched_feed_items
+        WHERE feed_url = $1 AND pending_digest
+        ORDER BY first_seen_at
+        "#,
+        feed.settings.url
+    )
+    .fetch_all(&mut transaction)
+    .await?;
+    //보낼 글이 없으면 주기를 넘기지 않는다. 다음 글은 발견한 뒤 첫 확인에서 발송된다.
+    if items.is_empty() {
+        return Ok(None);
+    }
+    let issue_id = insert_issue(
+        &mut transaction,
+        &feed.render_digest(&state.title, &items),
+        feed.visibility,
+        "published",
+        None
+    )
+    .await?;
+    let list_ids = get_default_list_ids(&mut transaction).await?;
+    enqueue_delivery_tasks(&mut transaction, issue_id, &list_ids, None).await?;
+    sqlx::query!(
+        r#"
+        UPDATE watched_feed_items SET pending_digest = FALSE, newsletter_issue_id = $2
+        WHERE feed_url = $1 AND pending_digest
+        "#,
+        feed.settings.url,
+        issue_id
+    )
+    .execute(&mut transaction)
+    .await?;
+    sqlx::query!(
+        r#"UPDATE watched_feeds SET last_digest_at = $2 WHERE feed_url = $1"#,
+        feed.settings.url,
+        now
+    )
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    tracing::info!(%issue_id, items = items.len(), "Sent a feed digest");
+    Ok(Some(issue_id))
+}
+
+//예약 시각이 지난 이슈를 발송한다. 이슈마다 트랜잭션을 나눠 하나가 실패해도 나머지는 발송된다.
+#[tracing::instrument(skip(pool))]
+pub async fn send_due_issues(pool: &PgPool, now: DateTime<Utc>) -> Result<u64, anyhow::Error> {
+    let due = sqlx::query!(
+        r#"
+        SELECT newsletter_issue_id FROM newsletter_issues
+        WHERE status = 'scheduled' AND scheduled_for <= $1
+        ORDER BY scheduled_for
+        "#,
+        now
+    )
+    .fetch_all(pool)
+    .await?;
+    let mut sent = 0;
+    for issue in due {
+        let mut transaction = pool.begin().await?;
+        if send_held_issue(&mut transaction, issue.newsletter_issue_id).await? {
+            sent += 1;
+        }
+        transaction.commit().await?;
+    }
+    Ok(sent)
+}
+
+async fn watcher_loop(
+    pool: PgPool,
+    watcher: FeedWatcher,
+    poll_interval: std::time::Duration
+) -> Result<(), anyhow::Error> {
+    loop {
+        if let Err(e) = watcher.run_once(&pool, Utc::now()).await {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to process watched feeds"
+            );
+        }
+        tokio::time::sleep(poll_interval).await;
+    }
+}
+
+pub async fn run_feed_watcher_until_stopped(
+    configuration: Settings
+) -> Result<(), anyhow::Error> {
+    let connection_pool = get_connection_pool(&configuration.database);
+    let watcher = FeedWatcher::new(&configuration.feed_watcher)?;
+    watcher_loop(connection_pool, watcher, configuration.feed_watcher.poll_interval()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_feed, plain_text, FeedItem, WatchedFeed};
+    use crate::configuration::{FeedMode, WatchedFeedSettings};
+
+    fn settings(mode: FeedMode) -> WatchedFeedSettings {
+        WatchedFeedSettings {
+            url: "https://blog.example.com/feed.xml".into(),
+            mode,
+            send_delay_minutes: 0,
+            digest_interval_hours: 24,
+            visibility: String::new(),
+            title_template: None,
+            html_template: None,
+            text_template: None
+        }
+    }
+
+    #[test]
+    fn rss_and_atom_feeds_are_parsed() {
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Blog</title>
+            <item><title>Hello</title><link>https://blog.example.com/hello</link><guid>hello-1</guid>
+            <description>&lt;p&gt;First &lt;b&gt;post&lt;/b&gt;&lt;/p&gt;</description></item>
+            </channel></rss>"#;
+        let feed = parse_feed(rss.as_bytes()).unwrap();
+        assert_eq!(feed.title, "Blog");
+        assert_eq!(feed.items[0].id, "hello-1");
+        assert_eq!(feed.items[0].link, "https://blog.example.com/hello");
+        assert_eq!(feed.items[0].summary, "First post");
+
+        let atom = r#"<?xml version="1.0"?><feed xmlns="http://www.w3.org/2005/Atom"><title>Blog</title>
+            <id>urn:blog</id><updated>2026-10-19T00:00:00Z</updated>
+            <entry><id>urn:post:1</id><title>Hello</title><updated>2026-10-19T00:00:00Z</updated>
+            <link href="https://blog.example.com/hello"/><summary>First post</summary></entry></feed>"#;
+        let feed = parse_feed(atom.as_bytes()).unwrap();
+        assert_eq!(feed.items[0].id, "urn:post:1");
+        assert_eq!(feed.items[0].title, "Hello");
+    }
+
+    #[test]
+    fn only_http_and_https_links_are_kept() {
+        let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Blog</title>
+            <item><title>Script</title><link>javascript:alert(1)</link><guid>1</guid></item>
+            <item><title>Data</title><link>data:text/html,hello</link><guid>2</guid></item>
+            <item><title>Web</title><link>https://blog.example.com/web</link><guid>3</guid></item>
+            </channel></rss>"#;
+        let feed = parse_feed(rss.as_bytes()).unwrap();
+        assert_eq!(feed.items[0].link, "");
+        assert_eq!(feed.items[1].link, "");
+        assert_eq!(feed.items[2].link, "https://blog.example.com/web");
+    }
+
+    #[test]
+    fn garbage_is_not_a_feed() {
+        assert!(parse_feed(b"<html><body>Not a feed</body></html>").is_err());
+    }
+
+    #[test]
+    fn tags_are_stripped_and_entities_decoded() {
+        assert_eq!(plain_text("<p>Fish &amp; chips</p>\n<p>today</p>"), "Fish & chips today");
+    }
+
+    #[test]
+    fn templates_only_accept_placeholders_of_their_mode() {
+        let mut item = settings(FeedMode::Draft);
+        item.title_template = Some("{{ items }}".into());
+        assert!(WatchedFeed::new(item).is_err());
+        let mut digest = settings(FeedMode::Digest);
+        digest.title_template = Some("{{ item_count }} new posts on {{ feed.title }}".into());
+        assert!(WatchedFeed::new(digest).is_ok());
+        let mut hidden = settings(FeedMode::Draft);
+        hidden.visibility = "everyone".into();
+        assert!(WatchedFeed::new(hidden).is_err());
+    }
+
+    #[test]
+    fn digests_escape_items_in_the_html_body() {
+        let feed = WatchedFeed::new(settings(FeedMode::Digest)).unwrap();
+        let items = vec![FeedItem {
+            id: "1".into(),
+            title: "Tom & Jerry".into(),
+            link: "https://blog.example.com/?a=1&b=2".into(),
+            summary: String::new()
+        }];
+        let issue = feed.render_digest("", &items);
+        assert_eq!(issue.title, "New from https://blog.example.com/feed.xml");
+        assert!(issue.html_content.contains(
+            r#"<li><a href="https://blog.example.com/?a=1&amp;b=2">Tom &amp; Jerry</a></li>"#
+        ));
+        assert!(issue.text_content.ends_with("- Tom & Jerry\n  https://blog.example.com/?a=1&b=2"));
+    }
+}
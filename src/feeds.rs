@@ -0,0 +1,172 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+use uuid::Uuid;
+
+//20261019 추가 / 공개 이슈의 RSS 2.0 및 Atom 피드.
+//본문은 웹 아카이브와 같이 렌더링한 HTML을 이스케이프해서 넣고, 링크는 아카이브의 고유 주소를 사용한다.
+pub struct FeedEntry {
+    pub id: Uuid,
+    pub title: String,
+    pub link: String,
+    pub html: String,
+    pub published_at: DateTime<Utc>
+}
+
+pub struct FeedInfo<'a> {
+    pub title: &'a str,
+    pub description: &'a str,
+    //아카이브 목록 페이지
+    pub site_link: &'a str,
+    //피드 자신의 주소
+    pub self_link: &'a str
+}
+
+//XML의 다섯 가지 예약 문자를 이스케이프한다.
+fn xml(s: &str) -> String {
+    htmlescape::encode_minimal(s)
+}
+
+//가장 최근에 발행된 이슈의 발행 시각. 항목은 최근 순으로 정렬되어 있다.
+pub fn last_modified(entries: &[FeedEntry]) -> Option<DateTime<Utc>> {
+    entries.iter().map(|entry| entry.published_at).max()
+}
+
+pub fn rss(info: &FeedInfo, entries: &[FeedEntry]) -> String {
+    let mut items = String::new();
+    for entry in entries {
+        write!(
+            items,
+            r#"
+    <item>
+      <title>{title}</title>
+      <link>{link}</link>
+      <guid isPermaLink="true">{link}</guid>
+      <pubDate>{published_at}</pubDate>
+      <description>{html}</description>
+    </item>"#,
+            title = xml(&entry.title),
+            link = xml(&entry.link),
+            published_at = entry.published_at.to_rfc2822(),
+            html = xml(&entry.html)
+        ).unwrap();
+    }
+    let last_build_date = last_modified(entries)
+        .map(|date| format!("\n    <lastBuildDate>{}</lastBuildDate>", date.to_rfc2822()))
+        .unwrap_or_default();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:atom="http://www.w3.org/2005/Atom">
+  <channel>
+    <title>{title}</title>
+    <link>{site_link}</link>
+    <description>{description}</description>
+    <atom:link href="{self_link}" rel="self" type="application/rss+xml"/>{last_build_date}{items}
+  </channel>
+</rss>
+"#,
+        title = xml(info.title),
+        site_link = xml(info.site_link),
+        description = xml(info.description),
+        self_link = xml(info.self_link)
+    )
+}
+
+pub fn atom(info: &FeedInfo, entries: &[FeedEntry]) -> String {
+    let rfc3339 = |date: DateTime<Utc>| date.to_rfc3339_opts(SecondsFormat::Secs, true);
+    let mut items = String::new();
+    for entry in entries {
+        write!(
+            items,
+            r#"
+  <entry>
+    <id>urn:uuid:{id}</id>
+    <title>{title}</title>
+    <link rel="alternate" type="text/html" href="{link}"/>
+    <published>{published_at}</published>
+    <updated>{published_at}</updated>
+    <content type="html">{html}</content>
+  </entry>"#,
+            id = entry.id,
+            title = xml(&entry.title),
+            link = xml(&entry.link),
+            published_at = rfc3339(entry.published_at),
+            html = xml(&entry.html)
+        ).unwrap();
+    }
+    //Atom 피드에는 'updated'가 반드시 있어야 한다. 이슈가 없으면 고정된 값을 넣어 ETag가 바뀌지 않도록 한다.
+    let updated = last_modified(entries).unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <id>{self_link}</id>
+  <title>{title}</title>
+  <subtitle>{description}</subtitle>
+  <updated>{updated}</updated>
+  <link rel="self" type="application/atom+xml" href="{self_link}"/>
+  <link rel="alternate" type="text/html" href="{site_link}"/>{items}
+</feed>
+"#,
+        self_link = xml(info.self_link),
+        title = xml(info.title),
+        description = xml(info.description),
+        updated = rfc3339(updated),
+        site_link = xml(info.site_link)
+    )
+}
+
+//본문의 다이제스트. 공개 범위를 바꾸거나 이슈를 추가하면 본문이 바뀌므로 ETag도 바뀐다.
+pub fn etag(body: &str) -> String {
+    hex::encode(&Sha256::digest(body.as_bytes())[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{atom, etag, rss, FeedEntry, FeedInfo};
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    fn info() -> FeedInfo<'static> {
+        FeedInfo {
+            title: "News & notes",
+            description: "Our newsletter",
+            site_link: "https://example.com/archive",
+            self_link: "https://example.com/feed.rss"
+        }
+    }
+
+    fn entry() -> FeedEntry {
+        FeedEntry {
+            id: Uuid::nil(),
+            title: "Tom & Jerry".into(),
+            link: "https://example.com/archive/tom-jerry".into(),
+            html: "<p>Hi</p>".into(),
+            published_at: Utc.with_ymd_and_hms(2026, 10, 19, 8, 0, 0).unwrap()
+        }
+    }
+
+    #[test]
+    fn rss_items_escape_the_title_and_the_html_content() {
+        let feed = rss(&info(), &[entry()]);
+        assert!(feed.contains("<title>News &amp; notes</title>"));
+        assert!(feed.contains("<title>Tom &amp; Jerry</title>"));
+        assert!(feed.contains("<description>&lt;p&gt;Hi&lt;/p&gt;</description>"));
+        assert!(feed.contains("<pubDate>Mon, 19 Oct 2026 08:00:00 +0000</pubDate>"));
+        assert!(feed.contains(r#"<guid isPermaLink="true">https://example.com/archive/tom-jerry</guid>"#));
+    }
+
+    #[test]
+    fn atom_entries_have_an_id_a_permalink_and_html_content() {
+        let feed = atom(&info(), &[entry()]);
+        assert!(feed.contains("<updated>2026-10-19T08:00:00Z</updated>"));
+        assert!(feed.contains("<id>urn:uuid:00000000-0000-0000-0000-000000000000</id>"));
+        assert!(feed.contains(r#"<link rel="alternate" type="text/html" href="https://example.com/archive/tom-jerry"/>"#));
+        assert!(feed.contains(r#"<content type="html">&lt;p&gt;Hi&lt;/p&gt;</content>"#));
+    }
+
+    #[test]
+    fn an_empty_feed_is_stable() {
+        assert_eq!(etag(&atom(&info(), &[])), etag(&atom(&info(), &[])));
+        assert!(atom(&info(), &[]).contains("<updated>1970-01-01T00:00:00Z</updated>"));
+    }
+}
@@ -0,0 +1,25 @@
+use actix_web::dev::Payload;
+use actix_web::{web, FromRequest, HttpRequest};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use std::pin::Pin;
+use crate::utils::e400;
+
+//20261019 추가 / 'web::Form'과 같지만 'lists=a&lists=b'처럼 반복되는 키를 Vec으로 모을 수 있다.
+//'web::Form'이 사용하는 serde_urlencoded는 중복된 키를 지원하지 않는다.
+pub struct HtmlForm<T>(pub T);
+
+impl<T: DeserializeOwned + 'static> FromRequest for HtmlForm<T> {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, payload: &mut Payload) -> Self::Future {
+        let body = web::Bytes::from_request(req, payload);
+        Box::pin(async move {
+            let body = body.await?;
+            serde_html_form::from_bytes(&body)
+                .map(HtmlForm)
+                .map_err(e400)
+        })
+    }
+}
@@ -1,27 +1,39 @@
-use crate::email_client::EmailClient;
+use crate::email_client::{EmailClient, SendEmailError};
 use crate::domain::SubscriberEmail;
 use sqlx::{PgPool, Postgres, Transaction};
-//use tracing::{field::display, Span};
+use tracing::{field::display, Span};
 use uuid::Uuid;
 use std::time::Duration;
 //20250317 추가
 use crate::{configuration::Settings, startup::get_connection_pool};
+//20261019 추가
+use crate::email_templates::{personalize_issue, IssueContent, RenderedEmail, SubscriberProfile};
+use crate::signed_links::LinkSigner;
+use crate::tracking::{inject_open_pixel, register_links, rewrite_links, trackable_links};
+use crate::utm::UtmTagger;
+use crate::archive::Visibility;
+use crate::drip_sequences::is_in_sequence_list;
 
-struct NewsletterIssue {
-    title: String,
-    text_content: String,
-    html_content: String
+pub struct NewsletterIssue {
+    pub title: String,
+    pub text_content: String,
+    pub html_content: String,
+    //20261019 추가
+    pub track_opens: bool,
+    pub utm_campaign: Option<String>,
+    pub slug: String,
+    pub visibility: String
 }
 
 #[tracing::instrument(skip_all)]
-async fn get_issue(
+pub async fn get_issue(
     pool: &PgPool,
     issue_id: Uuid
 ) -> Result<NewsletterIssue, anyhow::Error> {
     let issue = sqlx::query_as!(
         NewsletterIssue,
         r#"
-        SELECT title, text_content, html_content
+        SELECT title, text_content, html_content, track_opens, utm_campaign, slug, visibility
         FROM newsletter_issues
         WHERE newsletter_issue_id = $1
         "#,
@@ -32,6 +44,124 @@ async fn get_issue(
     Ok(issue)
 }
 
+pub struct Recipient {
+    pub email: String,
+    pub status: String,
+    pub profile: SubscriberProfile,
+    //20261019 추가
+    pub tracking_opt_out: bool,
+    //20261019 추가 / 지금 발송을 일시 중지한 상태인지
+    pub paused: bool
+}
+
+//20261019 수정 / 큐에 적재된 이후의 구독 해지, 이름 및 주소 변경을 반영하도록 발송 시점에 구독자를 다시 읽는다.
+#[tracing::instrument(skip(pool))]
+pub async fn get_recipient(
+    pool: &PgPool,
+    subscriber_id: Uuid
+) -> Result<Option<Recipient>, anyhow::Error> {
+    let row = sqlx::query!(
+        r#"
+        SELECT email, status, name, custom_fields, tracking_opt_out,
+            COALESCE(paused_until > now(), FALSE) AS "paused!"
+        FROM subscriptions WHERE id = $1
+        "#,
+        subscriber_id
+    )
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|r| Recipient {
+        email: r.email,
+        status: r.status,
+        profile: SubscriberProfile {
+            name: r.name,
+            custom_fields: r.custom_fields
+        },
+        tracking_opt_out: r.tracking_opt_out,
+        paused: r.paused
+    }))
+}
+
+//20261019 추가 / 모든 이슈 이메일 끝에 구독 설정 링크를 붙인다.
+//20261019 수정 / 웹 아카이브에서 볼 수 있는 이슈는 '브라우저에서 보기' 링크도 붙인다.
+pub fn append_footer(mut email: RenderedEmail, preferences_link: &str, archive_link: Option<&str>) -> RenderedEmail {
+    if let Some(archive_link) = archive_link {
+        email.html_body.push_str(&format!(
+            r#"<p><a href="{}">View this issue in your browser</a></p>"#,
+            htmlescape::encode_minimal(archive_link)
+        ));
+        email.text_body.push_str(&format!("\n\nView this issue in your browser: {}", archive_link));
+    }
+    email.html_body.push_str(&format!(
+        r#"<p><a href="{}">Manage your subscription preferences or unsubscribe</a></p>"#,
+        htmlescape::encode_minimal(preferences_link)
+    ));
+    email.text_body.push_str(&format!(
+        "\n\nManage your subscription preferences or unsubscribe: {}",
+        preferences_link
+    ));
+    email
+}
+
+//20261019 추가 / 주간 다이제스트도 이슈마다 같은 개인화, UTM 파라미터, 클릭 추적을 적용하도록 나눴다.
+//웹 아카이브 링크가 있으면 함께 반환한다.
+#[tracing::instrument(skip_all)]
+pub async fn render_issue(
+    pool: &PgPool,
+    link_signer: &LinkSigner,
+    utm_tagger: &UtmTagger,
+    issue_id: Uuid,
+    issue: &NewsletterIssue,
+    subscriber_id: Uuid,
+    recipient: &Recipient
+) -> Result<(RenderedEmail, Option<String>), anyhow::Error> {
+    //20261019 추가 / 구독자의 이름과 사용자 정의 필드로 이슈를 개인화한다.
+    let content = IssueContent {
+        title: &issue.title,
+        html_content: &issue.html_content,
+        text_content: &issue.text_content
+    };
+    //개인화 이전에 발행된 이슈는 플레이스홀더 문법에 맞지 않을 수 있다. 그대로 발송한다.
+    let rendered = personalize_issue(&content, &recipient.profile).unwrap_or_else(|_| RenderedEmail {
+        subject: issue.title.clone(),
+        html_body: issue.html_content.clone(),
+        text_body: issue.text_content.clone()
+    });
+    //20261019 추가 / 클릭 추적 리다이렉트가 파라미터를 붙인 URL로 이동하도록 먼저 붙인다.
+    let rendered = match &issue.utm_campaign {
+        Some(campaign) => rewrite_links(rendered, |url| utm_tagger.tag(url, campaign)),
+        None => rendered
+    };
+    //20261019 추가 / 구독자가 추적을 거부하지 않았으면 링크를 클릭 추적 리다이렉트로 바꾼다.
+    //20261019 수정 / 개인화 이전의 이슈 본문에 있는 링크만 등록하고 바꾼다.
+    //사용자 정의 필드 값으로 들어온 URL에 서명된 리다이렉트를 만들어주지 않기 위해서다.
+    let rendered = if recipient.tracking_opt_out {
+        rendered
+    } else {
+        let issue_links = RenderedEmail {
+            subject: issue.title.clone(),
+            html_body: issue.html_content.clone(),
+            text_body: issue.text_content.clone()
+        };
+        let issue_links = match &issue.utm_campaign {
+            Some(campaign) => rewrite_links(issue_links, |url| utm_tagger.tag(url, campaign)),
+            None => issue_links
+        };
+        let link_ids = register_links(pool, issue_id, &trackable_links(&issue_links)).await?;
+        rewrite_links(rendered, |url| {
+            link_ids
+                .get(url)
+                .map(|link_id| link_signer.click_link(issue_id, subscriber_id, *link_id))
+        })
+    };
+    let archive_link = match Visibility::parse(&issue.visibility) {
+        Ok(Visibility::Public) => Some(link_signer.archive_link(&issue.slug, issue_id, None)),
+        Ok(Visibility::Subscribers) => Some(link_signer.archive_link(&issue.slug, issue_id, Some(subscriber_id))),
+        _ => None
+    };
+    Ok((rendered, archive_link))
+}
+
 pub enum ExecutionOutcome {
     TaskCompleted,
     EmptyQueue
@@ -41,32 +171,83 @@ pub enum ExecutionOutcome {
     skip_all,
     fields(
         newsletter_issue_id=tracing::field::Empty,
-        subscriber_email=tracing::field::Empty
+        subscriber_id=tracing::field::Empty
     ),
     err
 )]
 pub async fn try_execute_task(
     pool: &PgPool,
-    email_client: &EmailClient
+    email_client: &EmailClient,
+    //20261019 추가 / 구독 설정 링크를 만든다.
+    link_signer: &LinkSigner,
+    //20261019 추가 / 이슈가 요청하면 외부 링크에 UTM 파라미터를 붙인다.
+    utm_tagger: &UtmTagger
 ) -> Result<ExecutionOutcome, anyhow::Error> {
     let task = dequeue_task(pool).await?;
     if task.is_none() {
         return Ok(ExecutionOutcome::EmptyQueue)
     }
-    let (transaction, issue_id, email) = task.unwrap();
-    match SubscriberEmail::parse(email.clone()) {
+    let (transaction, issue_id, subscriber_id) = task.unwrap();
+    Span::current()
+        .record("newsletter_issue_id", display(issue_id))
+        .record("subscriber_id", display(subscriber_id));
+    //20261019 수정 / 큐에는 구독자 ID만 있으므로 현재 상태와 주소를 확인한다.
+    let recipient = match get_recipient(pool, subscriber_id).await? {
+        Some(recipient) if recipient.status == "confirmed" => recipient,
+        Some(_) => {
+            //이슈가 발행된 뒤 구독을 해지했다.
+            tracing::info!("Skipping a subscriber that is no longer confirmed");
+            delete_task(transaction, issue_id, subscriber_id).await?;
+            return Ok(ExecutionOutcome::TaskCompleted);
+        }
+        None => {
+            //외래 키가 작업을 함께 삭제하므로 보통은 일어나지 않는다.
+            tracing::info!("Skipping a subscriber that no longer exists");
+            delete_task(transaction, issue_id, subscriber_id).await?;
+            return Ok(ExecutionOutcome::TaskCompleted);
+        }
+    };
+    //20261019 추가 / 적재된 뒤에 발송을 일시 중지했거나, 시퀀스 단계라면 시퀀스의 리스트에서 빠졌는지 확인한다.
+    if recipient.paused || !is_in_sequence_list(pool, issue_id, subscriber_id).await? {
+        tracing::info!("Skipping a subscriber that paused delivery or left the sequence's list");
+        delete_task(transaction, issue_id, subscriber_id).await?;
+        return Ok(ExecutionOutcome::TaskCompleted);
+    }
+    match SubscriberEmail::parse(recipient.email.clone()) {
         Ok(email) => {
             let issue = get_issue(pool, issue_id).await?;
-            if let Err(e) = email_client
+            let (rendered, archive_link) = render_issue(
+                pool, link_signer, utm_tagger, issue_id, &issue, subscriber_id, &recipient
+            ).await?;
+            let mut rendered = append_footer(
+                rendered,
+                &link_signer.preferences_link(subscriber_id),
+                archive_link.as_deref()
+            );
+            //20261019 추가 / 추적을 켠 이슈이고 구독자가 거부하지 않았을 때만 열람 추적 이미지를 넣는다.
+            if issue.track_opens && !recipient.tracking_opt_out {
+                rendered.html_body = inject_open_pixel(
+                    &rendered.html_body,
+                    &link_signer.open_pixel_link(issue_id, subscriber_id)
+                );
+            }
+            match email_client
                 .send_email(
-                    &email, &issue.title, &issue.html_content, &issue.text_content
+                    &email, &rendered.subject, &rendered.html_body, &rendered.text_body
                 )
                 .await {
-                    tracing::error!(
-                        error.cause_chain = ?e,
-                        error.message = %e,
-                        "Failed to deliver issue to a confirmed subscriber. Skipping."
-                    );
+                    Ok(()) => {}
+                    //20261019 추가 / 적재된 뒤에 억제된 주소
+                    Err(SendEmailError::Suppressed) => {
+                        tracing::info!("Skipping a suppressed subscriber.");
+                    }
+                    Err(e) => {
+                        tracing::error!(
+                            error.cause_chain = ?e,
+                            error.message = %e,
+                            "Failed to deliver issue to a confirmed subscriber. Skipping."
+                        );
+                    }
                 }
         }
         Err(e) => {
@@ -77,8 +258,7 @@ pub async fn try_execute_task(
             );
         }
     }
-    //TODO : 이메일을 발송한다.
-    delete_task(transaction, issue_id, &email).await?;
+    delete_task(transaction, issue_id, subscriber_id).await?;
     Ok(ExecutionOutcome::TaskCompleted)
 }
 
@@ -87,11 +267,11 @@ type PgTransaction = Transaction<'static, Postgres>;
 #[tracing::instrument(skip_all)]
 async fn dequeue_task(
     pool: &PgPool
-) -> Result<Option<(PgTransaction, Uuid, String)>, anyhow::Error> {
+) -> Result<Option<(PgTransaction, Uuid, Uuid)>, anyhow::Error> {
     let mut transaction = pool.begin().await?;
     let r = sqlx::query!(
         r#"
-        SELECT newsletter_issue_id, subscriber_email
+        SELECT newsletter_issue_id, subscriber_id
         FROM issue_delivery_queue
         FOR UPDATE
         SKIP LOCKED
@@ -104,7 +284,7 @@ async fn dequeue_task(
         Ok(Some((
             transaction,
             r.newsletter_issue_id,
-            r.subscriber_email
+            r.subscriber_id
         )))
     }else {
         Ok(None)
@@ -115,16 +295,16 @@ async fn dequeue_task(
 async fn delete_task(
     mut transaction: PgTransaction,
     issue_id: Uuid,
-    email: &str
+    subscriber_id: Uuid
 ) -> Result<(), anyhow::Error> {
     sqlx::query!(
         r#"
         DELETE FROM issue_delivery_queue
         WHERE newsletter_issue_id = $1
-          AND subscriber_email = $2
+          AND subscriber_id = $2
         "#,
         issue_id,
-        email
+        subscriber_id
     )
     .execute(&mut transaction)
     .await?;
@@ -134,10 +314,12 @@ async fn delete_task(
 
 async fn worker_loop(
     pool: PgPool,
-    email_client: EmailClient
+    email_client: EmailClient,
+    link_signer: LinkSigner,
+    utm_tagger: UtmTagger
 ) -> Result<(), anyhow::Error> {
     loop{
-        match try_execute_task(&pool, &email_client).await {
+        match try_execute_task(&pool, &email_client, &link_signer, &utm_tagger).await {
             Ok(ExecutionOutcome::EmptyQueue) => {
                 tokio::time::sleep(Duration::from_secs(10)).await;
             }
@@ -154,6 +336,11 @@ pub async fn run_worker_until_stopped(
 ) -> Result<(), anyhow::Error> {
     let connection_pool = get_connection_pool(&configuration.database);
     //헬퍼 함수를 사용한다.
-    let email_client = configuration.email_client.client();
-    worker_loop(connection_pool, email_client).await
+    let email_client = configuration.email_client.client().with_suppression_list(connection_pool.clone());
+    let utm_tagger = UtmTagger::new(&configuration.utm, &configuration.application.base_url);
+    let link_signer = LinkSigner::new(
+        configuration.application.base_url,
+        configuration.application.hmac_secret
+    );
+    worker_loop(connection_pool, email_client, link_signer, utm_tagger).await
 }
\ No newline at end of file
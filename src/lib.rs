@@ -15,4 +15,24 @@ pub mod utils;
 //20250305 추가
 pub mod idempotency;
 //20250314 추가
-pub mod issue_delivery_worker;
\ No newline at end of file
+pub mod issue_delivery_worker;
+//20261019 추가
+pub mod subscriptions_cleanup_worker;
+pub mod email_templates;
+pub mod html_form;
+pub mod segment;
+pub mod audience;
+pub mod custom_fields;
+pub mod signed_links;
+pub mod subscriber_import;
+pub mod personal_data;
+pub mod subscription_events;
+pub mod bounces;
+pub mod suppression;
+pub mod tracking;
+pub mod utm;
+pub mod archive;
+pub mod feeds;
+pub mod feed_watcher;
+pub mod drip_sequences;
+pub mod digests;
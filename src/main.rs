@@ -3,6 +3,12 @@ use zero2prod::startup::Application;
 use zero2prod::telemetry::{get_subscriber, init_subscriber};
 //20250317 추가
 use zero2prod::issue_delivery_worker::run_worker_until_stopped;
+//20261019 추가
+use zero2prod::subscriptions_cleanup_worker::run_cleanup_worker_until_stopped;
+use zero2prod::subscriber_import::run_import_worker_until_stopped;
+use zero2prod::feed_watcher::run_feed_watcher_until_stopped;
+use zero2prod::drip_sequences::run_drip_scheduler_until_stopped;
+use zero2prod::digests::run_digest_sender_until_stopped;
 use std::fmt::{Debug, Display};
 use tokio::task::JoinError;
 
@@ -21,11 +27,21 @@ async fn main() -> anyhow::Result<()> {
     let configuration = get_configuration().expect("Failed to read configuration.");
     let application = Application::build(configuration.clone()).await?;
     let application_task = tokio::spawn(application.run_until_stopped());
-    let worker_task = tokio::spawn(run_worker_until_stopped(configuration));
+    let worker_task = tokio::spawn(run_worker_until_stopped(configuration.clone()));
+    let cleanup_task = tokio::spawn(run_cleanup_worker_until_stopped(configuration.clone()));
+    let import_task = tokio::spawn(run_import_worker_until_stopped(configuration.clone()));
+    let feed_watcher_task = tokio::spawn(run_feed_watcher_until_stopped(configuration.clone()));
+    let drip_task = tokio::spawn(run_drip_scheduler_until_stopped(configuration.clone()));
+    let digest_task = tokio::spawn(run_digest_sender_until_stopped(configuration));
 
     tokio::select! {
         o = application_task => report_exit("API", o),
-        o = worker_task =>  report_exit("Background worker", o)
+        o = worker_task =>  report_exit("Background worker", o),
+        o = cleanup_task => report_exit("Cleanup worker", o),
+        o = import_task => report_exit("Import worker", o),
+        o = feed_watcher_task => report_exit("Feed watcher", o),
+        o = drip_task => report_exit("Drip scheduler", o),
+        o = digest_task => report_exit("Digest sender", o)
     };
     Ok(())
 }
@@ -0,0 +1,277 @@
+use chrono::Utc;
+use serde_json::Value;
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+use crate::suppression::{is_suppressed, suppress_email};
+
+//20261019 추가 / 개인정보 열람 및 삭제 요청(GDPR) 처리.
+//이메일 주소를 참조하는 모든 행을 모아서 내보내고, 삭제할 때는 같은 행을 한 트랜잭션에서 지운 뒤
+//주소를 다시 가져오지 않도록 주소의 다이제스트만 억제 목록에 남긴다.
+
+async fn get_subscriber_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    email: &str
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT id FROM subscriptions WHERE lower(email) = lower($1) FOR UPDATE"#,
+        email.trim()
+    )
+    .fetch_all(transaction)
+    .await?;
+    Ok(rows.into_iter().map(|r| r.id).collect())
+}
+
+//주소에 관련된 모든 데이터를 하나의 JSON 문서로 모은다. 테이블마다 행의 배열을 담는다.
+#[tracing::instrument(skip(pool, email))]
+pub async fn collect_personal_data(
+    pool: &PgPool,
+    email: &str
+) -> Result<Value, sqlx::Error> {
+    let email = email.trim();
+    let mut transaction = pool.begin().await?;
+    let subscriber_ids = get_subscriber_ids(&mut transaction, email).await?;
+    let subscriptions = sqlx::query_scalar!(
+        r#"SELECT COALESCE(json_agg(s), '[]') AS "rows!" FROM subscriptions s WHERE s.id = ANY($1)"#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    //토큰 자체(다이제스트)는 개인정보가 아니므로 제외한다.
+    let subscription_tokens = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'created_at', t.created_at,
+            'consumed_at', t.consumed_at,
+            'new_email', t.new_email
+        ) ORDER BY t.created_at), '[]') AS "rows!"
+        FROM subscription_tokens t
+        WHERE t.subscriber_id = ANY($1) OR lower(t.new_email) = lower($2)
+        "#,
+        &subscriber_ids,
+        email
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    let list_memberships = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'list', l.slug,
+            'status', m.status,
+            'created_at', m.created_at
+        ) ORDER BY l.slug), '[]') AS "rows!"
+        FROM list_memberships m
+        JOIN lists l ON l.list_id = m.list_id
+        WHERE m.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    let tags = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'tag', t.tag,
+            'created_at', t.created_at
+        ) ORDER BY t.tag), '[]') AS "rows!"
+        FROM subscriber_tags t
+        WHERE t.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    let pending_deliveries = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'newsletter_issue_id', q.newsletter_issue_id,
+            'title', i.title
+        )), '[]') AS "rows!"
+        FROM issue_delivery_queue q
+        JOIN newsletter_issues i ON i.newsletter_issue_id = q.newsletter_issue_id
+        WHERE q.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    //20261019 추가 / 주간 다이제스트를 기다리는 이슈
+    let pending_digest_issues = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'newsletter_issue_id', d.newsletter_issue_id,
+            'title', i.title,
+            'queued_at', d.queued_at
+        ) ORDER BY d.queued_at), '[]') AS "rows!"
+        FROM digest_queue d
+        JOIN newsletter_issues i ON i.newsletter_issue_id = d.newsletter_issue_id
+        WHERE d.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    let imports = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'source', i.source,
+            'imported_at', i.created_at,
+            'email', r.email,
+            'name', r.name,
+            'consented_at', r.consented_at,
+            'outcome', r.outcome
+        ) ORDER BY i.created_at), '[]') AS "rows!"
+        FROM subscriber_import_rows r
+        JOIN subscriber_imports i ON i.import_id = r.import_id
+        WHERE lower(r.email) = lower($1)
+        "#,
+        email
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    //20261019 추가 / 구독 동의 기록
+    let subscription_events = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'event_type', e.event_type,
+            'source', e.source,
+            'ip_address', e.ip_address,
+            'user_agent', e.user_agent,
+            'details', e.details,
+            'occurred_at', e.occurred_at
+        ) ORDER BY e.occurred_at, e.event_id), '[]') AS "rows!"
+        FROM subscription_events e
+        WHERE e.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    //20261019 추가 / 이슈 열람 기록. 구독자를 삭제하면 외래 키로 함께 삭제된다.
+    let issue_opens = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'newsletter_issue_id', o.newsletter_issue_id,
+            'title', i.title,
+            'first_opened_at', o.first_opened_at,
+            'last_opened_at', o.last_opened_at,
+            'open_count', o.open_count,
+            'user_agent_class', o.user_agent_class
+        ) ORDER BY o.first_opened_at), '[]') AS "rows!"
+        FROM issue_opens o
+        JOIN newsletter_issues i ON i.newsletter_issue_id = o.newsletter_issue_id
+        WHERE o.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    //20261019 추가 / 링크 클릭 기록
+    let link_clicks = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'newsletter_issue_id', l.newsletter_issue_id,
+            'url', l.url,
+            'first_clicked_at', c.first_clicked_at,
+            'last_clicked_at', c.last_clicked_at,
+            'click_count', c.click_count
+        ) ORDER BY c.first_clicked_at), '[]') AS "rows!"
+        FROM link_clicks c
+        JOIN issue_links l ON l.link_id = c.link_id
+        WHERE c.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    //20261019 추가 / 환영 이메일 시퀀스 등록
+    let drip_enrollments = sqlx::query_scalar!(
+        r#"
+        SELECT COALESCE(json_agg(json_build_object(
+            'sequence', q.name,
+            'enrolled_at', e.enrolled_at,
+            'stopped_at', e.stopped_at,
+            'stop_reason', e.stop_reason
+        ) ORDER BY e.enrolled_at), '[]') AS "rows!"
+        FROM drip_enrollments e
+        JOIN drip_sequences q ON q.sequence_id = e.sequence_id
+        WHERE e.subscriber_id = ANY($1)
+        "#,
+        &subscriber_ids
+    )
+    .fetch_one(&mut transaction)
+    .await?;
+    let suppressed = is_suppressed(&mut transaction, email).await?;
+    transaction.commit().await?;
+    Ok(serde_json::json!({
+        "email": email,
+        "generated_at": Utc::now().to_rfc3339(),
+        "subscriptions": subscriptions,
+        "subscription_tokens": subscription_tokens,
+        "list_memberships": list_memberships,
+        "tags": tags,
+        "pending_deliveries": pending_deliveries,
+        "pending_digest_issues": pending_digest_issues,
+        "imports": imports,
+        "subscription_events": subscription_events,
+        "issue_opens": issue_opens,
+        "link_clicks": link_clicks,
+        "drip_enrollments": drip_enrollments,
+        "suppressed": suppressed
+    }))
+}
+
+pub struct ErasureOutcome {
+    pub erased_subscribers: u64
+}
+
+//주소에 관련된 행을 모두 지우고 억제 목록에 추가한다. 하나라도 실패하면 아무것도 지우지 않는다.
+//20261019 수정 / 구독 이벤트도 지우고, 삭제했다는 사실만 개인정보 없이 새 이벤트로 남긴다.
+#[tracing::instrument(skip(pool, email))]
+pub async fn erase_personal_data(
+    pool: &PgPool,
+    email: &str,
+    source: EventSource
+) -> Result<ErasureOutcome, sqlx::Error> {
+    let email = email.trim();
+    let mut transaction = pool.begin().await?;
+    let subscriber_ids = get_subscriber_ids(&mut transaction, email).await?;
+    sqlx::query!(
+        r#"
+        DELETE FROM subscription_tokens
+        WHERE subscriber_id = ANY($1) OR lower(new_email) = lower($2)
+        "#,
+        &subscriber_ids,
+        email
+    )
+    .execute(&mut transaction)
+    .await?;
+    //리스트 멤버십, 태그, 발송 및 다이제스트 대기 행, 열람 및 클릭 기록, 시퀀스 등록은 외래 키로 함께 삭제된다.
+    let erased_subscribers = sqlx::query!(
+        r#"DELETE FROM subscriptions WHERE id = ANY($1)"#,
+        &subscriber_ids
+    )
+    .execute(&mut transaction)
+    .await?
+    .rows_affected();
+    sqlx::query!(
+        r#"DELETE FROM subscriber_import_rows WHERE lower(email) = lower($1)"#,
+        email
+    )
+    .execute(&mut transaction)
+    .await?;
+    sqlx::query!(
+        r#"DELETE FROM subscription_events WHERE subscriber_id = ANY($1)"#,
+        &subscriber_ids
+    )
+    .execute(&mut transaction)
+    .await?;
+    //요청한 사람의 IP와 User-Agent도 개인정보이므로 남기지 않는다.
+    let event_context = EventContext::without_request(source);
+    for subscriber_id in &subscriber_ids {
+        record_event(&mut transaction, *subscriber_id, EventType::Erased, &event_context, serde_json::json!({})).await?;
+    }
+    suppress_email(&mut transaction, email, "erasure").await?;
+    transaction.commit().await?;
+    Ok(ErasureOutcome { erased_subscribers })
+}
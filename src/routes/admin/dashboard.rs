@@ -36,6 +36,15 @@ pub async fn admin_dashboard(
                     <p>Availavle actions:</p>
                     <ol>
                         <li><a href="/admin/password">Change password</a></li>
+                        <li><a href="/admin/newsletters">Publish a newsletter issue</a></li>
+                        <li><a href="/admin/issues">Published issues</a></li>
+                        <li><a href="/admin/lists">Manage lists</a></li>
+                        <li><a href="/admin/sequences">Welcome sequences</a></li>
+                        <li><a href="/admin/subscribers">Manage subscribers</a></li>
+                        <li><a href="/admin/imports">Import subscribers</a></li>
+                        <li><a href="/admin/data-requests">Handle data requests</a></li>
+                        <li><a href="/admin/suppressions">Manage the suppression list</a></li>
+                        <li><a href="/admin/fields">Manage custom fields</a></li>
                         <li>
                             <form name="logoutForm" action="/admin/logout" method="post">
                                 <input type="submit" value="Logout">
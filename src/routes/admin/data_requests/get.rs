@@ -0,0 +1,74 @@
+use actix_web::http::header::{ContentDisposition, ContentType, DispositionParam, DispositionType};
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::IncomingFlashMessages;
+use sqlx::PgPool;
+use std::fmt::Write;
+use crate::personal_data::collect_personal_data;
+use crate::utils::{e400, e500};
+
+//20261019 추가 / 개인정보 열람 및 삭제 요청 처리
+pub async fn data_requests_page(
+    flash_messages: IncomingFlashMessages
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut msg_html = String::new();
+    for m in flash_messages.iter() {
+        writeln!(msg_html, "<p><i>{}</i></p>", m.content()).unwrap();
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Data requests</title>
+</head>
+<body>
+    {msg_html}
+    <form action="/admin/data-requests/export" method="get">
+        <label>Email
+            <input type="text" placeholder="Enter the email address" name="email">
+        </label>
+        <button type="submit">Export all data</button>
+    </form>
+    <form action="/admin/data-requests/erase" method="post">
+        <label>Email
+            <input type="text" placeholder="Enter the email address" name="email">
+        </label>
+        <button type="submit">Erase all data</button>
+    </form>
+    <p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
+
+#[derive(serde::Deserialize)]
+pub struct ExportQuery {
+    email: String
+}
+
+//주소를 참조하는 모든 행을 JSON 파일로 내려받는다. 구독자가 아니어도 가져오기 기록 등이 있을 수 있다.
+#[tracing::instrument(name = "Export personal data", skip_all)]
+pub async fn export_personal_data(
+    query: web::Query<ExportQuery>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    if query.email.trim().is_empty() {
+        return Err(e400("The email address must not be empty."));
+    }
+    let data = collect_personal_data(&pool, &query.email).await.map_err(e500)?;
+    Ok(personal_data_response(&data))
+}
+
+//개인정보 JSON을 첨부 파일로 응답한다. 구독 설정 페이지에서도 사용한다.
+pub fn personal_data_response(data: &serde_json::Value) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ContentType::json())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename("personal-data.json".into())]
+        })
+        .body(serde_json::to_string_pretty(data).unwrap())
+}
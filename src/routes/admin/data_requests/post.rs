@@ -0,0 +1,31 @@
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use sqlx::PgPool;
+use crate::personal_data::erase_personal_data;
+use crate::subscription_events::EventSource;
+use crate::utils::{e500, see_other};
+
+#[derive(serde::Deserialize)]
+pub struct FormData {
+    email: String
+}
+
+#[tracing::instrument(name = "Erase personal data", skip_all)]
+pub async fn erase_subscriber_data(
+    form: web::Form<FormData>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let email = form.0.email.trim().to_string();
+    if email.is_empty() {
+        FlashMessage::error("The email address must not be empty.").send();
+        return Ok(see_other("/admin/data-requests"));
+    }
+    let outcome = erase_personal_data(&pool, &email, EventSource::Admin).await.map_err(e500)?;
+    let email = htmlescape::encode_minimal(&email);
+    if outcome.erased_subscribers > 0 {
+        FlashMessage::info(format!("All data about {} has been erased and the address has been suppressed.", email)).send();
+    } else {
+        FlashMessage::info(format!("No subscriber was found for {}. The address has been suppressed.", email)).send();
+    }
+    Ok(see_other("/admin/data-requests"))
+}
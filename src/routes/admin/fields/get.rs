@@ -0,0 +1,73 @@
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::IncomingFlashMessages;
+use sqlx::PgPool;
+use std::fmt::Write;
+use crate::custom_fields::get_field_definitions;
+use crate::utils::e500;
+
+//20261019 추가 / 구독자 사용자 정의 필드 관리
+pub async fn fields_page(
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut msg_html = String::new();
+    for m in flash_messages.iter() {
+        writeln!(msg_html, "<p><i>{}</i></p>", m.content()).unwrap();
+    }
+    let mut rows_html = String::new();
+    for definition in get_field_definitions(pool.get_ref()).await.map_err(e500)? {
+        writeln!(
+            rows_html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            htmlescape::encode_minimal(&definition.label),
+            definition.key,
+            definition.field_type.as_str(),
+            if definition.required { "yes" } else { "no" },
+            htmlescape::encode_minimal(&definition.choices.join(", "))
+        ).unwrap();
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Custom fields</title>
+</head>
+<body>
+    {msg_html}
+    <table>
+        <tr><th>Label</th><th>Key</th><th>Type</th><th>Required</th><th>Choices</th></tr>
+        {rows_html}
+    </table>
+    <form action="/admin/fields" method="post">
+        <label>Label
+            <input type="text" placeholder="e.g. Preferred language" name="label">
+        </label>
+        <label>Key
+            <input type="text" placeholder="e.g. language" name="key">
+        </label>
+        <label>Type
+            <select name="field_type">
+                <option value="text">Text</option>
+                <option value="number">Number</option>
+                <option value="boolean">Yes / No</option>
+                <option value="choice">Choice</option>
+            </select>
+        </label>
+        <label>Choices
+            <input type="text" placeholder="e.g. en, ko" name="choices">
+        </label>
+        <label>
+            <input type="checkbox" name="required" value="true"> Required
+        </label>
+        <button type="submit">Create field</button>
+    </form>
+    <p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
@@ -0,0 +1,5 @@
+mod get;
+mod post;
+
+pub use get::fields_page;
+pub use post::create_field;
@@ -0,0 +1,68 @@
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use sqlx::PgPool;
+use crate::custom_fields::{parse_field_key, FieldType};
+use crate::utils::{e500, see_other};
+
+#[derive(serde::Deserialize)]
+pub struct FormData {
+    label: String,
+    key: String,
+    field_type: String,
+    #[serde(default)]
+    choices: String,
+    #[serde(default)]
+    required: bool
+}
+
+//20261019 추가 / 사용자 정의 필드를 만든다. 키는 한 번 만들면 바꿀 수 없다. (세그먼트와 이슈가 키로 참조한다)
+#[tracing::instrument(name = "Create a custom field", skip(form, pool))]
+pub async fn create_field(
+    form: web::Form<FormData>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let FormData { label, key, field_type, choices, required } = form.0;
+    let validated = parse_field_key(&key).and_then(|key| {
+        let field_type = FieldType::parse(&field_type)?;
+        if label.trim().is_empty() {
+            return Err("The field label must not be empty.".to_string());
+        }
+        let choices: Vec<String> = choices
+            .split(',')
+            .map(|c| c.trim().to_string())
+            .filter(|c| !c.is_empty())
+            .collect();
+        if field_type == FieldType::Choice && choices.is_empty() {
+            return Err("A choice field needs at least one choice.".to_string());
+        }
+        Ok((key, field_type, choices))
+    });
+    let (key, field_type, choices) = match validated {
+        Ok(validated) => validated,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other("/admin/fields"));
+        }
+    };
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO custom_field_definitions (key, label, field_type, required, choices)
+        VALUES ($1, $2, $3, $4, $5)
+        ON CONFLICT (key) DO NOTHING
+        "#,
+        key,
+        label.trim(),
+        field_type.as_str(),
+        required,
+        &choices
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    if result.rows_affected() == 0 {
+        FlashMessage::error(format!("A field with the key '{}' already exists.", key)).send();
+    } else {
+        FlashMessage::info(format!("The field '{}' has been created.", key)).send();
+    }
+    Ok(see_other("/admin/fields"))
+}
@@ -0,0 +1,257 @@
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::IncomingFlashMessages;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::fmt::Write;
+use uuid::Uuid;
+use crate::routes::get_lists;
+use crate::utils::e500;
+
+//20261019 추가 / CSV 가져오기 작업과 행 처리 결과 요약
+pub struct ImportSummary {
+    pub import_id: Uuid,
+    pub list_name: String,
+    pub mode: String,
+    pub source: String,
+    pub status: String,
+    pub created_at: DateTime<Utc>,
+    pub total_rows: i64,
+    pub pending_rows: i64,
+    pub imported_rows: i64,
+    pub invalid_rows: i64,
+    pub duplicate_rows: i64,
+    pub existing_rows: i64,
+    pub suppressed_rows: i64,
+    pub failed_rows: i64
+}
+
+fn flash_html(flash_messages: &IncomingFlashMessages) -> String {
+    let mut msg_html = String::new();
+    for m in flash_messages.iter() {
+        writeln!(msg_html, "<p><i>{}</i></p>", m.content()).unwrap();
+    }
+    msg_html
+}
+
+pub async fn imports_page(
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let msg_html = flash_html(&flash_messages);
+    let mut list_options = String::new();
+    for list in get_lists(&pool).await.map_err(e500)? {
+        writeln!(
+            list_options,
+            r#"<option value="{}">{}</option>"#,
+            list.list_id,
+            htmlescape::encode_minimal(&list.name)
+        ).unwrap();
+    }
+    let mut rows_html = String::new();
+    for import in get_imports(&pool, None).await.map_err(e500)? {
+        writeln!(
+            rows_html,
+            r#"<tr><td><a href="/admin/imports/{}">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+            import.import_id,
+            import.created_at.format("%Y-%m-%d %H:%M"),
+            htmlescape::encode_minimal(&import.source),
+            htmlescape::encode_minimal(&import.list_name),
+            import.mode,
+            import.status,
+            import.imported_rows
+        ).unwrap();
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Import subscribers</title>
+</head>
+<body>
+    {msg_html}
+    <p>Upload a CSV file with <code>email</code> and <code>name</code> columns and an optional <code>consent_date</code> column (YYYY-MM-DD).
+    Nothing is imported until you have reviewed the validation report.</p>
+    <form action="/admin/imports" method="post" enctype="multipart/form-data">
+        <label>CSV file
+            <input type="file" name="file" accept=".csv,text/csv">
+        </label>
+        <label>List
+            <select name="list_id">
+                {list_options}
+            </select>
+        </label>
+        <label>Mode
+            <select name="mode">
+                <option value="confirmation_email">Send confirmation emails</option>
+                <option value="confirmed">Import as confirmed subscribers</option>
+            </select>
+        </label>
+        <label>Source
+            <input type="text" placeholder="e.g. previous-provider" name="source">
+        </label>
+        <label>Consent date
+            <input type="date" name="consent_date">
+        </label>
+        <button type="submit">Validate file</button>
+    </form>
+    <table>
+        <tr><th>Uploaded</th><th>Source</th><th>List</th><th>Mode</th><th>Status</th><th>Imported</th></tr>
+        {rows_html}
+    </table>
+    <p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
+
+//검증 결과와 진행 상황. 초안 상태에서는 가져오기를 시작할 수 있다.
+pub async fn import_report(
+    import_id: web::Path<Uuid>,
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let import_id = import_id.into_inner();
+    let import = match get_imports(&pool, Some(import_id)).await.map_err(e500)?.pop() {
+        Some(import) => import,
+        None => return Ok(HttpResponse::NotFound().finish())
+    };
+    let msg_html = flash_html(&flash_messages);
+    let mut rows_html = String::new();
+    for row in get_rejected_rows(&pool, import_id).await.map_err(e500)? {
+        let reason = match row.outcome.as_str() {
+            "duplicate" => "Duplicate of an earlier row".to_string(),
+            "existing" => "Already a subscriber".to_string(),
+            "suppressed" => "Suppressed address".to_string(),
+            _ => row.error.unwrap_or_default()
+        };
+        writeln!(
+            rows_html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            row.line,
+            htmlescape::encode_minimal(&row.email),
+            htmlescape::encode_minimal(&row.name),
+            htmlescape::encode_minimal(&reason)
+        ).unwrap();
+    }
+    let action_html = match import.status.as_str() {
+        "draft" => format!(
+            r#"<form action="/admin/imports/{}/start" method="post">
+        <button type="submit">Import {} subscriber(s)</button>
+    </form>"#,
+            import_id, import.pending_rows
+        ),
+        "queued" => format!(
+            "<p>Importing: {} of {} row(s) remaining.</p>",
+            import.pending_rows, import.pending_rows + import.imported_rows
+        ),
+        _ => "<p>This import has been completed.</p>".to_string()
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Import report</title>
+</head>
+<body>
+    {msg_html}
+    <p>Source: {source} / List: {list_name} / Mode: {mode}</p>
+    <ul>
+        <li>Rows: {total}</li>
+        <li>Ready to import: {pending}</li>
+        <li>Imported: {imported}</li>
+        <li>Invalid: {invalid}</li>
+        <li>Duplicates: {duplicate}</li>
+        <li>Already subscribed: {existing}</li>
+        <li>Suppressed: {suppressed}</li>
+        <li>Failed: {failed}</li>
+    </ul>
+    {action_html}
+    <table>
+        <tr><th>Line</th><th>Email</th><th>Name</th><th>Reason</th></tr>
+        {rows_html}
+    </table>
+    <p><a href="/admin/imports">&lt;- Back</a></p>
+</body>
+</html>"#,
+            source = htmlescape::encode_minimal(&import.source),
+            list_name = htmlescape::encode_minimal(&import.list_name),
+            mode = import.mode,
+            total = import.total_rows,
+            pending = import.pending_rows,
+            imported = import.imported_rows,
+            invalid = import.invalid_rows,
+            duplicate = import.duplicate_rows,
+            existing = import.existing_rows,
+            suppressed = import.suppressed_rows,
+            failed = import.failed_rows
+        )))
+}
+
+#[tracing::instrument(skip(pool))]
+async fn get_imports(
+    pool: &PgPool,
+    import_id: Option<Uuid>
+) -> Result<Vec<ImportSummary>, sqlx::Error> {
+    sqlx::query_as!(
+        ImportSummary,
+        r#"
+        SELECT i.import_id, l.name AS list_name, i.mode, i.source, i.status, i.created_at,
+            COUNT(r.line) AS "total_rows!",
+            COUNT(r.line) FILTER (WHERE r.outcome = 'pending') AS "pending_rows!",
+            COUNT(r.line) FILTER (WHERE r.outcome = 'imported') AS "imported_rows!",
+            COUNT(r.line) FILTER (WHERE r.outcome = 'invalid') AS "invalid_rows!",
+            COUNT(r.line) FILTER (WHERE r.outcome = 'duplicate') AS "duplicate_rows!",
+            COUNT(r.line) FILTER (WHERE r.outcome = 'existing') AS "existing_rows!",
+            COUNT(r.line) FILTER (WHERE r.outcome = 'suppressed') AS "suppressed_rows!",
+            COUNT(r.line) FILTER (WHERE r.outcome = 'failed') AS "failed_rows!"
+        FROM subscriber_imports i
+        JOIN lists l ON l.list_id = i.list_id
+        LEFT JOIN subscriber_import_rows r ON r.import_id = i.import_id
+        WHERE $1::uuid IS NULL OR i.import_id = $1
+        GROUP BY i.import_id, l.name
+        ORDER BY i.created_at DESC
+        LIMIT 50
+        "#,
+        import_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+struct RejectedRow {
+    line: i32,
+    email: String,
+    name: String,
+    outcome: String,
+    error: Option<String>
+}
+
+//가져오지 않는(또는 못한) 행. 보고서가 너무 커지지 않도록 처음 1000행만 보여준다.
+#[tracing::instrument(skip(pool))]
+async fn get_rejected_rows(
+    pool: &PgPool,
+    import_id: Uuid
+) -> Result<Vec<RejectedRow>, sqlx::Error> {
+    sqlx::query_as!(
+        RejectedRow,
+        r#"
+        SELECT line, email, name, outcome, error
+        FROM subscriber_import_rows
+        WHERE import_id = $1 AND outcome IN ('invalid', 'duplicate', 'existing', 'suppressed', 'failed')
+        ORDER BY line
+        LIMIT 1000
+        "#,
+        import_id
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,5 @@
+mod get;
+mod post;
+
+pub use get::{import_report, imports_page};
+pub use post::{start_import, upload_import};
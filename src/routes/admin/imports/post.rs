@@ -0,0 +1,79 @@
+use actix_multipart::form::{bytes::Bytes, text::Text, MultipartForm};
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::subscriber_import::{create_import, parse_import_csv, ImportMode};
+use crate::utils::{e500, see_other};
+
+//20261019 추가 / CSV 파일 업로드 폼
+#[derive(MultipartForm)]
+pub struct UploadForm {
+    file: Bytes,
+    list_id: Text<Uuid>,
+    mode: Text<String>,
+    source: Text<String>,
+    consent_date: Option<Text<String>>
+}
+
+//업로드한 파일을 검증하고 초안으로 저장한다. 구독자는 관리자가 보고서를 확인하고 시작할 때 추가된다.
+#[tracing::instrument(name = "Upload a subscriber import", skip(form, pool))]
+pub async fn upload_import(
+    MultipartForm(form): MultipartForm<UploadForm>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let mode = match ImportMode::parse(&form.mode) {
+        Ok(mode) => mode,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other("/admin/imports"));
+        }
+    };
+    let source = form.source.trim();
+    if source.is_empty() {
+        FlashMessage::error("The source of the subscribers must not be empty.").send();
+        return Ok(see_other("/admin/imports"));
+    }
+    let consent_date = form.consent_date.as_ref().map(|d| d.as_str());
+    let rows = match parse_import_csv(&form.file.data, mode, consent_date) {
+        Ok(rows) => rows,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other("/admin/imports"));
+        }
+    };
+    let list_exists = sqlx::query!(r#"SELECT list_id FROM lists WHERE list_id = $1"#, *form.list_id)
+        .fetch_optional(pool.get_ref())
+        .await
+        .map_err(e500)?
+        .is_some();
+    if !list_exists {
+        FlashMessage::error("The selected list does not exist.").send();
+        return Ok(see_other("/admin/imports"));
+    }
+    let import_id = create_import(&pool, *form.list_id, mode, source, &rows)
+        .await
+        .map_err(e500)?;
+    Ok(see_other(&format!("/admin/imports/{}", import_id)))
+}
+
+#[tracing::instrument(name = "Start a subscriber import", skip(pool))]
+pub async fn start_import(
+    import_id: web::Path<Uuid>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let import_id = import_id.into_inner();
+    let result = sqlx::query!(
+        r#"UPDATE subscriber_imports SET status = 'queued' WHERE import_id = $1 AND status = 'draft'"#,
+        import_id
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    if result.rows_affected() == 0 {
+        FlashMessage::error("This import has already been started.").send();
+    } else {
+        FlashMessage::info("The import has been started. Subscribers are added in the background.").send();
+    }
+    Ok(see_other(&format!("/admin/imports/{}", import_id)))
+}
@@ -0,0 +1,293 @@
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::IncomingFlashMessages;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::fmt::Write;
+use uuid::Uuid;
+use crate::archive::Visibility;
+use crate::utils::e500;
+
+//20261019 추가 / 발행한 이슈와 열람 통계
+pub struct IssueSummary {
+    pub newsletter_issue_id: Uuid,
+    pub title: String,
+    pub published_at: DateTime<Utc>,
+    pub track_opens: bool,
+    pub recipient_count: i32,
+    pub pending_deliveries: i64,
+    pub unique_opens: i64,
+    pub total_opens: i64,
+    //20261019 추가
+    pub slug: String,
+    pub visibility: String,
+    pub status: String,
+    pub scheduled_for: Option<DateTime<Utc>>,
+    pub digest_recipient_count: i32,
+    pub pending_digest_deliveries: i64
+}
+
+impl IssueSummary {
+    //20261019 추가 / 피드 감시 작업이 만든 초안과 예약 이슈는 아직 발송되지 않았다.
+    fn is_held(&self) -> bool {
+        matches!(self.status.as_str(), "draft" | "scheduled")
+    }
+
+    fn status_label(&self) -> String {
+        match (self.status.as_str(), self.scheduled_for) {
+            ("draft", _) => "Draft".to_string(),
+            //20261019 추가 / 환영 이메일 시퀀스의 단계는 구독자마다 따로 발송된다.
+            ("sequence", _) => "Sequence step".to_string(),
+            ("scheduled", Some(scheduled_for)) => format!("Scheduled for {}", scheduled_for.format("%Y-%m-%d %H:%M")),
+            _ => self.published_at.format("%Y-%m-%d %H:%M").to_string()
+        }
+    }
+
+    //발행할 때 큐에 적재한 구독자 수 대비 한 번 이상 연 구독자의 비율
+    fn open_rate(&self) -> String {
+        if !self.track_opens {
+            return "Not tracked".to_string();
+        }
+        if self.recipient_count == 0 {
+            return "-".to_string();
+        }
+        format!("{:.1}%", self.unique_opens as f64 * 100.0 / self.recipient_count as f64)
+    }
+}
+
+pub async fn issues_page(
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut rows_html = String::new();
+    for issue in get_issues(&pool, None).await.map_err(e500)? {
+        writeln!(
+            rows_html,
+            r#"<tr><td><a href="/admin/issues/{}">{}</a></td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+            issue.newsletter_issue_id,
+            htmlescape::encode_minimal(&issue.title),
+            issue.status_label(),
+            issue.recipient_count,
+            issue.open_rate()
+        ).unwrap();
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Published issues</title>
+</head>
+<body>
+    <table>
+        <tr><th>Title</th><th>Published</th><th>Recipients</th><th>Open rate</th></tr>
+        {rows_html}
+    </table>
+    <p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
+
+//이미지 프록시는 구독자 대신 이미지를 미리 가져오므로 열람 수는 추정치다. 기기 분류별로 나눠 보여준다.
+pub async fn issue_report(
+    issue_id: web::Path<Uuid>,
+    //20261019 추가
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let issue_id = issue_id.into_inner();
+    let issue = match get_issues(&pool, Some(issue_id)).await.map_err(e500)?.pop() {
+        Some(issue) => issue,
+        None => return Ok(HttpResponse::NotFound().finish())
+    };
+    let mut breakdown_html = String::new();
+    for row in get_open_breakdown(&pool, issue_id).await.map_err(e500)? {
+        writeln!(
+            breakdown_html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td></tr>",
+            row.user_agent_class, row.unique_opens, row.total_opens
+        ).unwrap();
+    }
+    //20261019 추가 / 링크별 클릭
+    let mut links_html = String::new();
+    for link in get_link_clicks(&pool, issue_id).await.map_err(e500)? {
+        let url = htmlescape::encode_minimal(&link.url);
+        writeln!(
+            links_html,
+            r#"<tr><td><a href="{url}">{url}</a></td><td>{}</td><td>{}</td></tr>"#,
+            link.unique_clicks, link.total_clicks
+        ).unwrap();
+    }
+    //20261019 추가 / 웹 아카이브 공개 범위
+    let mut msg_html = String::new();
+    for m in flash_messages.iter() {
+        writeln!(msg_html, "<p><i>{}</i></p>", m.content()).unwrap();
+    }
+    let mut visibility_html = String::new();
+    for visibility in Visibility::ALL {
+        let selected = if issue.visibility == visibility.as_str() { " selected" } else { "" };
+        writeln!(
+            visibility_html,
+            r#"<option value="{}"{selected}>{}</option>"#,
+            visibility.as_str(),
+            visibility.label()
+        ).unwrap();
+    }
+    //20261019 추가 / 초안과 예약 이슈는 바로 발송할 수 있다.
+    let send_html = if issue.is_held() {
+        format!(
+            r#"<form action="/admin/issues/{issue_id}/send" method="post">
+        <button type="submit">Send now to the default list</button>
+    </form>"#
+        )
+    } else {
+        String::new()
+    };
+    let tracking_html = if issue.track_opens {
+        ""
+    } else {
+        "<p>Open tracking was not enabled for this issue.</p>"
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Issue report</title>
+</head>
+<body>
+    {msg_html}
+    <p>{title} / {status}</p>
+    {send_html}
+    <form action="/admin/issues/{issue_id}/visibility" method="post">
+        <label>Web archive (<a href="/archive/{slug}">/archive/{slug}</a>):
+            <select name="visibility">{visibility_html}</select>
+        </label>
+        <button type="submit">Save</button>
+    </form>
+    {tracking_html}
+    <ul>
+        <li>Recipients: {recipients}</li>
+        <li>Pending deliveries: {pending}</li>
+        <li>Weekly digest recipients: {digest_recipients}</li>
+        <li>Waiting for the weekly digest: {pending_digests}</li>
+        <li>Unique opens: {unique_opens}</li>
+        <li>Total opens: {total_opens}</li>
+        <li>Open rate: {open_rate}</li>
+    </ul>
+    <table>
+        <tr><th>Client</th><th>Unique opens</th><th>Total opens</th></tr>
+        {breakdown_html}
+    </table>
+    <table>
+        <tr><th>Link</th><th>Unique clicks</th><th>Total clicks</th></tr>
+        {links_html}
+    </table>
+    <p><a href="/admin/issues">&lt;- Back</a></p>
+</body>
+</html>"#,
+            title = htmlescape::encode_minimal(&issue.title),
+            slug = htmlescape::encode_minimal(&urlencoding::encode(&issue.slug)),
+            status = if issue.status == "published" { format!("Published: {}", issue.status_label()) } else { issue.status_label() },
+            recipients = issue.recipient_count,
+            pending = issue.pending_deliveries,
+            digest_recipients = issue.digest_recipient_count,
+            pending_digests = issue.pending_digest_deliveries,
+            unique_opens = issue.unique_opens,
+            total_opens = issue.total_opens,
+            open_rate = issue.open_rate()
+        )))
+}
+
+#[tracing::instrument(skip(pool))]
+async fn get_issues(
+    pool: &PgPool,
+    issue_id: Option<Uuid>
+) -> Result<Vec<IssueSummary>, sqlx::Error> {
+    sqlx::query_as!(
+        IssueSummary,
+        r#"
+        SELECT i.newsletter_issue_id, i.title, i.published_at, i.track_opens, i.recipient_count,
+            (SELECT COUNT(*) FROM issue_delivery_queue q
+             WHERE q.newsletter_issue_id = i.newsletter_issue_id) AS "pending_deliveries!",
+            (SELECT COUNT(*) FROM issue_opens o
+             WHERE o.newsletter_issue_id = i.newsletter_issue_id) AS "unique_opens!",
+            (SELECT COALESCE(SUM(o.open_count), 0) FROM issue_opens o
+             WHERE o.newsletter_issue_id = i.newsletter_issue_id) AS "total_opens!",
+            i.slug, i.visibility, i.status, i.scheduled_for, i.digest_recipient_count,
+            (SELECT COUNT(*) FROM digest_queue d
+             WHERE d.newsletter_issue_id = i.newsletter_issue_id) AS "pending_digest_deliveries!"
+        FROM newsletter_issues i
+        WHERE ($1::uuid IS NULL AND i.status <> 'sequence') OR i.newsletter_issue_id = $1
+        ORDER BY i.published_at DESC
+        LIMIT 50
+        "#,
+        issue_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+struct OpenBreakdown {
+    user_agent_class: String,
+    unique_opens: i64,
+    total_opens: i64
+}
+
+struct LinkClicks {
+    url: String,
+    unique_clicks: i64,
+    total_clicks: i64
+}
+
+//클릭이 없는 링크도 보여준다. 클릭이 많은 링크부터.
+#[tracing::instrument(skip(pool))]
+async fn get_link_clicks(
+    pool: &PgPool,
+    issue_id: Uuid
+) -> Result<Vec<LinkClicks>, sqlx::Error> {
+    sqlx::query_as!(
+        LinkClicks,
+        r#"
+        SELECT l.url,
+            COUNT(c.subscriber_id) AS "unique_clicks!",
+            COALESCE(SUM(c.click_count), 0) AS "total_clicks!"
+        FROM issue_links l
+        LEFT JOIN link_clicks c ON c.link_id = l.link_id
+        WHERE l.newsletter_issue_id = $1
+        GROUP BY l.link_id, l.url
+        ORDER BY 2 DESC, l.url
+        "#,
+        issue_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(skip(pool))]
+async fn get_open_breakdown(
+    pool: &PgPool,
+    issue_id: Uuid
+) -> Result<Vec<OpenBreakdown>, sqlx::Error> {
+    sqlx::query_as!(
+        OpenBreakdown,
+        r#"
+        SELECT user_agent_class,
+            COUNT(*) AS "unique_opens!",
+            COALESCE(SUM(open_count), 0) AS "total_opens!"
+        FROM issue_opens
+        WHERE newsletter_issue_id = $1
+        GROUP BY user_agent_class
+        ORDER BY user_agent_class
+        "#,
+        issue_id
+    )
+    .fetch_all(pool)
+    .await
+}
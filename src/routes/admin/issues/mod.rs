@@ -0,0 +1,5 @@
+mod get;
+mod post;
+
+pub use get::{issue_report, issues_page};
+pub use post::{send_issue, update_issue_visibility};
@@ -0,0 +1,57 @@
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::archive::{set_visibility, Visibility};
+use crate::utils::{e500, see_other};
+use crate::audience::send_held_issue;
+use anyhow::Context;
+
+#[derive(serde::Deserialize)]
+pub struct VisibilityForm {
+    visibility: String
+}
+
+//20261019 추가 / 발행한 뒤에 웹 아카이브 공개 범위를 바꾼다. 이미 발송된 이메일의 링크에도 바로 적용된다.
+#[tracing::instrument(name = "Change the visibility of an issue", skip(form, pool))]
+pub async fn update_issue_visibility(
+    issue_id: web::Path<Uuid>,
+    form: web::Form<VisibilityForm>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let issue_id = issue_id.into_inner();
+    let report_path = format!("/admin/issues/{}", issue_id);
+    let visibility = match Visibility::parse(&form.visibility) {
+        Ok(visibility) => visibility,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other(&report_path));
+        }
+    };
+    if !set_visibility(&pool, issue_id, visibility).await.map_err(e500)? {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    FlashMessage::info(format!("The issue is now {} on the web archive.", visibility.label().to_lowercase())).send();
+    Ok(see_other(&report_path))
+}
+
+//20261019 추가 / 피드 감시 작업이 만든 초안이나 예약 이슈를 바로 기본 리스트로 발송한다.
+#[tracing::instrument(name = "Send a held issue", skip(pool))]
+pub async fn send_issue(
+    issue_id: web::Path<Uuid>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let issue_id = issue_id.into_inner();
+    let mut transaction = pool.begin().await.map_err(e500)?;
+    let sent = send_held_issue(&mut transaction, issue_id)
+        .await
+        .context("Failed to enqueue the held issue")
+        .map_err(e500)?;
+    transaction.commit().await.map_err(e500)?;
+    if sent {
+        FlashMessage::info("The issue has been accepted - emails will go out shortly.").send();
+    } else {
+        FlashMessage::error("The issue has already been sent.").send();
+    }
+    Ok(see_other(&format!("/admin/issues/{}", issue_id)))
+}
@@ -0,0 +1,5 @@
+mod get;
+mod post;
+
+pub use get::{lists_page, get_lists, MailingList};
+pub use post::create_list;
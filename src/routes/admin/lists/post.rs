@@ -0,0 +1,50 @@
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::ListSlug;
+use crate::utils::{e500, see_other};
+
+#[derive(serde::Deserialize)]
+pub struct FormData {
+    name: String,
+    slug: String
+}
+
+#[tracing::instrument(name = "Create a mailing list", skip(form, pool))]
+pub async fn create_list(
+    form: web::Form<FormData>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let FormData { name, slug } = form.0;
+    let slug = match ListSlug::parse(slug) {
+        Ok(slug) => slug,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other("/admin/lists"));
+        }
+    };
+    if name.trim().is_empty() {
+        FlashMessage::error("The list name must not be empty.").send();
+        return Ok(see_other("/admin/lists"));
+    }
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO lists (list_id, slug, name)
+        VALUES ($1, $2, $3)
+        ON CONFLICT (slug) DO NOTHING
+        "#,
+        Uuid::new_v4(),
+        slug.as_ref(),
+        name.trim()
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    if result.rows_affected() == 0 {
+        FlashMessage::error(format!("A list with the slug '{}' already exists.", slug.as_ref())).send();
+    } else {
+        FlashMessage::info(format!("The list '{}' has been created.", slug.as_ref())).send();
+    }
+    Ok(see_other("/admin/lists"))
+}
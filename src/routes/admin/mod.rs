@@ -2,8 +2,25 @@ mod dashboard;
 mod password;
 mod logout;
 mod newsletter;
+//20261019 추가
+mod lists;
+mod subscribers;
+mod fields;
+mod imports;
+mod data_requests;
+mod suppressions;
+mod issues;
+mod sequences;
 
 pub use dashboard::admin_dashboard;
 pub use password::*;
 pub use logout::log_out;
-pub use newsletter::*;
\ No newline at end of file
+pub use newsletter::*;
+pub use lists::*;
+pub use subscribers::*;
+pub use fields::*;
+pub use imports::*;
+pub use data_requests::*;
+pub use suppressions::*;
+pub use issues::*;
+pub use sequences::*;
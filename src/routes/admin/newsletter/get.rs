@@ -4,11 +4,21 @@ use actix_web_flash_messages::IncomingFlashMessages;
 use std::fmt::Write;
 //20250306 추가
 use crate::session_state::TypedSession;
+//20261019 추가
+use actix_web::web;
+use sqlx::PgPool;
+use crate::routes::admin::lists::get_lists;
+use crate::routes::DEFAULT_LIST_SLUG;
+use crate::utils::e500;
+use uuid::Uuid;
+use crate::archive::Visibility;
 
 //20250306 msg_html TypedSession에 insert / get 추가
 pub async fn publish_newsletter_form(
     flash_messages: IncomingFlashMessages,
-    session: TypedSession
+    session: TypedSession,
+    //20261019 추가
+    pool: web::Data<PgPool>
 ) -> Result<HttpResponse, actix_web::Error> {
     let mut msg_html = String::new();
     for m in flash_messages.iter() {
@@ -21,10 +31,73 @@ pub async fn publish_newsletter_form(
     let flash_message = session.get_flash_message().expect("Failed to get_flash_message");
     let msg_html = flash_message.unwrap_or_default();
 
-    let idempotency_key = uuid::Uuid::new_v4();
+    let body = render_publish_form(&pool, &msg_html, &PublishFormValues::default()).await.map_err(e500)?;
     Ok(HttpResponse::Ok()
         .content_type(ContentType::html())
-        .body(format!(
+        .body(body))
+}
+
+//20261019 추가 / 수신자 수 미리보기나 잘못된 세그먼트로 폼을 다시 보여줄 때 입력한 값을 유지하기 위해 사용한다.
+#[derive(Default)]
+pub struct PublishFormValues {
+    pub title: String,
+    pub text_content: String,
+    pub html_content: String,
+    pub lists: Vec<Uuid>,
+    pub segment: String,
+    //20261019 추가
+    pub track_opens: bool,
+    pub utm_tagging: bool,
+    pub utm_campaign: String,
+    pub visibility: String,
+    pub idempotency_key: Option<String>
+}
+
+pub async fn render_publish_form(
+    pool: &PgPool,
+    msg_html: &str,
+    values: &PublishFormValues
+) -> Result<String, sqlx::Error> {
+    //20261019 추가 / 이슈를 받을 리스트를 선택한다. 선택된 리스트가 없으면 기본 리스트를 미리 선택한다.
+    let mut lists_html = String::new();
+    for list in get_lists(pool).await? {
+        let checked = if values.lists.is_empty() {
+            list.slug == DEFAULT_LIST_SLUG
+        } else {
+            values.lists.contains(&list.list_id)
+        };
+        let checked = if checked { " checked" } else { "" };
+        writeln!(
+            lists_html,
+            r#"<label><input type="checkbox" name="lists" value="{}"{checked}> {}</label><br>"#,
+            list.list_id,
+            htmlescape::encode_minimal(&list.name)
+        ).unwrap();
+    }
+
+    let idempotency_key = values
+        .idempotency_key
+        .clone()
+        .unwrap_or_else(|| Uuid::new_v4().to_string());
+    let idempotency_key = htmlescape::encode_attribute(&idempotency_key);
+    let title = htmlescape::encode_attribute(&values.title);
+    let text_content = htmlescape::encode_minimal(&values.text_content);
+    let html_content = htmlescape::encode_minimal(&values.html_content);
+    let segment = htmlescape::encode_minimal(&values.segment);
+    let track_opens = if values.track_opens { " checked" } else { "" };
+    let utm_tagging = if values.utm_tagging { " checked" } else { "" };
+    let utm_campaign = htmlescape::encode_attribute(&values.utm_campaign);
+    let mut visibility_html = String::new();
+    for visibility in Visibility::ALL {
+        let selected = if values.visibility == visibility.as_str() { " selected" } else { "" };
+        writeln!(
+            visibility_html,
+            r#"<option value="{}"{selected}>{}</option>"#,
+            visibility.as_str(),
+            visibility.label()
+        ).unwrap();
+    }
+    Ok(format!(
             r#"<!DOCTYPE html>
 <html lang="en">
 <head>
@@ -35,22 +108,43 @@ pub async fn publish_newsletter_form(
     {msg_html}
     <form action="/admin/newsletters" method="post">
         <label>Title:<br>
-            <input type="text" placeholder="Enter the issue title" name="title">
+            <input type="text" placeholder="Enter the issue title" name="title" value="{title}">
         </label>
         <br>
         <label>Plain text content:<br>
-            <textarea placeholder="Enter the content in plain text" name="text_content" rows="20" cols="50"></textarea>
+            <textarea placeholder="Enter the content in plain text" name="text_content" rows="20" cols="50">{text_content}</textarea>
         </label>
         <br>
         <label>HTML content:<br>
-            <textarea placeholder="Enter the content in HTML format" name="html_content" rows="20" cols="50"></textarea>
+            <textarea placeholder="Enter the content in HTML format" name="html_content" rows="20" cols="50">{html_content}</textarea>
+        </label>
+        <br>
+        <fieldset>
+            <legend>Send to lists:</legend>
+            {lists_html}
+        </fieldset>
+        <p>Use {{{{ subscriber_name }}}} or {{{{ fields.&lt;key&gt; }}}} in the title and content to personalize the issue.</p>
+        <label>Segment (optional):<br>
+            <textarea placeholder="e.g. tag = beta AND subscribed_at >= now-30d" name="segment" rows="3" cols="50">{segment}</textarea>
+        </label>
+        <br>
+        <label><input type="checkbox" name="track_opens"{track_opens}> Track opens (not for subscribers who opted out)</label>
+        <br>
+        <label><input type="checkbox" name="utm_tagging"{utm_tagging}> Add UTM parameters to external links</label>
+        <label>Campaign:
+            <input type="text" placeholder="Defaults to the title" name="utm_campaign" value="{utm_campaign}">
+        </label>
+        <br>
+        <label>Web archive:
+            <select name="visibility">{visibility_html}</select>
         </label>
         <br>
         <input hidden type="text" name="idempotency_key" value="{idempotency_key}">
+        <button type="submit" formaction="/admin/newsletters/preview">Preview recipient count</button>
         <button type="submit">Publish</button>
     </form>
     <p><a href="/admin/dashboard">&lt;- Back</a></p>
 </body>
 </html>"#,
-        )))
-}
\ No newline at end of file
+        ))
+}
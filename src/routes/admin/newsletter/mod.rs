@@ -1,5 +1,8 @@
 mod get;
 mod post;
+//20261019 추가
+mod preview;
 
 pub use get::publish_newsletter_form;
-pub use post::publish_newsletter;
\ No newline at end of file
+pub use post::publish_newsletter;
+pub use preview::preview_recipient_count;
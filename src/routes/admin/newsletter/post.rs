@@ -16,15 +16,85 @@ use crate::idempotency::{try_processing, NextAction};
 //20250314 추가
 use sqlx::{Postgres, Transaction};
 use uuid::Uuid;
+//20261019 추가
+use crate::html_form::HtmlForm;
+use crate::audience::{enqueue_delivery_tasks, get_default_list_ids};
+use crate::segment::Segment;
+use super::get::{render_publish_form, PublishFormValues};
+use actix_web::http::header::ContentType;
+use crate::custom_fields::get_field_definitions;
+use crate::email_templates::{validate_issue_content, IssueContent};
+use crate::utm::campaign_slug;
+use crate::archive::{unique_issue_slug, Visibility};
 
 #[derive(serde::Deserialize)]
 pub struct FormData {
-    title: String,
-    text_content: String,
-    html_content: String,
+    pub title: String,
+    pub text_content: String,
+    pub html_content: String,
     //20250305 추가 / 멱등성 키
-    idempotency_key: String
+    pub idempotency_key: String,
+    //20261019 추가 / 이슈를 받을 리스트. 선택하지 않으면 기본 리스트로 발송한다.
+    #[serde(default)]
+    pub lists: Vec<Uuid>,
+    //20261019 추가 / 리스트 멤버 중 일부에게만 보내기 위한 세그먼트. 비어 있으면 모두에게 보낸다.
+    #[serde(default)]
+    pub segment: String,
+    //20261019 추가 / 체크하면 이 이슈의 열람을 추적한다.
+    #[serde(default)]
+    pub track_opens: Option<String>,
+    //20261019 추가 / 체크하면 외부 링크에 UTM 파라미터를 붙인다. 캠페인 이름이 비어 있으면 제목으로 만든다.
+    #[serde(default)]
+    pub utm_tagging: Option<String>,
+    #[serde(default)]
+    pub utm_campaign: String,
+    //20261019 추가 / 웹 아카이브 공개 범위. 비어 있으면 공개한다.
+    #[serde(default)]
+    pub visibility: String
+}
+
+impl FormData {
+    //20261019 추가
+    pub fn parse_visibility(&self) -> Result<Visibility, String> {
+        match self.visibility.as_str() {
+            "" => Ok(Visibility::Public),
+            visibility => Visibility::parse(visibility)
+        }
+    }
 
+    //20261019 추가
+    pub fn utm_campaign(&self) -> Option<String> {
+        self.utm_tagging.as_ref()?;
+        match self.utm_campaign.trim() {
+            "" => Some(campaign_slug(&self.title)),
+            campaign => Some(campaign.to_string())
+        }
+    }
+
+    pub fn parse_segment(&self) -> Result<Option<Segment>, String> {
+        if self.segment.trim().is_empty() {
+            Ok(None)
+        } else {
+            Segment::parse(&self.segment).map(Some)
+        }
+    }
+}
+
+impl From<FormData> for PublishFormValues {
+    fn from(form: FormData) -> Self {
+        Self {
+            title: form.title,
+            text_content: form.text_content,
+            html_content: form.html_content,
+            lists: form.lists,
+            segment: form.segment,
+            track_opens: form.track_opens.is_some(),
+            utm_tagging: form.utm_tagging.is_some(),
+            utm_campaign: form.utm_campaign,
+            visibility: form.visibility,
+            idempotency_key: Some(form.idempotency_key)
+        }
+    }
 }
 
 //20250314 수정 / 오류처리
@@ -34,15 +104,36 @@ pub struct FormData {
     fields(user_id=%&*user_id)
 )]
 pub async fn publish_newsletter(
-    form: web::Form<FormData>,
+    //20261019 수정 / 여러 리스트를 선택할 수 있도록 'HtmlForm'을 사용한다.
+    form: HtmlForm<FormData>,
     //사용자 세션에서 추출한 사용자 id를 주입한다.
     user_id: ReqData<UserId>,
     pool: web::Data<PgPool>,
     //email_client: web::Data<EmailClient>
 ) -> Result<HttpResponse, actix_web::Error> {
     let user_id = user_id.into_inner();
+    //20261019 추가 / 잘못된 세그먼트나 개인화 플레이스홀더는 입력한 내용을 유지한 채로 폼을 다시 보여준다.
+    let segment = match form.0.parse_segment() {
+        Ok(segment) => segment,
+        Err(e) => return reject_form(&pool, &format!("Invalid segment: {}", e), form.0).await
+    };
+    let visibility = match form.0.parse_visibility() {
+        Ok(visibility) => visibility,
+        Err(e) => return reject_form(&pool, &e, form.0).await
+    };
+    let field_definitions = get_field_definitions(pool.get_ref()).await.map_err(e500)?;
+    let field_keys: Vec<String> = field_definitions.into_iter().map(|d| d.key).collect();
+    let content = IssueContent {
+        title: &form.0.title,
+        html_content: &form.0.html_content,
+        text_content: &form.0.text_content
+    };
+    if let Err(e) = validate_issue_content(&content, &field_keys) {
+        return reject_form(&pool, &format!("Invalid placeholder: {}", e), form.0).await;
+    }
     //차용 검사기가 오류를 발생하지 않도록 폼을 제거해야 한다.
-    let FormData {title, text_content, html_content, idempotency_key} = form.0;
+    let utm_campaign = form.0.utm_campaign();
+    let FormData {title, text_content, html_content, idempotency_key, lists, track_opens, ..} = form.0;
     let idempotency_key: IdempotencyKey = idempotency_key.try_into().map_err(e400)?;
     //20250310 추가 / 요청을 처리한 뒤 idempotency테이블에 행을 삽입 후 즉시 호출자에게 반환 하기위한 처리
     let mut transaction = match try_processing(&pool, &idempotency_key, *user_id).await.map_err(e500)? {
@@ -52,11 +143,21 @@ pub async fn publish_newsletter(
             return Ok(saved_response);
         }
     };
-    let issue_id = insert_newsletter_issue(&mut transaction, &title, &text_content, &html_content)
+    let issue_id = insert_newsletter_issue(
+        &mut transaction, &title, &text_content, &html_content, track_opens.is_some(), utm_campaign.as_deref(), visibility
+    )
         .await
         .context("Failed to store newsletter issue details")
         .map_err(e500)?;
-    enqueue_delivery_tasks(&mut transaction, issue_id)
+    let list_ids = if lists.is_empty() {
+        get_default_list_ids(&mut transaction)
+            .await
+            .context("Failed to look up the default list")
+            .map_err(e500)?
+    } else {
+        lists
+    };
+    enqueue_delivery_tasks(&mut transaction, issue_id, &list_ids, segment.as_ref())
         .await
         .context("Failed to enqueue delivery tasks")
         .map_err(e500)?;
@@ -66,6 +167,15 @@ pub async fn publish_newsletter(
     Ok(response)
 }
 
+//20261019 추가
+async fn reject_form(pool: &PgPool, message: &str, form: FormData) -> Result<HttpResponse, actix_web::Error> {
+    let msg_html = format!("<p><i>{}</i></p>", htmlescape::encode_minimal(message));
+    let body = render_publish_form(pool, &msg_html, &form.into()).await.map_err(e500)?;
+    Ok(HttpResponse::BadRequest()
+        .content_type(ContentType::html())
+        .body(body))
+}
+
 //20250310 추가
 fn success_message() -> FlashMessage {
     FlashMessage::info("The nesletter issue has been accepted - emails will go out shortly.")
@@ -76,45 +186,35 @@ async fn insert_newsletter_issue(
     transaction: &mut Transaction<'_, Postgres>,
     title: &str,
     text_content: &str,
-    html_content: &str
+    html_content: &str,
+    //20261019 추가
+    track_opens: bool,
+    utm_campaign: Option<&str>,
+    visibility: Visibility
 ) -> Result<Uuid, sqlx::Error> {
     let newsletter_issue_id = Uuid::new_v4();
+    //20261019 추가 / 웹 아카이브 주소
+    let slug = unique_issue_slug(&mut *transaction, title).await?;
     sqlx::query!(
         r#"
         INSERT INTO newsletter_issues (
-            newsletter_issue_id, title, text_content, html_content, published_at
+            newsletter_issue_id, title, text_content, html_content, published_at, track_opens, utm_campaign,
+            slug, visibility
         )
         VALUES (
-            $1, $2, $3, $4, now()
+            $1, $2, $3, $4, now(), $5, $6, $7, $8
         )
         "#,
         newsletter_issue_id,
         title,
         text_content,
-        html_content
+        html_content,
+        track_opens,
+        utm_campaign,
+        slug,
+        visibility.as_str()
     )
     .execute(transaction)
     .await?;
     Ok(newsletter_issue_id)
 }
-
-#[tracing::instrument(skip_all)]
-async fn enqueue_delivery_tasks(
-    transaction: &mut Transaction<'_, Postgres>,
-    newsletter_issue_id: Uuid
-) -> Result<(), sqlx::Error> {
-    sqlx::query!(
-        r#"
-        INSERT INTO issue_delivery_queue (
-            newsletter_issue_id, subscriber_email
-        )
-        SELECT $1, email
-        FROM subscriptions
-        WHERE status = 'confirmed'
-        "#,
-        newsletter_issue_id
-    )
-    .execute(transaction)
-    .await?;
-    Ok(())
-}
\ No newline at end of file
@@ -0,0 +1,42 @@
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+use anyhow::Context;
+use sqlx::PgPool;
+use super::get::render_publish_form;
+use super::post::FormData;
+use crate::audience::get_default_list_ids;
+use crate::audience::count_recipients;
+use crate::html_form::HtmlForm;
+use crate::utils::e500;
+
+//20261019 추가 / 발행하기 전에 선택한 리스트와 세그먼트에 해당하는 수신자 수를 보여준다.
+//입력한 내용을 잃지 않도록 같은 값으로 발행 폼을 다시 보여준다.
+#[tracing::instrument(name = "Preview the recipient count of an issue", skip_all)]
+pub async fn preview_recipient_count(
+    form: HtmlForm<FormData>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let form = form.0;
+    let msg_html = match form.parse_segment() {
+        Ok(segment) => {
+            let list_ids = if form.lists.is_empty() {
+                get_default_list_ids(pool.get_ref())
+                    .await
+                    .context("Failed to look up the default list")
+                    .map_err(e500)?
+            } else {
+                form.lists.clone()
+            };
+            let count = count_recipients(&pool, &list_ids, segment.as_ref())
+                .await
+                .context("Failed to count the recipients")
+                .map_err(e500)?;
+            format!("<p><i>This issue would be sent to {} subscriber(s).</i></p>", count)
+        }
+        Err(e) => format!("<p><i>Invalid segment: {}</i></p>", htmlescape::encode_minimal(&e))
+    };
+    let body = render_publish_form(&pool, &msg_html, &form.into()).await.map_err(e500)?;
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(body))
+}
@@ -45,7 +45,7 @@ pub async fn change_password(
                 FlashMessage::error("The current password is incorrect.").send();
                 Ok(see_other("/admin/password"))
             }
-            AuthError::UnexpectError(_) => Err(e500(e).into())
+            AuthError::UnexpectError(_) => Err(e500(e))
         }
     }
     crate::authentication::change_password(*user_id, form.0.new_password, &pool).await.map_err(e500)?;
@@ -0,0 +1,236 @@
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::IncomingFlashMessages;
+use sqlx::PgPool;
+use std::fmt::Write;
+use uuid::Uuid;
+use crate::routes::admin::lists::get_lists;
+use crate::utils::e500;
+
+//20261019 추가 / 환영 이메일 시퀀스
+pub struct SequenceSummary {
+    pub sequence_id: Uuid,
+    pub name: String,
+    pub list_name: String,
+    pub active: bool,
+    pub steps: i64,
+    pub active_enrollments: i64,
+    pub completed_enrollments: i64,
+    pub stopped_enrollments: i64
+}
+
+fn flash_html(flash_messages: &IncomingFlashMessages) -> String {
+    let mut msg_html = String::new();
+    for m in flash_messages.iter() {
+        writeln!(msg_html, "<p><i>{}</i></p>", m.content()).unwrap();
+    }
+    msg_html
+}
+
+pub async fn sequences_page(
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let msg_html = flash_html(&flash_messages);
+    let mut rows_html = String::new();
+    for sequence in get_sequences(&pool, None).await.map_err(e500)? {
+        writeln!(
+            rows_html,
+            r#"<tr><td><a href="/admin/sequences/{}">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+            sequence.sequence_id,
+            htmlescape::encode_minimal(&sequence.name),
+            htmlescape::encode_minimal(&sequence.list_name),
+            sequence.steps,
+            sequence.active_enrollments,
+            if sequence.active { "Active" } else { "Inactive" }
+        ).unwrap();
+    }
+    let mut lists_html = String::new();
+    for list in get_lists(&pool).await.map_err(e500)? {
+        writeln!(
+            lists_html,
+            r#"<option value="{}">{}</option>"#,
+            list.list_id,
+            htmlescape::encode_minimal(&list.name)
+        ).unwrap();
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Welcome sequences</title>
+</head>
+<body>
+    {msg_html}
+    <table>
+        <tr><th>Name</th><th>List</th><th>Steps</th><th>Active enrollments</th><th>Status</th></tr>
+        {rows_html}
+    </table>
+    <p>Subscribers are enrolled when they confirm their subscription to the list.</p>
+    <form action="/admin/sequences" method="post">
+        <label>Name
+            <input type="text" placeholder="e.g. Welcome" name="name">
+        </label>
+        <label>List
+            <select name="list_id">{lists_html}</select>
+        </label>
+        <button type="submit">Create sequence</button>
+    </form>
+    <p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
+
+struct SequenceStep {
+    delay_days: i32,
+    newsletter_issue_id: Uuid,
+    title: String,
+    enqueued: i64
+}
+
+pub async fn sequence_details(
+    sequence_id: web::Path<Uuid>,
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let sequence_id = sequence_id.into_inner();
+    let sequence = match get_sequences(&pool, Some(sequence_id)).await.map_err(e500)?.pop() {
+        Some(sequence) => sequence,
+        None => return Ok(HttpResponse::NotFound().finish())
+    };
+    let msg_html = flash_html(&flash_messages);
+    let mut steps_html = String::new();
+    for step in get_steps(&pool, sequence_id).await.map_err(e500)? {
+        writeln!(
+            steps_html,
+            r#"<tr><td>Day {}</td><td><a href="/admin/issues/{}">{}</a></td><td>{}</td></tr>"#,
+            step.delay_days,
+            step.newsletter_issue_id,
+            htmlescape::encode_minimal(&step.title),
+            step.enqueued
+        ).unwrap();
+    }
+    let (status, toggle_label, toggle_value) = if sequence.active {
+        ("Active", "Deactivate", "false")
+    } else {
+        ("Inactive", "Activate", "true")
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Welcome sequence</title>
+</head>
+<body>
+    {msg_html}
+    <p>{name} / List: {list_name} / {status}</p>
+    <form action="/admin/sequences/{sequence_id}/active" method="post">
+        <input hidden type="text" name="active" value="{toggle_value}">
+        <button type="submit">{toggle_label}</button>
+    </form>
+    <ul>
+        <li>Active enrollments: {active_enrollments}</li>
+        <li>Completed enrollments: {completed_enrollments}</li>
+        <li>Stopped enrollments: {stopped_enrollments}</li>
+    </ul>
+    <table>
+        <tr><th>Delay</th><th>Email</th><th>Sent</th></tr>
+        {steps_html}
+    </table>
+    <form action="/admin/sequences/{sequence_id}/steps" method="post">
+        <label>Days after confirmation:<br>
+            <input type="number" min="0" max="365" name="delay_days" value="0">
+        </label>
+        <br>
+        <label>Title:<br>
+            <input type="text" placeholder="Enter the email title" name="title">
+        </label>
+        <br>
+        <label>Plain text content:<br>
+            <textarea placeholder="Enter the content in plain text" name="text_content" rows="10" cols="50"></textarea>
+        </label>
+        <br>
+        <label>HTML content:<br>
+            <textarea placeholder="Enter the content in HTML format" name="html_content" rows="10" cols="50"></textarea>
+        </label>
+        <br>
+        <p>Use {{{{ subscriber_name }}}} or {{{{ fields.&lt;key&gt; }}}} to personalize the email.</p>
+        <button type="submit">Add step</button>
+    </form>
+    <p><a href="/admin/sequences">&lt;- Back</a></p>
+</body>
+</html>"#,
+            name = htmlescape::encode_minimal(&sequence.name),
+            list_name = htmlescape::encode_minimal(&sequence.list_name),
+            active_enrollments = sequence.active_enrollments,
+            completed_enrollments = sequence.completed_enrollments,
+            stopped_enrollments = sequence.stopped_enrollments
+        )))
+}
+
+//등록 중 모든 단계를 받은 것은 완료, 멈춘 것은 중지, 나머지는 진행 중이다.
+#[tracing::instrument(skip(pool))]
+async fn get_sequences(
+    pool: &PgPool,
+    sequence_id: Option<Uuid>
+) -> Result<Vec<SequenceSummary>, sqlx::Error> {
+    sqlx::query_as!(
+        SequenceSummary,
+        r#"
+        WITH progress AS (
+            SELECT e.sequence_id, e.stopped_at IS NOT NULL AS stopped,
+                NOT EXISTS (
+                    SELECT 1 FROM drip_steps st
+                    WHERE st.sequence_id = e.sequence_id
+                      AND NOT EXISTS (
+                        SELECT 1 FROM drip_deliveries d
+                        WHERE d.step_id = st.step_id AND d.subscriber_id = e.subscriber_id
+                      )
+                ) AS completed
+            FROM drip_enrollments e
+        )
+        SELECT q.sequence_id, q.name, l.name AS list_name, q.active,
+            (SELECT COUNT(*) FROM drip_steps st WHERE st.sequence_id = q.sequence_id) AS "steps!",
+            (SELECT COUNT(*) FROM progress p
+             WHERE p.sequence_id = q.sequence_id AND NOT p.stopped AND NOT p.completed) AS "active_enrollments!",
+            (SELECT COUNT(*) FROM progress p
+             WHERE p.sequence_id = q.sequence_id AND NOT p.stopped AND p.completed) AS "completed_enrollments!",
+            (SELECT COUNT(*) FROM progress p
+             WHERE p.sequence_id = q.sequence_id AND p.stopped) AS "stopped_enrollments!"
+        FROM drip_sequences q
+        JOIN lists l ON l.list_id = q.list_id
+        WHERE $1::uuid IS NULL OR q.sequence_id = $1
+        ORDER BY q.created_at
+        "#,
+        sequence_id
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(skip(pool))]
+async fn get_steps(pool: &PgPool, sequence_id: Uuid) -> Result<Vec<SequenceStep>, sqlx::Error> {
+    sqlx::query_as!(
+        SequenceStep,
+        r#"
+        SELECT st.delay_days, st.newsletter_issue_id, i.title,
+            (SELECT COUNT(*) FROM drip_deliveries d WHERE d.step_id = st.step_id) AS "enqueued!"
+        FROM drip_steps st
+        JOIN newsletter_issues i ON i.newsletter_issue_id = st.newsletter_issue_id
+        WHERE st.sequence_id = $1
+        ORDER BY st.delay_days, st.created_at
+        "#,
+        sequence_id
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,5 @@
+mod get;
+mod post;
+
+pub use get::{sequence_details, sequences_page};
+pub use post::{add_sequence_step, create_sequence, set_sequence_active};
@@ -0,0 +1,168 @@
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use anyhow::Context;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::archive::unique_issue_slug;
+use crate::custom_fields::get_field_definitions;
+use crate::email_templates::{validate_issue_content, IssueContent};
+use crate::utils::{e500, see_other};
+
+#[derive(serde::Deserialize)]
+pub struct SequenceForm {
+    name: String,
+    list_id: Uuid
+}
+
+//20261019 추가 / 환영 이메일 시퀀스
+#[tracing::instrument(name = "Create a drip sequence", skip(form, pool))]
+pub async fn create_sequence(
+    form: web::Form<SequenceForm>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let SequenceForm { name, list_id } = form.0;
+    if name.trim().is_empty() {
+        FlashMessage::error("The sequence name must not be empty.").send();
+        return Ok(see_other("/admin/sequences"));
+    }
+    let sequence_id = Uuid::new_v4();
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO drip_sequences (sequence_id, name, list_id)
+        SELECT $1, $2, list_id FROM lists WHERE list_id = $3
+        "#,
+        sequence_id,
+        name.trim(),
+        list_id
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    if result.rows_affected() == 0 {
+        FlashMessage::error("The list does not exist.").send();
+        return Ok(see_other("/admin/sequences"));
+    }
+    FlashMessage::info(format!(
+        "The sequence '{}' has been created.",
+        htmlescape::encode_minimal(name.trim())
+    )).send();
+    Ok(see_other(&format!("/admin/sequences/{}", sequence_id)))
+}
+
+#[derive(serde::Deserialize)]
+pub struct StepForm {
+    delay_days: i32,
+    title: String,
+    text_content: String,
+    html_content: String
+}
+
+//단계의 내용은 웹 아카이브와 피드에 나오지 않는 이슈로 저장해서 발송 워커와 이슈 보고서를 그대로 쓴다.
+#[tracing::instrument(name = "Add a drip sequence step", skip(form, pool))]
+pub async fn add_sequence_step(
+    sequence_id: web::Path<Uuid>,
+    form: web::Form<StepForm>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let sequence_id = sequence_id.into_inner();
+    let details_path = format!("/admin/sequences/{}", sequence_id);
+    let exists = sqlx::query_scalar!(
+        r#"SELECT EXISTS (SELECT 1 FROM drip_sequences WHERE sequence_id = $1) AS "exists!""#,
+        sequence_id
+    )
+    .fetch_one(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    if !exists {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    let StepForm { delay_days, title, text_content, html_content } = form.0;
+    if !(0..=365).contains(&delay_days) {
+        FlashMessage::error("The delay must be between 0 and 365 days.").send();
+        return Ok(see_other(&details_path));
+    }
+    if title.trim().is_empty() || text_content.trim().is_empty() || html_content.trim().is_empty() {
+        FlashMessage::error("The title and both contents must not be empty.").send();
+        return Ok(see_other(&details_path));
+    }
+    let field_definitions = get_field_definitions(pool.get_ref()).await.map_err(e500)?;
+    let field_keys: Vec<String> = field_definitions.into_iter().map(|d| d.key).collect();
+    let content = IssueContent {
+        title: &title,
+        html_content: &html_content,
+        text_content: &text_content
+    };
+    if let Err(e) = validate_issue_content(&content, &field_keys) {
+        FlashMessage::error(htmlescape::encode_minimal(&format!("Invalid placeholder: {}", e))).send();
+        return Ok(see_other(&details_path));
+    }
+    let mut transaction = pool.begin().await.map_err(e500)?;
+    let newsletter_issue_id = Uuid::new_v4();
+    let slug = unique_issue_slug(&mut transaction, &title).await.map_err(e500)?;
+    sqlx::query!(
+        r#"
+        INSERT INTO newsletter_issues (
+            newsletter_issue_id, title, text_content, html_content, published_at, slug, visibility, status
+        )
+        VALUES ($1, $2, $3, $4, now(), $5, 'hidden', 'sequence')
+        "#,
+        newsletter_issue_id,
+        title,
+        text_content,
+        html_content,
+        slug
+    )
+    .execute(&mut transaction)
+    .await
+    .context("Failed to store the sequence step content")
+    .map_err(e500)?;
+    sqlx::query!(
+        r#"
+        INSERT INTO drip_steps (step_id, sequence_id, delay_days, newsletter_issue_id)
+        VALUES ($1, $2, $3, $4)
+        "#,
+        Uuid::new_v4(),
+        sequence_id,
+        delay_days,
+        newsletter_issue_id
+    )
+    .execute(&mut transaction)
+    .await
+    .context("Failed to store the sequence step")
+    .map_err(e500)?;
+    transaction.commit().await.map_err(e500)?;
+    FlashMessage::info(format!("The day {} email has been added.", delay_days)).send();
+    Ok(see_other(&details_path))
+}
+
+#[derive(serde::Deserialize)]
+pub struct ActiveForm {
+    active: bool
+}
+
+//비활성 시퀀스는 새로 등록하지 않고, 등록된 구독자에게도 다시 활성화할 때까지 보내지 않는다.
+#[tracing::instrument(name = "Activate or deactivate a drip sequence", skip(form, pool))]
+pub async fn set_sequence_active(
+    sequence_id: web::Path<Uuid>,
+    form: web::Form<ActiveForm>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let sequence_id = sequence_id.into_inner();
+    let result = sqlx::query!(
+        r#"UPDATE drip_sequences SET active = $1 WHERE sequence_id = $2"#,
+        form.active,
+        sequence_id
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    if result.rows_affected() == 0 {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+    if form.active {
+        FlashMessage::info("The sequence has been activated.").send();
+    } else {
+        FlashMessage::info("The sequence has been deactivated.").send();
+    }
+    Ok(see_other(&format!("/admin/sequences/{}", sequence_id)))
+}
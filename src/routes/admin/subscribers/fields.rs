@@ -0,0 +1,62 @@
+use actix_web::{web, HttpRequest, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use serde_json::Value;
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::custom_fields::{get_field_definitions, validate_custom_fields};
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+use crate::utils::{e500, see_other};
+
+//20261019 추가 / 관리자가 구독자의 사용자 정의 필드를 수정한다.
+//정의된 필드의 값만 바꾸고, 정의가 삭제된 필드의 값은 그대로 둔다.
+#[tracing::instrument(name = "Update the custom fields of a subscriber", skip(form, pool, request))]
+pub async fn update_subscriber_fields(
+    subscriber_id: web::Path<Uuid>,
+    form: web::Form<HashMap<String, String>>,
+    pool: web::Data<PgPool>,
+    //20261019 추가
+    request: HttpRequest
+) -> Result<HttpResponse, actix_web::Error> {
+    let subscriber_id = subscriber_id.into_inner();
+    let location = format!("/admin/subscribers/{}", subscriber_id);
+    let definitions = get_field_definitions(pool.get_ref()).await.map_err(e500)?;
+    let values = match validate_custom_fields(&definitions, &form.0) {
+        Ok(values) => values,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other(&location));
+        }
+    };
+    let keys: Vec<String> = definitions.into_iter().map(|d| d.key).collect();
+    let mut transaction = pool.begin().await.map_err(e500)?;
+    let updated = sqlx::query!(
+        r#"
+        UPDATE subscriptions
+        SET custom_fields = (custom_fields - $2::text[]) || $3
+        WHERE id = $1
+        "#,
+        subscriber_id,
+        &keys,
+        Value::Object(values)
+    )
+    .execute(&mut transaction)
+    .await
+    .map_err(e500)?
+    .rows_affected();
+    //20261019 추가 / 관리자의 변경도 구독 이벤트 기록에 남긴다.
+    if updated == 1 {
+        record_event(
+            &mut transaction,
+            subscriber_id,
+            EventType::PreferencesChanged,
+            &EventContext::from_request(&request, EventSource::Admin),
+            serde_json::json!({ "changed": ["custom_fields"] })
+        )
+        .await
+        .map_err(e500)?;
+    }
+    transaction.commit().await.map_err(e500)?;
+    FlashMessage::info("The custom fields have been saved.").send();
+    Ok(see_other(&location))
+}
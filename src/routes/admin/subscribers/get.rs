@@ -0,0 +1,254 @@
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::IncomingFlashMessages;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::fmt::Write;
+use uuid::Uuid;
+use crate::utils::e500;
+use crate::custom_fields::{display_value, get_field_definitions, FieldType};
+use crate::subscription_events::get_events;
+
+//20261019 추가 / 관리자용 구독자 목록. 최근 구독자부터 보여준다.
+pub struct SubscriberSummary {
+    pub id: Uuid,
+    pub email: String,
+    pub name: String,
+    pub status: String,
+    pub subscribed_at: DateTime<Utc>,
+    pub tags: Vec<String>,
+    pub custom_fields: serde_json::Value
+}
+
+pub async fn subscribers_page(
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut rows_html = String::new();
+    for subscriber in get_subscribers(&pool).await.map_err(e500)? {
+        writeln!(
+            rows_html,
+            r#"<tr><td><a href="/admin/subscribers/{}">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>"#,
+            subscriber.id,
+            htmlescape::encode_minimal(&subscriber.email),
+            htmlescape::encode_minimal(&subscriber.name),
+            subscriber.status,
+            subscriber.subscribed_at.format("%Y-%m-%d"),
+            subscriber.tags.join(", ")
+        ).unwrap();
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Subscribers</title>
+</head>
+<body>
+    <table>
+        <tr><th>Email</th><th>Name</th><th>Status</th><th>Subscribed at</th><th>Tags</th></tr>
+        {rows_html}
+    </table>
+    <form action="/admin/subscribers/export" method="get">
+        <label>Format
+            <select name="format">
+                <option value="csv">CSV</option>
+                <option value="ndjson">NDJSON</option>
+            </select>
+        </label>
+        <label>Status
+            <select name="status">
+                <option value="">Any</option>
+                <option value="confirmed">Confirmed</option>
+                <option value="pending_confirmation">Pending confirmation</option>
+                <option value="unsubscribed">Unsubscribed</option>
+                <option value="bounced">Bounced</option>
+                <option value="complained">Complained</option>
+            </select>
+        </label>
+        <label>Segment
+            <input type="text" placeholder="e.g. tag = beta" name="segment">
+        </label>
+        <button type="submit">Export subscribers</button>
+    </form>
+    <p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
+
+pub async fn subscriber_details(
+    subscriber_id: web::Path<Uuid>,
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let subscriber = match get_subscriber(&pool, *subscriber_id).await.map_err(e500)? {
+        Some(subscriber) => subscriber,
+        None => return Ok(HttpResponse::NotFound().finish())
+    };
+    let mut msg_html = String::new();
+    for m in flash_messages.iter() {
+        writeln!(msg_html, "<p><i>{}</i></p>", m.content()).unwrap();
+    }
+    let mut tags_html = String::new();
+    for tag in &subscriber.tags {
+        writeln!(
+            tags_html,
+            r#"<li>{tag}
+            <form action="/admin/subscribers/{}/tags/delete" method="post" style="display: inline">
+                <input hidden type="text" name="tag" value="{tag}">
+                <button type="submit">Remove</button>
+            </form>
+        </li>"#,
+            subscriber.id
+        ).unwrap();
+    }
+    //20261019 추가 / 사용자 정의 필드 편집
+    let mut fields_html = String::new();
+    for definition in get_field_definitions(pool.get_ref()).await.map_err(e500)? {
+        let current = subscriber
+            .custom_fields
+            .get(&definition.key)
+            .map(|value| match (definition.field_type, value) {
+                (FieldType::Boolean, serde_json::Value::Bool(true)) => "yes".to_string(),
+                (FieldType::Boolean, serde_json::Value::Bool(false)) => "no".to_string(),
+                (_, value) => display_value(value)
+            })
+            .unwrap_or_default();
+        let options = match definition.field_type {
+            FieldType::Boolean => Some(vec!["yes".to_string(), "no".to_string()]),
+            FieldType::Choice => Some(definition.choices.clone()),
+            FieldType::Text | FieldType::Number => None
+        };
+        let input_html = match options {
+            Some(options) => {
+                let mut options_html = String::from(r#"<option value=""></option>"#);
+                for option in options {
+                    let selected = if option == current { " selected" } else { "" };
+                    write!(
+                        options_html,
+                        r#"<option value="{}"{selected}>{}</option>"#,
+                        htmlescape::encode_attribute(&option),
+                        htmlescape::encode_minimal(&option)
+                    ).unwrap();
+                }
+                format!(r#"<select name="{}">{options_html}</select>"#, definition.key)
+            }
+            None => format!(
+                r#"<input type="text" name="{}" value="{}">"#,
+                definition.key,
+                htmlescape::encode_attribute(&current)
+            )
+        };
+        writeln!(
+            fields_html,
+            "<label>{}{}<br>{input_html}</label><br>",
+            htmlescape::encode_minimal(&definition.label),
+            if definition.required { " *" } else { "" }
+        ).unwrap();
+    }
+    //20261019 추가 / 구독 이벤트 타임라인. 오래된 이벤트부터 보여준다.
+    let mut events_html = String::new();
+    for event in get_events(pool.get_ref(), subscriber.id).await.map_err(e500)? {
+        writeln!(
+            events_html,
+            "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+            event.occurred_at.to_rfc3339(),
+            event.event_type,
+            event.source,
+            htmlescape::encode_minimal(event.ip_address.as_deref().unwrap_or("")),
+            htmlescape::encode_minimal(event.user_agent.as_deref().unwrap_or("")),
+            htmlescape::encode_minimal(&event.details.to_string())
+        ).unwrap();
+    }
+    let id = subscriber.id;
+    let email = htmlescape::encode_minimal(&subscriber.email);
+    let name = htmlescape::encode_minimal(&subscriber.name);
+    let status = subscriber.status;
+    let subscribed_at = subscriber.subscribed_at.to_rfc3339();
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>{email}</title>
+</head>
+<body>
+    {msg_html}
+    <h1>{email}</h1>
+    <p>Name: {name}</p>
+    <p>Status: {status}</p>
+    <p>Subscribed at: {subscribed_at}</p>
+    <h2>Tags</h2>
+    <ul>
+        {tags_html}
+    </ul>
+    <form action="/admin/subscribers/{id}/tags" method="post">
+        <label>Tag
+            <input type="text" placeholder="e.g. beta" name="tag">
+        </label>
+        <button type="submit">Add tag</button>
+    </form>
+    <h2>Custom fields</h2>
+    <form action="/admin/subscribers/{id}/fields" method="post">
+        {fields_html}
+        <button type="submit">Save fields</button>
+    </form>
+    <h2>Timeline</h2>
+    <table>
+        <tr><th>Time</th><th>Event</th><th>Source</th><th>IP address</th><th>User agent</th><th>Details</th></tr>
+        {events_html}
+    </table>
+    <p><a href="/admin/subscribers">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
+
+#[tracing::instrument(skip_all)]
+async fn get_subscribers(pool: &PgPool) -> Result<Vec<SubscriberSummary>, sqlx::Error> {
+    sqlx::query_as!(
+        SubscriberSummary,
+        r#"
+        SELECT s.id, s.email, s.name, s.status, s.subscribed_at,
+            COALESCE(
+                (SELECT array_agg(t.tag ORDER BY t.tag) FROM subscriber_tags t WHERE t.subscriber_id = s.id),
+                '{}'
+            ) AS "tags!",
+            s.custom_fields
+        FROM subscriptions s
+        ORDER BY s.subscribed_at DESC
+        LIMIT 100
+        "#
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[tracing::instrument(skip(pool))]
+async fn get_subscriber(
+    pool: &PgPool,
+    subscriber_id: Uuid
+) -> Result<Option<SubscriberSummary>, sqlx::Error> {
+    sqlx::query_as!(
+        SubscriberSummary,
+        r#"
+        SELECT s.id, s.email, s.name, s.status, s.subscribed_at,
+            COALESCE(
+                (SELECT array_agg(t.tag ORDER BY t.tag) FROM subscriber_tags t WHERE t.subscriber_id = s.id),
+                '{}'
+            ) AS "tags!",
+            s.custom_fields
+        FROM subscriptions s
+        WHERE s.id = $1
+        "#,
+        subscriber_id
+    )
+    .fetch_optional(pool)
+    .await
+}
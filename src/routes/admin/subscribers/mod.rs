@@ -0,0 +1,10 @@
+mod get;
+mod tags;
+//20261019 추가
+mod fields;
+mod export;
+
+pub use get::{subscriber_details, subscribers_page};
+pub use tags::{add_subscriber_tag, remove_subscriber_tag};
+pub use fields::update_subscriber_fields;
+pub use export::export_subscribers;
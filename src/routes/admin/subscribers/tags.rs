@@ -0,0 +1,64 @@
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::domain::SubscriberTag;
+use crate::utils::{e500, see_other};
+
+#[derive(serde::Deserialize)]
+pub struct FormData {
+    tag: String
+}
+
+//20261019 추가 / 구독자에게 태그를 붙인다. 이미 붙어 있는 태그는 무시한다.
+#[tracing::instrument(name = "Tag a subscriber", skip(form, pool))]
+pub async fn add_subscriber_tag(
+    subscriber_id: web::Path<Uuid>,
+    form: web::Form<FormData>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let subscriber_id = subscriber_id.into_inner();
+    let location = format!("/admin/subscribers/{}", subscriber_id);
+    let tag = match SubscriberTag::parse(form.0.tag) {
+        Ok(tag) => tag,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other(&location));
+        }
+    };
+    //존재하지 않는 구독자에게는 태그를 붙이지 않는다.
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO subscriber_tags (subscriber_id, tag)
+        SELECT id, $2 FROM subscriptions WHERE id = $1
+        ON CONFLICT DO NOTHING
+        "#,
+        subscriber_id,
+        tag.as_ref()
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    if result.rows_affected() == 1 {
+        FlashMessage::info(format!("The tag '{}' has been added.", tag.as_ref())).send();
+    }
+    Ok(see_other(&location))
+}
+
+#[tracing::instrument(name = "Remove a tag from a subscriber", skip(form, pool))]
+pub async fn remove_subscriber_tag(
+    subscriber_id: web::Path<Uuid>,
+    form: web::Form<FormData>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let subscriber_id = subscriber_id.into_inner();
+    sqlx::query!(
+        r#"DELETE FROM subscriber_tags WHERE subscriber_id = $1 AND tag = $2"#,
+        subscriber_id,
+        form.0.tag.trim()
+    )
+    .execute(pool.get_ref())
+    .await
+    .map_err(e500)?;
+    Ok(see_other(&format!("/admin/subscribers/{}", subscriber_id)))
+}
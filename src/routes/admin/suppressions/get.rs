@@ -0,0 +1,98 @@
+use actix_web::http::header::ContentType;
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::IncomingFlashMessages;
+use sqlx::PgPool;
+use std::fmt::Write;
+use crate::suppression::{get_suppression, list_suppressions, MANUAL_REASONS};
+use crate::utils::e500;
+
+#[derive(serde::Deserialize)]
+pub struct CheckQuery {
+    #[serde(default)]
+    email: String
+}
+
+//20261019 추가 / 억제 목록 관리. 주소는 다이제스트로만 저장되므로 목록에는 다이제스트를 보여주고,
+//특정 주소가 억제되어 있는지는 주소를 입력해서 확인한다.
+pub async fn suppressions_page(
+    query: web::Query<CheckQuery>,
+    flash_messages: IncomingFlashMessages,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let mut msg_html = String::new();
+    for m in flash_messages.iter() {
+        writeln!(msg_html, "<p><i>{}</i></p>", m.content()).unwrap();
+    }
+    let email = query.email.trim();
+    let mut check_html = String::new();
+    if !email.is_empty() {
+        let escaped = htmlescape::encode_minimal(email);
+        match get_suppression(&pool, email).await.map_err(e500)? {
+            Some(suppression) => writeln!(
+                check_html,
+                "<p>{} is suppressed ({}) since {}.</p>",
+                escaped,
+                suppression.reason,
+                suppression.created_at.to_rfc3339()
+            ),
+            None => writeln!(check_html, "<p>{} is not suppressed.</p>", escaped)
+        }
+        .unwrap();
+    }
+    let mut reasons_html = String::new();
+    for reason in MANUAL_REASONS {
+        writeln!(reasons_html, r#"<option value="{reason}">{reason}</option>"#).unwrap();
+    }
+    let mut rows_html = String::new();
+    for suppression in list_suppressions(&pool, 100).await.map_err(e500)? {
+        writeln!(
+            rows_html,
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>",
+            suppression.email_hash,
+            suppression.reason,
+            suppression.created_at.to_rfc3339()
+        ).unwrap();
+    }
+
+    Ok(HttpResponse::Ok()
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>Suppression list</title>
+</head>
+<body>
+    {msg_html}
+    <form action="/admin/suppressions" method="get">
+        <label>Email
+            <input type="text" placeholder="Enter the email address" name="email">
+        </label>
+        <button type="submit">Check</button>
+    </form>
+    {check_html}
+    <form action="/admin/suppressions" method="post">
+        <label>Email
+            <input type="text" placeholder="Enter the email address" name="email">
+        </label>
+        <label>Reason
+            <select name="reason">{reasons_html}</select>
+        </label>
+        <button type="submit">Suppress</button>
+    </form>
+    <form action="/admin/suppressions/delete" method="post">
+        <label>Email
+            <input type="text" placeholder="Enter the email address" name="email">
+        </label>
+        <button type="submit">Lift suppression</button>
+    </form>
+    <table>
+        <tr><th>Address digest</th><th>Reason</th><th>Suppressed at</th></tr>
+        {rows_html}
+    </table>
+    <p><a href="/admin/dashboard">&lt;- Back</a></p>
+</body>
+</html>"#,
+        )))
+}
@@ -0,0 +1,5 @@
+mod get;
+mod post;
+
+pub use get::suppressions_page;
+pub use post::{add_suppression, delete_suppression};
@@ -0,0 +1,64 @@
+use actix_web::{web, HttpResponse};
+use actix_web_flash_messages::FlashMessage;
+use sqlx::PgPool;
+use crate::domain::SubscriberEmail;
+use crate::suppression::{remove_suppression, suppress_email, MANUAL_REASONS};
+use crate::utils::{e500, see_other};
+
+#[derive(serde::Deserialize)]
+pub struct SuppressForm {
+    email: String,
+    reason: String
+}
+
+#[tracing::instrument(name = "Suppress an email address", skip_all)]
+pub async fn add_suppression(
+    form: web::Form<SuppressForm>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let SuppressForm { email, reason } = form.into_inner();
+    let email = match SubscriberEmail::parse(email.trim().to_string()) {
+        Ok(email) => email,
+        Err(e) => {
+            FlashMessage::error(htmlescape::encode_minimal(&e)).send();
+            return Ok(see_other("/admin/suppressions"));
+        }
+    };
+    if !MANUAL_REASONS.contains(&reason.as_str()) {
+        FlashMessage::error(format!("{} is not a valid reason.", htmlescape::encode_minimal(&reason))).send();
+        return Ok(see_other("/admin/suppressions"));
+    }
+    let added = suppress_email(pool.get_ref(), email.as_ref(), &reason).await.map_err(e500)?;
+    let escaped = htmlescape::encode_minimal(email.as_ref());
+    if added {
+        FlashMessage::info(format!("{} has been suppressed.", escaped)).send();
+    } else {
+        FlashMessage::info(format!("{} was already suppressed.", escaped)).send();
+    }
+    Ok(see_other("/admin/suppressions"))
+}
+
+#[derive(serde::Deserialize)]
+pub struct DeleteForm {
+    email: String
+}
+
+#[tracing::instrument(name = "Lift the suppression of an email address", skip_all)]
+pub async fn delete_suppression(
+    form: web::Form<DeleteForm>,
+    pool: web::Data<PgPool>
+) -> Result<HttpResponse, actix_web::Error> {
+    let email = form.0.email.trim().to_string();
+    if email.is_empty() {
+        FlashMessage::error("The email address must not be empty.").send();
+        return Ok(see_other("/admin/suppressions"));
+    }
+    let removed = remove_suppression(pool.get_ref(), &email).await.map_err(e500)?;
+    let escaped = htmlescape::encode_minimal(&email);
+    if removed {
+        FlashMessage::info(format!("The suppression of {} has been lifted.", escaped)).send();
+    } else {
+        FlashMessage::info(format!("{} was not suppressed.", escaped)).send();
+    }
+    Ok(see_other("/admin/suppressions"))
+}
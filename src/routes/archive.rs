@@ -0,0 +1,135 @@
+use actix_web::http::header::ContentType;
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use std::fmt::Write;
+use crate::archive::{get_archived_issue, list_public_issues, ArchivedIssue, Visibility};
+use crate::email_templates::SubscriberProfile;
+use crate::routes::subscription_pages::{landing_page, something_went_wrong_page};
+use crate::signed_links::LinkSigner;
+
+//20261019 추가 / 발행한 이슈의 공개 웹 아카이브
+#[derive(serde::Deserialize)]
+pub struct ArchiveQuery {
+    token: Option<String>
+}
+
+fn log_and_fail(e: anyhow::Error) -> HttpResponse {
+    tracing::error!(
+        error.cause_chain = ?e,
+        error.message = %e,
+        "Failed to show the archive"
+    );
+    something_went_wrong_page()
+}
+
+fn not_found_page() -> HttpResponse {
+    landing_page(
+        StatusCode::NOT_FOUND,
+        "Issue not found",
+        r#"<p>This issue does not exist or is not available on the web. <a href="/archive">Browse the archive</a>.</p>"#
+    )
+}
+
+#[tracing::instrument(name = "Show the archive", skip_all)]
+pub async fn archive_index(pool: web::Data<PgPool>) -> HttpResponse {
+    let issues = match list_public_issues(&pool).await {
+        Ok(issues) => issues,
+        Err(e) => return log_and_fail(e.into())
+    };
+    let mut issues_html = String::new();
+    for issue in issues {
+        writeln!(
+            issues_html,
+            r#"<li><a href="/archive/{}">{}</a> ({})</li>"#,
+            htmlescape::encode_minimal(&urlencoding::encode(&issue.slug)),
+            htmlescape::encode_minimal(&issue.title),
+            issue.published_at.format("%Y-%m-%d")
+        ).unwrap();
+    }
+    if issues_html.is_empty() {
+        issues_html.push_str("<li>No issues have been published yet.</li>");
+    }
+    //20261019 수정 / 피드 주소를 안내한다.
+    landing_page(
+        StatusCode::OK,
+        "Newsletter archive",
+        &format!(
+            "<ul>\n{issues_html}</ul>\n    <p>Follow along with <a href=\"/feed.rss\">RSS</a> or <a href=\"/feed.atom\">Atom</a>.</p>"
+        )
+    )
+}
+
+#[tracing::instrument(name = "Show an archived issue", skip(pool, link_signer, query))]
+pub async fn archive_issue(
+    slug: web::Path<String>,
+    query: web::Query<ArchiveQuery>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>
+) -> HttpResponse {
+    let issue = match get_archived_issue(&pool, &slug).await {
+        Ok(Some(issue)) => issue,
+        Ok(None) => return not_found_page(),
+        Err(e) => return log_and_fail(e.into())
+    };
+    //이메일의 서명된 링크로 들어온 구독자
+    let subscriber_id = query
+        .token
+        .as_deref()
+        .and_then(|token| link_signer.verify_archive_token(token))
+        .filter(|(issue_id, _)| *issue_id == issue.newsletter_issue_id)
+        .map(|(_, subscriber_id)| subscriber_id);
+    match Visibility::parse(&issue.visibility) {
+        Ok(Visibility::Public) => {}
+        Ok(Visibility::Subscribers) if subscriber_id.is_some() => {}
+        Ok(Visibility::Subscribers) => {
+            return landing_page(
+                StatusCode::UNAUTHORIZED,
+                "Subscribers only",
+                r#"<p>This issue is only available to subscribers. Use the "View this issue in your browser" link in your email.</p>"#
+            )
+        }
+        _ => return not_found_page()
+    }
+    match render_issue(&pool, &issue, subscriber_id).await {
+        Ok(body) => HttpResponse::Ok().content_type(ContentType::html()).body(body),
+        Err(e) => log_and_fail(e)
+    }
+}
+
+async fn render_issue(
+    pool: &PgPool,
+    issue: &ArchivedIssue,
+    subscriber_id: Option<uuid::Uuid>
+) -> Result<String, anyhow::Error> {
+    let profile = match subscriber_id {
+        Some(subscriber_id) => sqlx::query_as!(
+            SubscriberProfile,
+            r#"SELECT name, custom_fields FROM subscriptions WHERE id = $1"#,
+            subscriber_id
+        )
+        .fetch_optional(pool)
+        .await?,
+        None => None
+    };
+    let (title, html) = issue.render(profile);
+    //본문이 완전한 HTML 문서이면 그대로, 일부분이면 페이지로 감싼다.
+    if html.to_ascii_lowercase().contains("<html") {
+        return Ok(html);
+    }
+    Ok(format!(
+        r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>{title}</title>
+</head>
+<body>
+    <h1>{title}</h1>
+    {html}
+    <p><a href="/archive">Browse the archive</a></p>
+</body>
+</html>"#,
+        title = htmlescape::encode_minimal(&title)
+    ))
+}
@@ -0,0 +1,127 @@
+use actix_web::http::header::{
+    CacheControl, CacheDirective, ContentType, ETag, EntityTag, HttpDate, IfModifiedSince, IfNoneMatch,
+    LastModified
+};
+use actix_web::{web, HttpMessage, HttpRequest, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::SystemTime;
+use crate::archive::{issues_last_updated, recent_public_issues};
+use crate::configuration::FeedSettings;
+use crate::feeds::{atom, etag, last_modified, rss, FeedEntry, FeedInfo};
+use crate::signed_links::LinkSigner;
+use crate::startup::ApplicationBaseUrl;
+use crate::utils::e500;
+
+//20261019 추가 / 공개 이슈의 RSS, Atom 피드. 수집기가 같은 피드를 반복해서 받아가지 않도록 조건부 GET을 지원한다.
+#[tracing::instrument(name = "Serve the RSS feed", skip_all)]
+pub async fn rss_feed(
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>,
+    base_url: web::Data<ApplicationBaseUrl>,
+    feed_settings: web::Data<FeedSettings>
+) -> Result<HttpResponse, actix_web::Error> {
+    let entries = get_entries(&pool, &link_signer, feed_settings.max_entries).await?;
+    let site_link = format!("{}/archive", base_url.0);
+    let self_link = format!("{}/feed.rss", base_url.0);
+    let info = FeedInfo {
+        title: &feed_settings.title,
+        description: &feed_settings.description,
+        site_link: &site_link,
+        self_link: &self_link
+    };
+    let body = rss(&info, &entries);
+    let last_modified = feed_last_modified(&pool, &entries).await?;
+    Ok(feed_response(&request, "application/rss+xml; charset=utf-8", body, last_modified))
+}
+
+#[tracing::instrument(name = "Serve the Atom feed", skip_all)]
+pub async fn atom_feed(
+    request: HttpRequest,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>,
+    base_url: web::Data<ApplicationBaseUrl>,
+    feed_settings: web::Data<FeedSettings>
+) -> Result<HttpResponse, actix_web::Error> {
+    let entries = get_entries(&pool, &link_signer, feed_settings.max_entries).await?;
+    let site_link = format!("{}/archive", base_url.0);
+    let self_link = format!("{}/feed.atom", base_url.0);
+    let info = FeedInfo {
+        title: &feed_settings.title,
+        description: &feed_settings.description,
+        site_link: &site_link,
+        self_link: &self_link
+    };
+    let body = atom(&info, &entries);
+    let last_modified = feed_last_modified(&pool, &entries).await?;
+    Ok(feed_response(&request, "application/atom+xml; charset=utf-8", body, last_modified))
+}
+
+async fn get_entries(
+    pool: &PgPool,
+    link_signer: &LinkSigner,
+    max_entries: u32
+) -> Result<Vec<FeedEntry>, actix_web::Error> {
+    let issues = recent_public_issues(pool, max_entries as i64).await.map_err(e500)?;
+    Ok(issues
+        .into_iter()
+        .map(|issue| {
+            let (title, html) = issue.render(None);
+            FeedEntry {
+                id: issue.newsletter_issue_id,
+                title,
+                link: link_signer.archive_link(&issue.slug, issue.newsletter_issue_id, None),
+                html,
+                published_at: issue.published_at
+            }
+        })
+        .collect())
+}
+
+//20261019 추가 / 가장 최근 발행 시각만으로는 이전 이슈를 숨기거나 수정한 것을 알 수 없으므로 이슈가 바뀐 시각도 반영한다.
+async fn feed_last_modified(
+    pool: &PgPool,
+    entries: &[FeedEntry]
+) -> Result<Option<DateTime<Utc>>, actix_web::Error> {
+    let last_updated = issues_last_updated(pool).await.map_err(e500)?;
+    Ok(last_updated.max(last_modified(entries)))
+}
+
+//'If-None-Match'가 있으면 ETag로만 판단하고, 없으면 'If-Modified-Since'를 피드가 마지막으로 바뀐 시각과 비교한다.
+fn feed_response(
+    request: &HttpRequest,
+    content_type: &str,
+    body: String,
+    last_modified: Option<DateTime<Utc>>
+) -> HttpResponse {
+    let etag = EntityTag::new_strong(etag(&body));
+    let not_modified = match request.get_header::<IfNoneMatch>() {
+        Some(IfNoneMatch::Any) => true,
+        Some(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(&etag)),
+        None => match (request.get_header::<IfModifiedSince>(), last_modified) {
+            //HTTP 날짜는 초 단위이므로 초 단위로 비교한다.
+            (Some(IfModifiedSince(since)), Some(last_modified)) => {
+                last_modified.timestamp() <= DateTime::<Utc>::from(SystemTime::from(since)).timestamp()
+            }
+            _ => false
+        }
+    };
+    let mut response = if not_modified {
+        HttpResponse::NotModified()
+    } else {
+        HttpResponse::Ok()
+    };
+    response
+        .insert_header(ETag(etag))
+        .insert_header(CacheControl(vec![CacheDirective::Public, CacheDirective::MaxAge(300)]));
+    if let Some(last_modified) = last_modified {
+        response.insert_header(LastModified(HttpDate::from(SystemTime::from(last_modified))));
+    }
+    if not_modified {
+        return response.finish();
+    }
+    response
+        .content_type(ContentType(content_type.parse().unwrap()))
+        .body(body)
+}
@@ -41,10 +41,10 @@ pub async fn login(
         username: form.0.username,
         password: form.0.password
     }; 
-    tracing::Span::current().record("username", &tracing::field::display(&credentials.username));
+    tracing::Span::current().record("username", tracing::field::display(&credentials.username));
     match validate_credentials(credentials, &pool).await {
         Ok(user_id) => {
-            tracing::Span::current().record("user_id", &tracing::field::display(&user_id));
+            tracing::Span::current().record("user_id", tracing::field::display(&user_id));
             session.renew();
             session.insert_user_id(user_id)
                 .map_err(|e| login_redirect(LoginError::UnexpectError(e.into())))?;
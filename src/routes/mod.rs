@@ -4,10 +4,22 @@ mod subscriptions_confirm;
 mod home;
 mod login;
 mod admin;
+//20261019 추가
+mod subscription_pages;
+mod preferences;
+mod webhooks;
+mod tracking;
+mod archive;
+mod feeds;
 
 pub use health_check::*;
 pub use subscriptions::*;
 pub use subscriptions_confirm::*;
 pub use home::*;
 pub use login::*;
-pub use admin::*;
\ No newline at end of file
+pub use admin::*;
+pub use preferences::*;
+pub use webhooks::*;
+pub use tracking::*;
+pub use archive::*;
+pub use feeds::*;
\ No newline at end of file
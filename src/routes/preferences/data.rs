@@ -0,0 +1,73 @@
+use actix_web::{web, HttpResponse};
+use sqlx::PgPool;
+use uuid::Uuid;
+use crate::personal_data::{collect_personal_data, erase_personal_data};
+use crate::routes::personal_data_response;
+use crate::routes::subscription_pages::{data_erased_page, invalid_link_page, something_went_wrong_page};
+use crate::signed_links::LinkSigner;
+use crate::subscription_events::EventSource;
+
+//20261019 추가 / 구독 설정 링크로 구독자가 직접 자신의 데이터를 내려받거나 삭제한다.
+#[derive(serde::Deserialize)]
+pub struct Parameters {
+    token: String
+}
+
+async fn get_email(pool: &PgPool, subscriber_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    let row = sqlx::query!(r#"SELECT email FROM subscriptions WHERE id = $1"#, subscriber_id)
+        .fetch_optional(pool)
+        .await?;
+    Ok(row.map(|r| r.email))
+}
+
+fn log_and_fail(e: sqlx::Error) -> HttpResponse {
+    tracing::error!(
+        error.cause_chain = ?e,
+        error.message = %e,
+        "Failed to handle a personal data request"
+    );
+    something_went_wrong_page()
+}
+
+#[tracing::instrument(name = "Download personal data", skip_all)]
+pub async fn download_personal_data(
+    parameters: web::Query<Parameters>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>
+) -> HttpResponse {
+    let subscriber_id = match link_signer.verify_preferences_token(&parameters.token) {
+        Some(subscriber_id) => subscriber_id,
+        None => return invalid_link_page()
+    };
+    let email = match get_email(&pool, subscriber_id).await {
+        Ok(Some(email)) => email,
+        Ok(None) => return invalid_link_page(),
+        Err(e) => return log_and_fail(e)
+    };
+    match collect_personal_data(&pool, &email).await {
+        Ok(data) => personal_data_response(&data),
+        Err(e) => log_and_fail(e)
+    }
+}
+
+#[tracing::instrument(name = "Erase personal data from the preference center", skip_all)]
+pub async fn erase_my_data(
+    form: web::Form<Parameters>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>
+) -> HttpResponse {
+    let subscriber_id = match link_signer.verify_preferences_token(&form.token) {
+        Some(subscriber_id) => subscriber_id,
+        None => return invalid_link_page()
+    };
+    let email = match get_email(&pool, subscriber_id).await {
+        Ok(Some(email)) => email,
+        //이미 삭제되었다. 링크를 다시 눌러도 같은 결과를 보여준다.
+        Ok(None) => return data_erased_page(),
+        Err(e) => return log_and_fail(e)
+    };
+    match erase_personal_data(&pool, &email, EventSource::Form).await {
+        Ok(_) => data_erased_page(),
+        Err(e) => log_and_fail(e)
+    }
+}
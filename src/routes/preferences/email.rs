@@ -0,0 +1,110 @@
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse};
+use anyhow::Context;
+use sqlx::PgPool;
+use super::get::{get_preferences, render_preferences};
+use crate::domain::{NewSubscriber, SubscriberEmail, SubscriberName};
+use crate::email_client::EmailClient;
+use crate::email_templates::EmailChangeTemplate;
+use crate::routes::subscription_pages::{invalid_link_page, something_went_wrong_page};
+use crate::routes::{delete_tokens, generate_subscription_token, send_confirmation_email, store_token};
+use crate::signed_links::LinkSigner;
+use crate::startup::ApplicationBaseUrl;
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+
+#[derive(serde::Deserialize)]
+pub struct FormData {
+    token: String,
+    email: String
+}
+
+//20261019 추가 / 이메일 변경을 요청한다. 새 주소로 확인 링크를 보내고, 링크가 확인될 때까지 기존 주소를 유지한다.
+#[tracing::instrument(name = "Request an email change", skip_all)]
+pub async fn request_email_change(
+    form: web::Form<FormData>,
+    pool: web::Data<PgPool>,
+    email_client: web::Data<EmailClient>,
+    base_url: web::Data<ApplicationBaseUrl>,
+    email_change_template: web::Data<EmailChangeTemplate>,
+    link_signer: web::Data<LinkSigner>,
+    request: HttpRequest
+) -> HttpResponse {
+    let subscriber_id = match link_signer.verify_preferences_token(&form.token) {
+        Some(subscriber_id) => subscriber_id,
+        None => return invalid_link_page()
+    };
+    let result = async {
+        let new_email = match SubscriberEmail::parse(form.email.clone()) {
+            Ok(email) => email,
+            Err(e) => return Ok(Err(e))
+        };
+        let subscriber = match get_preferences(&pool, subscriber_id).await? {
+            Some(subscriber) if subscriber.status == "confirmed" => subscriber,
+            _ => return Ok(Ok(None))
+        };
+        if subscriber.email == new_email.as_ref() {
+            return Ok(Err("That is already your email address.".to_string()));
+        }
+        let message = format!(
+            "We have sent a confirmation link to {}. Your email address will change once you click it.",
+            new_email.as_ref()
+        );
+        let mut transaction = pool.begin().await?;
+        //20261019 수정 / 주소가 구독 중인지 알 수 없도록 이미 사용 중인 주소에도 같은 응답을 보여주고, 이메일은 보내지 않는다.
+        let taken = sqlx::query!(
+            r#"SELECT id FROM subscriptions WHERE lower(email) = lower($1) AND id <> $2"#,
+            new_email.as_ref(),
+            subscriber_id
+        )
+        .fetch_optional(&mut transaction)
+        .await?
+        .is_some();
+        if taken {
+            return Ok(Ok(Some(message)));
+        }
+        //이전에 요청한 변경 링크는 더 이상 사용할 수 없다.
+        delete_tokens(&mut transaction, subscriber_id)
+            .await
+            .context("Failed to delete the previous confirmation tokens")?;
+        let subscription_token = generate_subscription_token();
+        store_token(&mut transaction, subscriber_id, &subscription_token, Some(&new_email))
+            .await
+            .context("Failed to store the email change token")?;
+        transaction.commit().await?;
+        let recipient = NewSubscriber {
+            email: new_email,
+            name: SubscriberName::parse(subscriber.name).map_err(anyhow::Error::msg)?
+        };
+        send_confirmation_email(&email_client, &email_change_template.0, recipient, &base_url.0, &subscription_token)
+            .await
+            .context("Failed to send the email change confirmation")?;
+        record_event(
+            pool.get_ref(),
+            subscriber_id,
+            EventType::ConfirmationEmailSent,
+            &EventContext::from_request(&request, EventSource::Form),
+            serde_json::json!({ "purpose": "email_change" })
+        )
+        .await
+        .context("Failed to record the confirmation email event")?;
+        Ok::<_, anyhow::Error>(Ok(Some(message)))
+    }
+    .await;
+    let (status, message) = match result {
+        Ok(Ok(Some(message))) => (StatusCode::OK, message),
+        Ok(Ok(None)) => return invalid_link_page(),
+        Ok(Err(validation_error)) => (StatusCode::BAD_REQUEST, validation_error),
+        Err(e) => {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to request an email change"
+            );
+            return something_went_wrong_page();
+        }
+    };
+    let msg_html = format!("<p><i>{}</i></p>", htmlescape::encode_minimal(&message));
+    render_preferences(&pool, &form.token, subscriber_id, status, &msg_html)
+        .await
+        .unwrap_or_else(|_| something_went_wrong_page())
+}
@@ -0,0 +1,190 @@
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::fmt::Write;
+use uuid::Uuid;
+use crate::routes::subscription_pages::{
+    invalid_link_page, landing_page, something_went_wrong_page, unsubscribed_page
+};
+use crate::signed_links::LinkSigner;
+
+//20261019 추가 / 이메일의 서명된 링크로 들어오는 구독 설정 페이지
+#[derive(serde::Deserialize)]
+pub struct Parameters {
+    token: String
+}
+
+pub struct SubscriberPreferences {
+    pub email: String,
+    pub name: String,
+    pub status: String,
+    pub delivery_frequency: String,
+    pub paused_until: Option<DateTime<Utc>>,
+    //20261019 추가
+    pub tracking_opt_out: bool
+}
+
+pub struct ListChoice {
+    pub list_id: Uuid,
+    pub name: String,
+    pub selected: bool
+}
+
+#[tracing::instrument(name = "Show the preference center", skip_all)]
+pub async fn preferences_page(
+    parameters: web::Query<Parameters>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>
+) -> HttpResponse {
+    let subscriber_id = match link_signer.verify_preferences_token(&parameters.token) {
+        Some(subscriber_id) => subscriber_id,
+        None => return invalid_link_page()
+    };
+    match render_preferences(&pool, &parameters.token, subscriber_id, StatusCode::OK, "").await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to show the preference center"
+            );
+            something_went_wrong_page()
+        }
+    }
+}
+
+//구독자의 현재 설정으로 채운 폼을 보여준다. 'msg_html'은 폼 위에 표시된다.
+pub async fn render_preferences(
+    pool: &PgPool,
+    token: &str,
+    subscriber_id: Uuid,
+    status: StatusCode,
+    msg_html: &str
+) -> Result<HttpResponse, anyhow::Error> {
+    let subscriber = match get_preferences(pool, subscriber_id).await? {
+        Some(subscriber) => subscriber,
+        None => return Ok(invalid_link_page())
+    };
+    match subscriber.status.as_str() {
+        "confirmed" => {}
+        "unsubscribed" => return Ok(unsubscribed_page()),
+        _ => return Ok(invalid_link_page())
+    }
+
+    let mut lists_html = String::new();
+    for list in get_list_choices(pool, subscriber_id).await? {
+        let checked = if list.selected { " checked" } else { "" };
+        writeln!(
+            lists_html,
+            r#"<label><input type="checkbox" name="lists" value="{}"{checked}> {}</label><br>"#,
+            list.list_id,
+            htmlescape::encode_minimal(&list.name)
+        ).unwrap();
+    }
+    let mut frequency_html = String::new();
+    for (value, label) in [("immediate", "Every issue as it is published"), ("weekly", "A weekly digest")] {
+        let selected = if subscriber.delivery_frequency == value { " selected" } else { "" };
+        writeln!(frequency_html, r#"<option value="{value}"{selected}>{label}</option>"#).unwrap();
+    }
+    let mut pause_html = String::new();
+    match subscriber.paused_until.filter(|until| *until > Utc::now()) {
+        Some(until) => {
+            writeln!(
+                pause_html,
+                r#"<option value="keep" selected>Paused until {}</option>"#,
+                until.format("%Y-%m-%d")
+            ).unwrap();
+            writeln!(pause_html, r#"<option value="">Resume delivery now</option>"#).unwrap();
+        }
+        None => writeln!(pause_html, r#"<option value="" selected>Do not pause</option>"#).unwrap()
+    }
+    for days in [7, 30, 90] {
+        writeln!(pause_html, r#"<option value="{days}">Pause for {days} days</option>"#).unwrap();
+    }
+    let no_tracking = if subscriber.tracking_opt_out { " checked" } else { "" };
+    let token = htmlescape::encode_attribute(token);
+    let name = htmlescape::encode_attribute(&subscriber.name);
+    let email = htmlescape::encode_minimal(&subscriber.email);
+
+    Ok(landing_page(
+        status,
+        "Subscription preferences",
+        &format!(
+            r#"{msg_html}
+    <p>Preferences for {email}</p>
+    <form action="/preferences" method="post">
+        <input hidden type="text" name="token" value="{token}">
+        <label>Name
+            <input type="text" name="name" value="{name}">
+        </label>
+        <fieldset>
+            <legend>Lists</legend>
+            {lists_html}
+        </fieldset>
+        <label>Delivery
+            <select name="delivery_frequency">{frequency_html}</select>
+        </label>
+        <label>Pause
+            <select name="pause">{pause_html}</select>
+        </label>
+        <label><input type="checkbox" name="no_tracking"{no_tracking}> Do not track when I open emails or click links</label>
+        <button type="submit">Save preferences</button>
+    </form>
+    <form action="/preferences/email" method="post">
+        <input hidden type="text" name="token" value="{token}">
+        <label>New email address
+            <input type="text" name="email">
+        </label>
+        <button type="submit">Change email address</button>
+    </form>
+    <form action="/preferences/unsubscribe" method="post">
+        <input hidden type="text" name="token" value="{token}">
+        <button type="submit">Unsubscribe from everything</button>
+    </form>
+    <p><a href="/preferences/data?token={token}">Download all the data we hold about you</a></p>
+    <form action="/preferences/erase" method="post">
+        <input hidden type="text" name="token" value="{token}">
+        <button type="submit">Delete all my data</button>
+    </form>"#
+        )
+    ))
+}
+
+#[tracing::instrument(skip(pool))]
+pub async fn get_preferences(
+    pool: &PgPool,
+    subscriber_id: Uuid
+) -> Result<Option<SubscriberPreferences>, sqlx::Error> {
+    sqlx::query_as!(
+        SubscriberPreferences,
+        r#"
+        SELECT email, name, status, delivery_frequency, paused_until, tracking_opt_out
+        FROM subscriptions
+        WHERE id = $1
+        "#,
+        subscriber_id
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+#[tracing::instrument(skip(pool))]
+async fn get_list_choices(
+    pool: &PgPool,
+    subscriber_id: Uuid
+) -> Result<Vec<ListChoice>, sqlx::Error> {
+    sqlx::query_as!(
+        ListChoice,
+        r#"
+        SELECT l.list_id, l.name,
+            COALESCE(m.status IN ('confirmed', 'pending_confirmation'), false) AS "selected!"
+        FROM lists l
+        LEFT JOIN list_memberships m ON m.list_id = l.list_id AND m.subscriber_id = $1
+        ORDER BY l.created_at
+        "#,
+        subscriber_id
+    )
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,10 @@
+mod get;
+mod post;
+//20261019 추가
+mod email;
+mod data;
+
+pub use get::preferences_page;
+pub use post::{unsubscribe, update_preferences};
+pub use email::request_email_change;
+pub use data::{download_personal_data, erase_my_data};
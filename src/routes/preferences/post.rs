@@ -0,0 +1,231 @@
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse};
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use uuid::Uuid;
+use super::get::{get_preferences, render_preferences};
+use crate::domain::SubscriberName;
+use crate::html_form::HtmlForm;
+use crate::routes::subscription_pages::{invalid_link_page, something_went_wrong_page, unsubscribed_page};
+use crate::signed_links::LinkSigner;
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+
+#[derive(serde::Deserialize)]
+pub struct FormData {
+    token: String,
+    name: String,
+    #[serde(default)]
+    lists: Vec<Uuid>,
+    delivery_frequency: String,
+    //'keep'(현재 설정 유지), ''(일시 중지하지 않음), 또는 일시 중지할 일 수
+    pause: String,
+    //20261019 추가 / 체크하면 열람 및 클릭 추적을 거부한다.
+    #[serde(default)]
+    no_tracking: Option<String>
+}
+
+#[derive(serde::Deserialize)]
+pub struct UnsubscribeFormData {
+    token: String
+}
+
+pub const DELIVERY_FREQUENCIES: &[&str] = &["immediate", "weekly"];
+
+//일시 중지 설정. 'None'이면 현재 설정을 유지한다.
+fn parse_pause(pause: &str) -> Result<Option<Option<DateTime<Utc>>>, String> {
+    match pause {
+        "keep" => Ok(None),
+        "" => Ok(Some(None)),
+        days => match days.parse::<i64>() {
+            Ok(days) if (1..=365).contains(&days) => Ok(Some(Some(Utc::now() + chrono::Duration::days(days)))),
+            _ => Err(format!("{} is not a valid pause duration.", days))
+        }
+    }
+}
+
+fn log_and_fail(e: anyhow::Error) -> HttpResponse {
+    tracing::error!(
+        error.cause_chain = ?e,
+        error.message = %e,
+        "Failed to update subscription preferences"
+    );
+    something_went_wrong_page()
+}
+
+//20261019 추가 / 구독 설정을 저장한다. 다음 이슈 발송부터 적용된다.
+#[tracing::instrument(name = "Update subscription preferences", skip_all)]
+pub async fn update_preferences(
+    form: HtmlForm<FormData>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>,
+    request: HttpRequest
+) -> HttpResponse {
+    let form = form.0;
+    let subscriber_id = match link_signer.verify_preferences_token(&form.token) {
+        Some(subscriber_id) => subscriber_id,
+        None => return invalid_link_page()
+    };
+    let validated = SubscriberName::parse(form.name.clone()).and_then(|name| {
+        if !DELIVERY_FREQUENCIES.contains(&form.delivery_frequency.as_str()) {
+            return Err(format!("{} is not a valid delivery frequency.", form.delivery_frequency));
+        }
+        Ok((name, parse_pause(&form.pause)?))
+    });
+    let (name, pause) = match validated {
+        Ok(validated) => validated,
+        Err(e) => {
+            let msg_html = format!("<p><i>{}</i></p>", htmlescape::encode_minimal(&e));
+            return render_preferences(&pool, &form.token, subscriber_id, StatusCode::BAD_REQUEST, &msg_html)
+                .await
+                .unwrap_or_else(log_and_fail);
+        }
+    };
+    let result = async {
+        match get_preferences(&pool, subscriber_id).await? {
+            Some(subscriber) if subscriber.status == "confirmed" => {}
+            _ => return Ok(false)
+        }
+        let mut transaction = pool.begin().await?;
+        sqlx::query!(
+            r#"
+            UPDATE subscriptions
+            SET name = $2,
+                delivery_frequency = $3,
+                paused_until = CASE WHEN $4 THEN paused_until ELSE $5 END,
+                tracking_opt_out = $6
+            WHERE id = $1
+            "#,
+            subscriber_id,
+            name.as_ref(),
+            form.delivery_frequency,
+            pause.is_none(),
+            pause.flatten(),
+            form.no_tracking.is_some()
+        )
+        .execute(&mut transaction)
+        .await
+        .context("Failed to update the subscriber")?;
+        set_list_memberships(&mut transaction, subscriber_id, &form.lists)
+            .await
+            .context("Failed to update the list memberships")?;
+        record_event(
+            &mut transaction,
+            subscriber_id,
+            EventType::PreferencesChanged,
+            &EventContext::from_request(&request, EventSource::Form),
+            serde_json::json!({
+                "lists": form.lists,
+                "delivery_frequency": form.delivery_frequency,
+                "paused_until": pause.flatten().map(|until| until.to_rfc3339()),
+                "tracking_opt_out": form.no_tracking.is_some()
+            })
+        )
+        .await
+        .context("Failed to record the preferences event")?;
+        transaction.commit().await?;
+        Ok::<bool, anyhow::Error>(true)
+    }
+    .await;
+    match result {
+        Ok(true) => render_preferences(
+            &pool,
+            &form.token,
+            subscriber_id,
+            StatusCode::OK,
+            "<p><i>Your preferences have been saved.</i></p>"
+        )
+        .await
+        .unwrap_or_else(log_and_fail),
+        Ok(false) => invalid_link_page(),
+        Err(e) => log_and_fail(e)
+    }
+}
+
+//선택한 리스트는 바로 확인된 상태로 구독하고, 선택하지 않은 리스트는 구독을 해지한다.
+//서명된 링크는 구독자의 메일함으로만 발송되므로 이메일 소유 확인을 다시 거치지 않는다.
+#[tracing::instrument(skip(transaction))]
+async fn set_list_memberships(
+    transaction: &mut Transaction<'_, Postgres>,
+    subscriber_id: Uuid,
+    list_ids: &[Uuid]
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO list_memberships (list_id, subscriber_id, status)
+        SELECT list_id, $1, 'confirmed'
+        FROM lists
+        WHERE list_id = ANY($2)
+        ON CONFLICT (list_id, subscriber_id) DO UPDATE
+        SET status = 'confirmed'
+        "#,
+        subscriber_id,
+        list_ids
+    )
+    .execute(&mut *transaction)
+    .await?;
+    sqlx::query!(
+        r#"
+        UPDATE list_memberships
+        SET status = 'unsubscribed'
+        WHERE subscriber_id = $1 AND NOT (list_id = ANY($2))
+        "#,
+        subscriber_id,
+        list_ids
+    )
+    .execute(transaction)
+    .await?;
+    Ok(())
+}
+
+//20261019 추가 / 모든 리스트에서 구독을 해지한다. 다시 구독하려면 더블 옵트인을 새로 거쳐야 한다.
+#[tracing::instrument(name = "Unsubscribe from the preference center", skip_all)]
+pub async fn unsubscribe(
+    form: web::Form<UnsubscribeFormData>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>,
+    request: HttpRequest
+) -> HttpResponse {
+    let subscriber_id = match link_signer.verify_preferences_token(&form.token) {
+        Some(subscriber_id) => subscriber_id,
+        None => return invalid_link_page()
+    };
+    let event_context = EventContext::from_request(&request, EventSource::Form);
+    match unsubscribe_subscriber(&pool, subscriber_id, &event_context).await {
+        Ok(true) => unsubscribed_page(),
+        Ok(false) => invalid_link_page(),
+        Err(e) => log_and_fail(e)
+    }
+}
+
+#[tracing::instrument(skip(pool, event_context))]
+pub async fn unsubscribe_subscriber(
+    pool: &PgPool,
+    subscriber_id: Uuid,
+    //20261019 추가
+    event_context: &EventContext
+) -> Result<bool, anyhow::Error> {
+    let mut transaction = pool.begin().await?;
+    let updated = sqlx::query!(
+        r#"
+        UPDATE subscriptions SET status = 'unsubscribed'
+        WHERE id = $1 AND status IN ('confirmed', 'unsubscribed')
+        "#,
+        subscriber_id
+    )
+    .execute(&mut transaction)
+    .await?
+    .rows_affected();
+    sqlx::query!(
+        r#"UPDATE list_memberships SET status = 'unsubscribed' WHERE subscriber_id = $1"#,
+        subscriber_id
+    )
+    .execute(&mut transaction)
+    .await?;
+    if updated == 1 {
+        record_event(&mut transaction, subscriber_id, EventType::Unsubscribed, event_context, serde_json::json!({}))
+            .await?;
+    }
+    transaction.commit().await?;
+    Ok(updated == 1)
+}
@@ -0,0 +1,122 @@
+use actix_web::http::header::ContentType;
+use actix_web::http::StatusCode;
+use actix_web::HttpResponse;
+
+//20261019 추가 / 구독자가 메일함에서 링크를 클릭했을 때 빈 화면 대신 보여줄 랜딩 페이지들
+pub fn landing_page(status: StatusCode, title: &str, body_html: &str) -> HttpResponse {
+    HttpResponse::build(status)
+        .content_type(ContentType::html())
+        .body(format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+    <meta http-equiv="content-type" content="text/html; charset=utf-8">
+    <title>{title}</title>
+</head>
+<body>
+    <h1>{title}</h1>
+    {body_html}
+</body>
+</html>"#,
+        ))
+}
+
+pub fn check_your_inbox_page() -> HttpResponse {
+    landing_page(
+        StatusCode::OK,
+        "Check your inbox",
+        "<p>We have sent you an email. Click the link inside it to confirm your subscription.</p>"
+    )
+}
+
+pub fn subscription_confirmed_page() -> HttpResponse {
+    landing_page(
+        StatusCode::OK,
+        "Subscription confirmed",
+        "<p>Thank you! Your subscription has been confirmed.</p>"
+    )
+}
+
+pub fn already_confirmed_page() -> HttpResponse {
+    landing_page(
+        StatusCode::OK,
+        "Already confirmed",
+        "<p>Your subscription has already been confirmed. There is nothing else to do.</p>"
+    )
+}
+
+pub fn invalid_link_page() -> HttpResponse {
+    landing_page(
+        StatusCode::UNAUTHORIZED,
+        "Invalid link",
+        "<p>This link is invalid or has expired. Please subscribe again to receive a new one.</p>"
+    )
+}
+
+//만료된 링크는 이메일을 채운 폼을 함께 보여준다. 폼을 제출하면 새로운 확인 링크가 발송된다.
+pub fn expired_link_page(email: &str, name: &str) -> HttpResponse {
+    let email = htmlescape::encode_attribute(email);
+    let name = htmlescape::encode_attribute(name);
+    landing_page(
+        StatusCode::GONE,
+        "Link expired",
+        &format!(
+            r#"<p>This confirmation link has expired.</p>
+    <form action="/subscriptions" method="post">
+        <input hidden type="text" name="name" value="{name}">
+        <label>Email
+            <input type="text" name="email" value="{email}">
+        </label>
+        <button type="submit">Send me a new one</button>
+    </form>"#
+        )
+    )
+}
+
+pub fn unsubscribed_page() -> HttpResponse {
+    landing_page(
+        StatusCode::OK,
+        "Unsubscribed",
+        "<p>You have been unsubscribed and will not receive any more issues. You can subscribe again at any time.</p>"
+    )
+}
+
+pub fn email_changed_page() -> HttpResponse {
+    landing_page(
+        StatusCode::OK,
+        "Email address updated",
+        "<p>Your email address has been updated. Future issues will be sent to your new address.</p>"
+    )
+}
+
+pub fn email_change_link_expired_page() -> HttpResponse {
+    landing_page(
+        StatusCode::GONE,
+        "Link expired",
+        "<p>This link has expired. Please request the change again from your subscription preferences.</p>"
+    )
+}
+
+pub fn email_already_taken_page() -> HttpResponse {
+    landing_page(
+        StatusCode::CONFLICT,
+        "Email address already in use",
+        "<p>This email address is already subscribed, so your email address has not been changed.</p>"
+    )
+}
+
+pub fn data_erased_page() -> HttpResponse {
+    landing_page(
+        StatusCode::OK,
+        "Your data has been deleted",
+        "<p>We have deleted all the data we held about you. You will not receive any more emails from us.</p>"
+    )
+}
+
+pub fn something_went_wrong_page() -> HttpResponse {
+    landing_page(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Something went wrong",
+        "<p>We could not process your request. Please try again later.</p>"
+    )
+}
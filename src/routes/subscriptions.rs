@@ -7,7 +7,7 @@ use chrono::Utc;
 use uuid::Uuid;
 use crate::domain::{NewSubscriber, SubscriberName, SubscriberEmail};
 //20250206 추가
-use crate::email_client::EmailClient;
+use crate::email_client::{EmailClient, SendEmailError};
 //20250211 추가
 use crate::startup::ApplicationBaseUrl;
 use rand::distributions::Alphanumeric;
@@ -17,12 +17,33 @@ use actix_web::ResponseError;
 //20250213
 use actix_web::http::StatusCode;
 use anyhow::Context;
+//20261019 추가
+use sha2::{Digest, Sha256};
+use actix_web::HttpRequest;
+use actix_web::http::header::{Accept, Header};
+use crate::routes::subscription_pages::check_your_inbox_page;
+use crate::email_templates::ConfirmationEmailTemplate;
+use crate::html_form::HtmlForm;
+use crate::custom_fields::{get_field_definitions, validate_custom_fields};
+use std::collections::HashMap;
+use serde_json::{Map, Value};
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+use crate::suppression::is_suppressed;
+
+//20261019 추가 / 리스트를 지정하지 않은 구독 요청은 기본 리스트를 구독한다.
+pub const DEFAULT_LIST_SLUG: &str = "newsletter";
 
 #[derive(serde::Deserialize)]
 pub struct FormData {
     email: String,
-    name: String
-}   
+    name: String,
+    //20261019 추가 / 구독할 리스트의 slug 목록 (lists=a&lists=b)
+    #[serde(default)]
+    lists: Vec<String>,
+    //20261019 추가 / 관리자가 정의한 사용자 정의 필드 (company=Acme&country=KR). 정의되지 않은 키는 무시한다.
+    #[serde(flatten)]
+    custom_fields: HashMap<String, String>
+}
 
 //와이어 포맷(HTML 폼에서 수집한 url-decoded 데이터)을 도메인 모델(NewSubscriber)로 변환한다.
 impl TryFrom<FormData> for NewSubscriber {
@@ -38,44 +59,171 @@ impl TryFrom<FormData> for NewSubscriber {
 //traccing::instrument가 비동기함수에 적용될 때는 Instrument::instrument를 사용하도록 주의해야한다.
 #[tracing::instrument(
     name = "Adding a new subscriber",
-    skip(form, pool, email_client, base_url),
+    skip(form, pool, email_client, base_url, confirmation_email_template, request),
     fields(
-        subscriber_email = %form.email,
-        subscriber_name = %form.name
+        subscriber_email = %form.0.email,
+        subscriber_name = %form.0.name
     )
 )]
 //유입되는 HTTP 요청에 대해 HTTP 응답을 생성한다.
 pub async fn subscribe(
-    form: web::Form<FormData>, 
+    //20261019 수정 / 여러 리스트를 선택할 수 있도록 'web::Form' 대신 'HtmlForm'을 사용한다.
+    mut form: HtmlForm<FormData>,
     pool: web::Data<PgPool>,
     //20250206 추가 - 앱 콘테스트에서 이메일 클라이언트를 얻는다.
     email_client: web::Data<EmailClient>,
     //20250211 추가 - 도메인 전달 -> 도메인과 프로토콜은 애플리케이션이 실행되는 환경에 따라 다르기 때문에 새로 추가
-    base_url: web::Data<ApplicationBaseUrl>
+    base_url: web::Data<ApplicationBaseUrl>,
+    //20261019 추가 / 시작 시 검증된 확인 이메일 템플릿
+    confirmation_email_template: web::Data<ConfirmationEmailTemplate>,
+    //20261019 추가 / 응답 형식(HTML 또는 JSON)을 결정하기 위해 Accept 헤더를 읽는다.
+    request: HttpRequest
 ) -> Result<HttpResponse, SubscribeError> {
-    let new_subscriber = form.0.try_into().map_err(SubscribeError::ValidationError)?;
+    let mut list_slugs = form.0.lists.clone();
+    if list_slugs.is_empty() {
+        list_slugs.push(DEFAULT_LIST_SLUG.to_string());
+    }
+    list_slugs.sort();
+    list_slugs.dedup();
+    //20261019 추가 / 구독 동의 기록에 남길 요청 정보
+    let event_context = EventContext::from_request(
+        &request,
+        if prefers_json(&request) { EventSource::Api } else { EventSource::Form }
+    );
+    let submitted_fields = std::mem::take(&mut form.0.custom_fields);
+    let new_subscriber: NewSubscriber = form.0.try_into().map_err(SubscribeError::ValidationError)?;
     let mut transaction = pool.begin()
         .await
         .context("Failed to acquire a Postgres connection from the pool")?;
-    let subscriber_id = insert_subscriber(&mut transaction, &new_subscriber)
+    //20261019 추가 / 반송, 스팸 신고, 삭제 요청으로 억제된 주소에는 확인 이메일도 보내지 않는다.
+    //억제 여부가 드러나지 않도록 평소와 같은 응답을 반환한다.
+    if is_suppressed(&mut transaction, new_subscriber.email.as_ref())
         .await
-        .context("Failed to insert new subscriber in the database")?;
+        .context("Failed to check the suppression list")?
+    {
+        tracing::info!("Ignoring a subscription request for a suppressed address");
+        return Ok(subscription_accepted(&request));
+    }
+    //20261019 추가 / 사용자 정의 필드를 정의에 맞게 검증한다.
+    let field_definitions = get_field_definitions(&mut transaction)
+        .await
+        .context("Failed to load the custom field definitions")?;
+    let custom_fields = validate_custom_fields(&field_definitions, &submitted_fields)
+        .map_err(SubscribeError::ValidationError)?;
+    let list_ids = get_list_ids(&mut transaction, &list_slugs)
+        .await
+        .context("Failed to look up the requested lists")?;
+    if list_ids.len() != list_slugs.len() {
+        return Err(SubscribeError::ValidationError("Unknown list requested".into()));
+    }
+    //20261019 추가 / 이미 존재하는 이메일이면 상태에 따라 처리한다. UNIQUE 제약 조건 위반으로 500을 반환하지 않는다.
+    let existing_subscriber = get_subscriber_by_email(&mut transaction, &new_subscriber.email)
+        .await
+        .context("Failed to look up an existing subscriber in the database")?;
+    let subscriber_id = match existing_subscriber {
+        //이미 확인된 구독자는 새로 요청한 리스트만 확인 대기 상태로 추가한다.
+        //추가할 리스트가 없으면 아무것도 하지 않는다. 구독 여부가 노출되지 않도록 신규 구독과 동일한 응답을 반환한다.
+        Some((subscriber_id, status)) if status == "confirmed" => {
+            let added = add_list_memberships(&mut transaction, subscriber_id, &list_ids)
+                .await
+                .context("Failed to add list memberships")?;
+            if added == 0 {
+                transaction.commit()
+                    .await
+                    .context("Failed to commit SQL transaction to store a new subscriber")?;
+                return Ok(subscription_accepted(&request));
+            }
+            delete_tokens(&mut transaction, subscriber_id)
+                .await
+                .context("Failed to delete the previous confirmation tokens")?;
+            subscriber_id
+        }
+        //확인 대기 중인 구독자는 기존 토큰을 폐기하고 새로운 확인 링크를 다시 보낸다.
+        Some((subscriber_id, status)) if status == "pending_confirmation" => {
+            delete_tokens(&mut transaction, subscriber_id)
+                .await
+                .context("Failed to delete the previous confirmation tokens")?;
+            subscriber_id
+        }
+        //구독을 해지한 구독자는 새로운 더블 옵트인 사이클을 시작한다.
+        //확인된 구독자와 확인 대기 중인 구독자의 사용자 정의 필드는 이메일 소유를 확인하지 않은 요청으로 덮어쓰지 않는다.
+        Some((subscriber_id, _)) => {
+            delete_tokens(&mut transaction, subscriber_id)
+                .await
+                .context("Failed to delete the previous confirmation tokens")?;
+            reset_subscriber(&mut transaction, subscriber_id, &new_subscriber, &custom_fields)
+                .await
+                .context("Failed to restart the subscription of an existing subscriber")?;
+            subscriber_id
+        }
+        None => match insert_subscriber(&mut transaction, &new_subscriber, &custom_fields)
+            .await
+            .context("Failed to insert new subscriber in the database")? {
+                Some(subscriber_id) => subscriber_id,
+                //동시에 들어온 같은 이메일의 요청이 먼저 추가했다. 그 요청의 확인 링크도 유효하도록 토큰은 그대로 둔다.
+                None => get_subscriber_by_email(&mut transaction, &new_subscriber.email)
+                    .await
+                    .context("Failed to look up an existing subscriber in the database")?
+                    .map(|(subscriber_id, _)| subscriber_id)
+                    .context("The concurrently inserted subscriber no longer exists")?
+            }
+    };
+    //20261019 추가 / 요청한 리스트는 모두 확인 대기 상태가 되고, 이번에 발송하는 링크로 함께 확인된다.
+    add_list_memberships(&mut transaction, subscriber_id, &list_ids)
+        .await
+        .context("Failed to add list memberships")?;
+    record_event(
+        &mut transaction,
+        subscriber_id,
+        EventType::Subscribed,
+        &event_context,
+        serde_json::json!({ "lists": list_slugs })
+    )
+    .await
+    .context("Failed to record the subscription event")?;
     let subscription_token = generate_subscription_token();
-    store_token(&mut transaction, subscriber_id, &subscription_token)
+    store_token(&mut transaction, subscriber_id, &subscription_token, None)
         .await
         .context("Failed to store the confirmation token for a new subscriber")?;
     transaction.commit()
         .await
         .context("Failed to commit SQL transaction to store a new subscriber")?;
-    send_confirmation_email(&email_client, new_subscriber, &base_url.0, &subscription_token)
+    send_confirmation_email(&email_client, &confirmation_email_template, new_subscriber, &base_url.0, &subscription_token)
         .await
         .context("Failed to send a confirmation email")?;
+    record_event(
+        pool.get_ref(),
+        subscriber_id,
+        EventType::ConfirmationEmailSent,
+        &event_context,
+        serde_json::json!({})
+    )
+    .await
+    .context("Failed to record the confirmation email event")?;
+
+    Ok(subscription_accepted(&request))
+}
+
+fn prefers_json(request: &HttpRequest) -> bool {
+    Accept::parse(request)
+        .map(|accept| accept.preference().essence_str() == "application/json")
+        .unwrap_or(false)
+}
 
-    Ok(HttpResponse::Ok().finish())
+//20261019 추가 / 클라이언트가 JSON을 선호하면 JSON으로, 그렇지 않으면 "메일함을 확인하세요" 페이지로 응답한다.
+//어떤 경우에도 이미 구독 중인지 여부는 응답에 드러나지 않는다.
+fn subscription_accepted(request: &HttpRequest) -> HttpResponse {
+    if prefers_json(request) {
+        HttpResponse::Ok().json(serde_json::json!({
+            "message": "Check your inbox to confirm your subscription."
+        }))
+    } else {
+        check_your_inbox_page()
+    }
 }
 
 //대소문자를 구분하는 무작위 25문자로 구성된 구독 토큰을 생성한다.
-fn generate_subscription_token() -> String {
+pub fn generate_subscription_token() -> String {
     let mut rng = thread_rng();
     std::iter::repeat_with(|| rng.sample(Alphanumeric))
         .map(char::from)
@@ -83,31 +231,30 @@ fn generate_subscription_token() -> String {
         .collect()
 }
 
+//20261019 추가 / 데이터베이스에는 토큰의 SHA-256 다이제스트만 저장한다. 데이터베이스나 백업을 읽을 수 있어도 구독을 확인할 수 없다.
+pub fn hash_subscription_token(subscription_token: &str) -> String {
+    hex::encode(Sha256::digest(subscription_token.as_bytes()))
+}
+
 #[tracing::instrument(
     name = "Send a confirmation eamil to a new subscriber",
-    skip(email_client, new_subscriber, base_url)
+    skip(email_client, template, new_subscriber, base_url)
 )]
 pub async fn send_confirmation_email(
     email_client: &EmailClient,
+    //20261019 추가
+    template: &ConfirmationEmailTemplate,
     new_subscriber: NewSubscriber,
     //20250211 추가
     base_url: &str,
     subscription_token: &str
-) -> Result<(), reqwest::Error> {
+) -> Result<(), SendEmailError> {
     //동적 루트와 함께 확인 링크르르 생성한다.
     let confirmation_link = format!("{}/subscriptions/confirm?subscription_token={}", base_url, subscription_token);
-    let plain_body = format!(
-        "Welcome to our newsletter!\nvisit {} to confirm your subscription.",
-        confirmation_link
-    );
-    let html_body = format!(
-        "Welcome to our newsletter<br />\
-        Click <a href=\"{}>hereM/a> to confirm your subscription.",
-        confirmation_link
-
-    );
+    //20261019 수정 / 인라인 문자열 대신 템플릿으로 제목과 본문을 만든다.
+    let email = template.render(&confirmation_link, new_subscriber.name.as_ref());
 
-    email_client.send_email(&new_subscriber.email, "Welcome!", &html_body, &plain_body).await
+    email_client.send_email(&new_subscriber.email, &email.subject, &email.html_body, &email.text_body).await
 }
 
 #[tracing::instrument(
@@ -117,45 +264,199 @@ pub async fn send_confirmation_email(
 
 pub async fn insert_subscriber(
     transaction: &mut Transaction<'_, Postgres>,
-    new_subscriber: &NewSubscriber
-) -> Result<Uuid, sqlx::Error> {
+    new_subscriber: &NewSubscriber,
+    //20261019 추가
+    custom_fields: &Map<String, Value>
+) -> Result<Option<Uuid>, sqlx::Error> {
     let subscriber_id = Uuid::new_v4();
-    sqlx::query!(
+    //20261019 수정 / 행이 없으면 'FOR UPDATE'로 잠글 수 없으므로 동시 요청은 UNIQUE 제약 조건에서 만난다.
+    //이미 추가된 이메일이면 'None'을 반환한다.
+    let row = sqlx::query!(
         r#"
-        INSERT INTO subscriptions (id, email, name, subscribed_at, status)
-        VALUES ($1, $2, $3, $4, 'pending_confirmation')
+        INSERT INTO subscriptions (id, email, name, subscribed_at, status, custom_fields)
+        VALUES ($1, $2, $3, $4, 'pending_confirmation', $5)
+        ON CONFLICT (email) DO NOTHING
+        RETURNING id
         "#,
         //구독자 id는 반환되거나 변수에 바운드되지 않는다.
         subscriber_id,
         new_subscriber.email.as_ref(),
         // 'as_ref'를 사용한다.
         new_subscriber.name.as_ref(),
-        Utc::now()
+        Utc::now(),
+        Value::Object(custom_fields.clone())
     )
-    .execute(transaction)
+    .fetch_optional(transaction)
     .await
     .map_err(|e| {
         tracing::error!("Failed to execute query: {:?}]", e);
         e   
     //'?'연산자를 사용해서 함수가 실패하면, 조기에 sqlx__Error를 반환한다. (오류 핸들링은 뒤에서 자세히)
     })?;
-    Ok(subscriber_id)
+    Ok(row.map(|r| r.id))
+}
+
+#[tracing::instrument(
+    name = "Get subscriber by email",
+    skip(email, transaction)
+)]
+pub async fn get_subscriber_by_email(
+    transaction: &mut Transaction<'_, Postgres>,
+    email: &SubscriberEmail
+) -> Result<Option<(Uuid, String)>, sqlx::Error> {
+    //동시에 같은 이메일로 요청이 들어오는 경우를 위해 행을 잠근다.
+    //20261019 수정 / 다른 모듈과 같이 대소문자를 구분하지 않는다.
+    let row = sqlx::query!(
+        r#"
+        SELECT id, status FROM subscriptions
+        WHERE lower(email) = lower($1)
+        ORDER BY subscribed_at
+        LIMIT 1
+        FOR UPDATE
+        "#,
+        email.as_ref()
+    )
+    .fetch_optional(transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    Ok(row.map(|r| (r.id, r.status)))
+}
+
+#[tracing::instrument(
+    name = "Restart the subscription of an existing subscriber",
+    skip(new_subscriber, transaction)
+)]
+pub async fn reset_subscriber(
+    transaction: &mut Transaction<'_, Postgres>,
+    subscriber_id: Uuid,
+    new_subscriber: &NewSubscriber,
+    //20261019 추가
+    custom_fields: &Map<String, Value>
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE subscriptions
+        SET name = $2, subscribed_at = $3, status = 'pending_confirmation', custom_fields = $4, confirmed_at = NULL
+        WHERE id = $1
+        "#,
+        subscriber_id,
+        new_subscriber.name.as_ref(),
+        Utc::now(),
+        Value::Object(custom_fields.clone())
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    //20261019 추가 / 이전에 구독했던 리스트는 새로운 더블 옵트인 사이클에서 다시 요청해야 한다.
+    sqlx::query!(
+        r#"UPDATE list_memberships SET status = 'unsubscribed' WHERE subscriber_id = $1"#,
+        subscriber_id
+    )
+    .execute(transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    Ok(())
+}
+
+#[tracing::instrument(
+    name = "Get list ids from slugs",
+    skip(transaction)
+)]
+pub async fn get_list_ids(
+    transaction: &mut Transaction<'_, Postgres>,
+    slugs: &[String]
+) -> Result<Vec<Uuid>, sqlx::Error> {
+    let rows = sqlx::query!(
+        r#"SELECT list_id FROM lists WHERE slug = ANY($1)"#,
+        slugs
+    )
+    .fetch_all(transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    Ok(rows.into_iter().map(|r| r.list_id).collect())
+}
+
+//20261019 추가 / 아직 확인되지 않은 리스트를 확인 대기 상태로 추가하고, 추가되거나 다시 대기 상태가 된 리스트 수를 반환한다.
+#[tracing::instrument(
+    name = "Add list memberships",
+    skip(transaction)
+)]
+pub async fn add_list_memberships(
+    transaction: &mut Transaction<'_, Postgres>,
+    subscriber_id: Uuid,
+    list_ids: &[Uuid]
+) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO list_memberships (list_id, subscriber_id, status)
+        SELECT list_id, $1, 'pending_confirmation'
+        FROM UNNEST($2::uuid[]) AS list_id
+        ON CONFLICT (list_id, subscriber_id) DO UPDATE
+        SET status = 'pending_confirmation'
+        WHERE list_memberships.status NOT IN ('confirmed', 'pending_confirmation')
+        "#,
+        subscriber_id,
+        list_ids
+    )
+    .execute(transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    Ok(result.rows_affected())
+}
+
+#[tracing::instrument(
+    name = "Delete subscription tokens of a subscriber",
+    skip(transaction)
+)]
+pub async fn delete_tokens(
+    transaction: &mut Transaction<'_, Postgres>,
+    subscriber_id: Uuid
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"DELETE FROM subscription_tokens WHERE subscriber_id = $1 AND consumed_at IS NULL"#,
+        subscriber_id
+    )
+    .execute(transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    Ok(())
 }
 
 #[tracing::instrument(
     name = "Store subscription token in the database",
-    skip(subscription_token, transaction)
+    skip(subscription_token, transaction, new_email)
 )]
 pub async fn store_token(
     transaction: &mut Transaction<'_, Postgres>,
     subscriber_id: Uuid,
-    subscription_token: &str
+    subscription_token: &str,
+    //20261019 추가 / 이메일 변경 요청이면 새 이메일 주소. 토큰이 확인되면 이 주소로 바뀐다.
+    new_email: Option<&SubscriberEmail>
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
-        r#"INSERT INTO subscription_tokens ( subscription_token, subscriber_id)
-        VALUES ($1, $2)"#,
-        subscription_token,
-        subscriber_id
+        r#"INSERT INTO subscription_tokens ( subscription_token, subscriber_id, new_email)
+        VALUES ($1, $2, $3)"#,
+        hash_subscription_token(subscription_token),
+        subscriber_id,
+        new_email.map(|e| e.as_ref())
     )
     .execute(transaction)
     .await
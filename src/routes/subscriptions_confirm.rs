@@ -1,73 +1,241 @@
-use actix_web::{HttpResponse, web};
-use sqlx::PgPool;
+use actix_web::{HttpRequest, HttpResponse, web};
+use sqlx::{PgPool, Postgres, Transaction};
 use uuid::Uuid;
+//20261019 추가
+use chrono::{DateTime, Utc};
+use crate::configuration::SubscriptionSettings;
+use crate::routes::hash_subscription_token;
+use crate::routes::subscription_pages::{
+    already_confirmed_page, email_already_taken_page, email_change_link_expired_page, email_changed_page,
+    expired_link_page, invalid_link_page, something_went_wrong_page, subscription_confirmed_page
+};
 
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+use crate::drip_sequences::enroll_subscriber;
 //use crate::telemetry::get_subscriber;
 
-//Parameters 구조체는 유입 요청에서 보기를 기대하는 모든 쿼리 파라미터를 정의 
+//Parameters 구조체는 유입 요청에서 보기를 기대하는 모든 쿼리 파라미터를 정의
 //serde::Deserialize를 구현해서 actix_web이 유입 요청 결로로부터 구조체를 활성화하도록 해야 됨
 #[derive(serde::Deserialize)]
 pub struct Parameters {
     subscription_token: String
 }
 
+//20261019 추가 / subscription_tokens 테이블의 한 행과 토큰 소유자의 현재 상태
+pub struct StoredToken {
+    pub subscriber_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub consumed_at: Option<DateTime<Utc>>,
+    pub subscriber_status: String,
+    //20261019 추가 / 이메일 변경 요청의 토큰이면 새 이메일 주소
+    pub new_email: Option<String>,
+    pub subscriber_email: String
+}
+
 #[tracing::instrument(
     name = "Confirm a pending subscriber",
-    skip(parameters, pool)
+    skip(parameters, pool, subscription_settings, request)
 )]
-pub async fn confirm(parameters: web::Query<Parameters>, pool: web::Data<PgPool>) -> HttpResponse {
-    let id = match get_subscriber_id_from_token(
-        &pool,
-        &parameters.subscription_token
-    ).await {
-        Ok(id) => id,
-        Err(_) => return HttpResponse::InternalServerError().finish()
-    };
-    match id {
-        //존재하지 않는 토큰
-        None => HttpResponse::Unauthorized().finish(),
-        Some(subscriber_id) => {
-            if confirm_subscriber(&pool, subscriber_id).await.is_err() {
-                return HttpResponse::InternalServerError().finish();
-            }
-            HttpResponse::Ok().finish()
+pub async fn confirm(
+    parameters: web::Query<Parameters>,
+    pool: web::Data<PgPool>,
+    //20261019 추가
+    subscription_settings: web::Data<SubscriptionSettings>,
+    //20261019 추가 / 구독 동의 기록에 남길 요청 정보
+    request: HttpRequest
+) -> HttpResponse {
+    let event_context = EventContext::from_request(&request, EventSource::EmailLink);
+    //20261019 수정 / 빈 바디 대신 구독자가 읽을 수 있는 HTML 페이지를 반환한다.
+    match try_confirm(&parameters.subscription_token, &pool, &subscription_settings, &event_context).await {
+        Ok(response) => response,
+        Err(e) => {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to confirm a subscriber"
+            );
+            something_went_wrong_page()
         }
     }
 }
 
+async fn try_confirm(
+    subscription_token: &str,
+    pool: &PgPool,
+    subscription_settings: &SubscriptionSettings,
+    event_context: &EventContext
+) -> Result<HttpResponse, anyhow::Error> {
+    let token = match get_token(pool, subscription_token).await? {
+        Some(token) => token,
+        //존재하지 않는 토큰
+        None => return Ok(invalid_link_page())
+    };
+    //20261019 추가 / 이메일 변경 요청의 토큰은 별도로 처리한다.
+    if let Some(new_email) = token.new_email.clone() {
+        return try_confirm_email_change(subscription_token, pool, subscription_settings, event_context, token, new_email).await;
+    }
+    //이미 사용된 토큰은 다시 사용할 수 없다. 구독이 유지되고 있다면 이미 확인되었음을 알려준다.
+    if token.consumed_at.is_some() {
+        return if token.subscriber_status == "confirmed" {
+            Ok(already_confirmed_page())
+        } else {
+            Ok(invalid_link_page())
+        };
+    }
+    //20261019 추가 / 유효 시간이 지난 토큰은 거부하고 새 링크를 요청할 수 있는 페이지를 보여준다.
+    if token.created_at + subscription_settings.confirmation_token_ttl() < Utc::now() {
+        let subscriber = sqlx::query!(
+            r#"SELECT email, name FROM subscriptions WHERE id = $1"#,
+            token.subscriber_id
+        )
+        .fetch_one(pool)
+        .await?;
+        return Ok(expired_link_page(&subscriber.email, &subscriber.name));
+    }
+    let mut transaction = pool.begin().await?;
+    if !consume_token(&mut transaction, subscription_token).await? {
+        //동시에 들어온 다른 요청이 먼저 토큰을 사용했다.
+        return Ok(already_confirmed_page());
+    }
+    confirm_subscriber(&mut transaction, token.subscriber_id).await?;
+    record_event(&mut transaction, token.subscriber_id, EventType::Confirmed, event_context, serde_json::json!({})).await?;
+    transaction.commit().await?;
+    Ok(subscription_confirmed_page())
+}
+
+//20261019 추가 / 새 이메일 주소의 소유가 확인되면 구독자의 이메일을 바꾼다.
+async fn try_confirm_email_change(
+    subscription_token: &str,
+    pool: &PgPool,
+    subscription_settings: &SubscriptionSettings,
+    event_context: &EventContext,
+    token: StoredToken,
+    new_email: String
+) -> Result<HttpResponse, anyhow::Error> {
+    if token.consumed_at.is_some() {
+        return if token.subscriber_email == new_email {
+            Ok(email_changed_page())
+        } else {
+            Ok(invalid_link_page())
+        };
+    }
+    if token.subscriber_status != "confirmed" {
+        return Ok(invalid_link_page());
+    }
+    if token.created_at + subscription_settings.confirmation_token_ttl() < Utc::now() {
+        return Ok(email_change_link_expired_page());
+    }
+    let mut transaction = pool.begin().await?;
+    if !consume_token(&mut transaction, subscription_token).await? {
+        return Ok(email_changed_page());
+    }
+    //링크를 요청한 뒤 다른 구독자가 같은 주소로 구독했을 수 있다.
+    //20261019 수정 / 대소문자만 다른 주소도 같은 주소로 본다.
+    let taken = sqlx::query!(
+        r#"SELECT id FROM subscriptions WHERE lower(email) = lower($1) AND id <> $2"#,
+        new_email,
+        token.subscriber_id
+    )
+    .fetch_optional(&mut transaction)
+    .await?
+    .is_some();
+    if taken {
+        return Ok(email_already_taken_page());
+    }
+    //20261019 수정 / 확인 후 변경 전에 같은 주소로 동시에 구독한 경우 고유 제약 조건 위반이 난다.
+    if let Err(e) = change_subscriber_email(&mut transaction, token.subscriber_id, &new_email).await {
+        return match e.as_database_error().and_then(|e| e.code()) {
+            Some(code) if code == "23505" => Ok(email_already_taken_page()),
+            _ => Err(e.into())
+        };
+    }
+    record_event(
+        &mut transaction,
+        token.subscriber_id,
+        EventType::PreferencesChanged,
+        event_context,
+        serde_json::json!({ "changed": ["email"] })
+    )
+    .await?;
+    transaction.commit().await?;
+    Ok(email_changed_page())
+}
+
+//발송 큐는 구독자 ID를 참조하므로 아직 발송되지 않은 이슈도 새 주소로 발송된다.
+#[tracing::instrument(
+    name = "Change the email of a subscriber",
+    skip(transaction, new_email)
+)]
+pub async fn change_subscriber_email(
+    transaction: &mut Transaction<'_, Postgres>,
+    subscriber_id: Uuid,
+    new_email: &str
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"UPDATE subscriptions SET email = $2 WHERE id = $1"#,
+        subscriber_id,
+        new_email
+    )
+    .execute(transaction)
+    .await?;
+    Ok(())
+}
+
 #[tracing::instrument(
     name = "Mark subscriber as confirm",
-    skip(subscriber_id, pool)
+    skip(subscriber_id, transaction)
 )]
 pub async fn confirm_subscriber(
-    pool: &PgPool,
+    transaction: &mut Transaction<'_, Postgres>,
     subscriber_id: Uuid
 ) -> Result<(), sqlx::Error> {
     sqlx::query!(
-        r#"UPDATE subscriptions SET status = 'confirmed' WHERE id = $1"#,
+        r#"UPDATE subscriptions SET status = 'confirmed', confirmed_at = COALESCE(confirmed_at, now()) WHERE id = $1"#,
+        subscriber_id,
+    )
+    .execute(&mut *transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    //20261019 추가 / 확인 대기 중인 리스트 구독도 함께 확인한다.
+    sqlx::query!(
+        r#"
+        UPDATE list_memberships SET status = 'confirmed'
+        WHERE subscriber_id = $1 AND status = 'pending_confirmation'
+        "#,
         subscriber_id,
     )
-    .execute(pool)
+    .execute(&mut *transaction)
     .await
     .map_err(|e| {
         tracing::error!("Failed to execute query: {:?}", e);
         e
     })?;
+    //20261019 추가 / 확인한 리스트의 환영 이메일 시퀀스를 시작한다.
+    enroll_subscriber(transaction, subscriber_id).await?;
     Ok(())
 }
 
 #[tracing::instrument(
-    name = "Get subscriber_id from token",
+    name = "Get subscription token",
     skip(subscription_token, pool)
 )]
-pub async fn get_subscriber_id_from_token(
+pub async fn get_token(
     pool: &PgPool,
     subscription_token: &str
-) -> Result<Option<Uuid>, sqlx::Error> {
-    let result = sqlx::query!(
-        "SELECT subscriber_id FROM subscription_tokens \
-        WHERE subscription_token = $1",
-        subscription_token
+) -> Result<Option<StoredToken>, sqlx::Error> {
+    let result = sqlx::query_as!(
+        StoredToken,
+        r#"
+        SELECT t.subscriber_id, t.created_at, t.consumed_at, s.status AS subscriber_status,
+            t.new_email, s.email AS subscriber_email
+        FROM subscription_tokens t
+        JOIN subscriptions s ON s.id = t.subscriber_id
+        WHERE t.subscription_token = $1
+        "#,
+        hash_subscription_token(subscription_token)
     )
     .fetch_optional(pool)
     .await
@@ -75,5 +243,31 @@ pub async fn get_subscriber_id_from_token(
         tracing::error!("Failed to execute query: {:?}", e);
         e
     })?;
-    Ok(result.map(|r| r.subscriber_id))
-}
\ No newline at end of file
+    Ok(result)
+}
+
+//20261019 추가 / 토큰을 사용된 것으로 표시한다. 아직 사용되지 않은 토큰이었을 때만 'true'를 반환한다.
+#[tracing::instrument(
+    name = "Mark subscription token as consumed",
+    skip(subscription_token, transaction)
+)]
+pub async fn consume_token(
+    transaction: &mut Transaction<'_, Postgres>,
+    subscription_token: &str
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        UPDATE subscription_tokens
+        SET consumed_at = now()
+        WHERE subscription_token = $1 AND consumed_at IS NULL
+        "#,
+        hash_subscription_token(subscription_token)
+    )
+    .execute(transaction)
+    .await
+    .map_err(|e| {
+        tracing::error!("Failed to execute query: {:?}", e);
+        e
+    })?;
+    Ok(result.rows_affected() == 1)
+}
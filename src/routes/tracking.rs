@@ -0,0 +1,61 @@
+use actix_web::http::header::{CacheControl, CacheDirective, ContentType, LOCATION};
+use actix_web::{web, HttpRequest, HttpResponse};
+use sqlx::PgPool;
+use crate::signed_links::LinkSigner;
+use crate::routes::subscription_pages::{invalid_link_page, something_went_wrong_page};
+use crate::tracking::{record_click, record_open, UserAgentClass, TRANSPARENT_GIF};
+
+//20261019 추가 / 열람 추적 이미지. 토큰이 잘못되었거나 기록하지 못해도 메일 클라이언트에는 항상 이미지를 돌려준다.
+#[tracing::instrument(name = "Record an issue open", skip_all)]
+pub async fn track_open(
+    token: web::Path<String>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>,
+    request: HttpRequest
+) -> HttpResponse {
+    if let Some((issue_id, subscriber_id)) = link_signer.verify_open_token(&token) {
+        let user_agent = request
+            .headers()
+            .get("User-Agent")
+            .and_then(|h| h.to_str().ok());
+        let user_agent_class = UserAgentClass::classify(user_agent);
+        if let Err(e) = record_open(&pool, issue_id, subscriber_id, user_agent_class).await {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to record an issue open"
+            );
+        }
+    }
+    HttpResponse::Ok()
+        .content_type(ContentType("image/gif".parse().unwrap()))
+        .insert_header(CacheControl(vec![CacheDirective::NoStore]))
+        .body(TRANSPARENT_GIF)
+}
+
+//20261019 추가 / 클릭 추적 리다이렉트. 서명이 올바르고 이슈에 등록된 링크일 때만 리다이렉트한다.
+#[tracing::instrument(name = "Record a link click", skip_all)]
+pub async fn track_click(
+    token: web::Path<String>,
+    pool: web::Data<PgPool>,
+    link_signer: web::Data<LinkSigner>
+) -> HttpResponse {
+    let (issue_id, subscriber_id, link_id) = match link_signer.verify_click_token(&token) {
+        Some(ids) => ids,
+        None => return invalid_link_page()
+    };
+    match record_click(&pool, issue_id, subscriber_id, link_id).await {
+        Ok(Some(url)) => HttpResponse::Found()
+            .insert_header((LOCATION, url))
+            .finish(),
+        Ok(None) => invalid_link_page(),
+        Err(e) => {
+            tracing::error!(
+                error.cause_chain = ?e,
+                error.message = %e,
+                "Failed to record a link click"
+            );
+            something_went_wrong_page()
+        }
+    }
+}
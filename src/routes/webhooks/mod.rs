@@ -0,0 +1,4 @@
+//20261019 추가 / 외부 서비스가 호출하는 웹훅
+mod postmark;
+
+pub use postmark::postmark_webhook;
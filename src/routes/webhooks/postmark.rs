@@ -0,0 +1,127 @@
+use actix_web::http::header::{self, HeaderMap, HeaderValue};
+use actix_web::http::StatusCode;
+use actix_web::{web, HttpRequest, HttpResponse, ResponseError};
+use anyhow::Context;
+use secrecy::ExposeSecret;
+use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+use crate::bounces::{process_bounce, BounceKind};
+use crate::configuration::WebhookSettings;
+use crate::routes::error_chain_fmt;
+
+//Postmark 반송/스팸 신고 웹훅의 바디 중 사용하는 필드
+#[derive(serde::Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct PostmarkEvent {
+    record_type: String,
+    #[serde(rename = "ID")]
+    id: i64,
+    #[serde(rename = "Type")]
+    bounce_type: Option<String>,
+    email: String,
+    #[serde(default)]
+    description: Option<String>
+}
+
+//20261019 추가 / Postmark 웹훅 수신. Postmark에는 URL에 Basic 인증 정보를 넣어서 등록한다.
+//(https://postmark:<password>@<host>/webhooks/postmark)
+#[tracing::instrument(
+    name = "Receive a Postmark webhook",
+    skip(body, pool, settings, request),
+    fields(record_type = tracing::field::Empty, event_id = tracing::field::Empty)
+)]
+pub async fn postmark_webhook(
+    //20261019 수정 / 인증하기 전에는 바디를 파싱하지 않는다.
+    body: web::Bytes,
+    pool: web::Data<PgPool>,
+    settings: web::Data<WebhookSettings>,
+    request: HttpRequest
+) -> Result<HttpResponse, WebhookError> {
+    authenticate(request.headers(), &settings).map_err(WebhookError::AuthError)?;
+    let event: PostmarkEvent = serde_json::from_slice(&body).map_err(WebhookError::InvalidPayload)?;
+    tracing::Span::current()
+        .record("record_type", tracing::field::display(&event.record_type))
+        .record("event_id", tracing::field::display(event.id));
+    let kind = match BounceKind::from_postmark(&event.record_type, event.bounce_type.as_deref()) {
+        Some(kind) => kind,
+        //처리하지 않는 알림도 200으로 응답해야 Postmark가 다시 보내지 않는다.
+        None => return Ok(HttpResponse::Ok().finish())
+    };
+    let details = serde_json::json!({
+        "record_type": event.record_type,
+        "type": event.bounce_type,
+        "description": event.description,
+        "postmark_id": event.id
+    });
+    let outcome = process_bounce(
+        &pool,
+        "postmark",
+        &event.id.to_string(),
+        &event.email,
+        kind,
+        details,
+        settings.soft_bounce_limit
+    )
+    .await
+    .context("Failed to process a bounce")?;
+    tracing::info!(?kind, ?outcome, "Processed a Postmark webhook");
+    Ok(HttpResponse::Ok().finish())
+}
+
+fn authenticate(headers: &HeaderMap, settings: &WebhookSettings) -> Result<(), anyhow::Error> {
+    let header_value = headers
+        .get(header::AUTHORIZATION)
+        .context("The 'Authorization' header was missing")?
+        .to_str()
+        .context("The 'Authorization' header was not a valid UTF8 string")?;
+    let base64encoded_segment = header_value
+        .strip_prefix("Basic ")
+        .context("The authorization scheme was not 'Basic'.")?;
+    let decoded_bytes = base64::decode_config(base64encoded_segment, base64::STANDARD)
+        .context("Failed to base64-decode 'Basic' credentials.")?;
+    let decoded_credentials = String::from_utf8(decoded_bytes)
+        .context("The decoded credential string is not valid UTF8.")?;
+    let (username, password) = decoded_credentials
+        .split_once(':')
+        .context("A password must be provided in 'Basic' auth.")?;
+    //20261019 수정 / 응답 시간으로 자격 증명을 추측할 수 없도록 상수 시간으로 비교한다.
+    let username_matches = username.as_bytes().ct_eq(settings.username.as_bytes());
+    let password_matches = password.as_bytes().ct_eq(settings.password.expose_secret().as_bytes());
+    if !bool::from(username_matches & password_matches) {
+        anyhow::bail!("Invalid webhook credentials.");
+    }
+    Ok(())
+}
+
+#[derive(thiserror::Error)]
+pub enum WebhookError {
+    #[error("Authentication failed")]
+    AuthError(#[source] anyhow::Error),
+    #[error("The webhook body is not a valid Postmark event")]
+    InvalidPayload(#[source] serde_json::Error),
+    #[error(transparent)]
+    UnexpectError(#[from] anyhow::Error)
+}
+
+impl std::fmt::Debug for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        error_chain_fmt(self, f)
+    }
+}
+
+impl ResponseError for WebhookError {
+    fn error_response(&self) -> HttpResponse {
+        match self {
+            WebhookError::UnexpectError(_) => HttpResponse::new(StatusCode::INTERNAL_SERVER_ERROR),
+            WebhookError::InvalidPayload(_) => HttpResponse::new(StatusCode::BAD_REQUEST),
+            WebhookError::AuthError(_) => {
+                let mut response = HttpResponse::new(StatusCode::UNAUTHORIZED);
+                response.headers_mut().insert(
+                    header::WWW_AUTHENTICATE,
+                    HeaderValue::from_static(r#"Basic realm="webhooks""#)
+                );
+                response
+            }
+        }
+    }
+}
@@ -0,0 +1,433 @@
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use sqlx::{Postgres, QueryBuilder};
+
+//20261019 추가 / 이슈를 보낼 구독자를 고르는 작은 세그먼트 정의 언어.
+//예) status = confirmed AND (tag = beta OR field.country = "KR") AND subscribed_at >= now-30d
+//파싱한 결과는 'subscriptions s'에 대한 SQL 조건으로 컴파일된다. 모든 값은 바인딩 파라미터로 전달된다.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Segment {
+    And(Box<Segment>, Box<Segment>),
+    Or(Box<Segment>, Box<Segment>),
+    Not(Box<Segment>),
+    Condition(Condition)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Condition {
+    Status { negated: bool, status: String },
+    Tag { negated: bool, tag: String },
+    SubscribedAt { op: Comparison, at: DateTime<Utc> },
+    Field { name: String, op: Comparison, value: FieldValue }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge
+}
+
+impl Comparison {
+    fn as_sql(&self) -> &'static str {
+        match self {
+            Comparison::Eq => "=",
+            Comparison::Ne => "<>",
+            Comparison::Lt => "<",
+            Comparison::Le => "<=",
+            Comparison::Gt => ">",
+            Comparison::Ge => ">="
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Number(f64)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Text(String),
+    Op(Comparison),
+    LParen,
+    RParen
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' => {
+                chars.next();
+                let mut text = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(escaped) => text.push(escaped),
+                            None => return Err("Unterminated string literal".into())
+                        },
+                        Some(c) => text.push(c),
+                        None => return Err("Unterminated string literal".into())
+                    }
+                }
+                tokens.push(Token::Text(text));
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let followed_by_eq = chars.peek() == Some(&'=');
+                if followed_by_eq {
+                    chars.next();
+                }
+                let op = match (c, followed_by_eq) {
+                    ('=', false) => Comparison::Eq,
+                    ('!', true) => Comparison::Ne,
+                    ('<', false) => Comparison::Lt,
+                    ('<', true) => Comparison::Le,
+                    ('>', false) => Comparison::Gt,
+                    ('>', true) => Comparison::Ge,
+                    _ => return Err(format!("Unexpected character '{}'", c))
+                };
+                tokens.push(Token::Op(op));
+            }
+            c if c.is_alphanumeric() || "_-.:+".contains(c) => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || "_-.:+".contains(c) {
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                tokens.push(Token::Word(word));
+            }
+            c => return Err(format!("Unexpected character '{}'", c))
+        }
+    }
+    Ok(tokens)
+}
+
+//20261019 추가 / 괄호와 'not'의 중첩 한도. 재귀 하강 파서가 스택을 넘치지 않도록 한다.
+const MAX_DEPTH: usize = 32;
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    now: DateTime<Utc>,
+    //20261019 추가 / 현재 괄호와 'not'의 중첩 깊이
+    depth: usize
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(w)) if w.eq_ignore_ascii_case(keyword))
+    }
+
+    fn parse_or(&mut self) -> Result<Segment, String> {
+        let mut left = self.parse_and()?;
+        while self.peek_keyword("or") {
+            self.next();
+            let right = self.parse_and()?;
+            left = Segment::Or(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_and(&mut self) -> Result<Segment, String> {
+        let mut left = self.parse_unary()?;
+        while self.peek_keyword("and") {
+            self.next();
+            let right = self.parse_unary()?;
+            left = Segment::And(Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn parse_unary(&mut self) -> Result<Segment, String> {
+        if !self.peek_keyword("not") && self.peek() != Some(&Token::LParen) {
+            return self.parse_condition().map(Segment::Condition);
+        }
+        //20261019 수정 / 중첩이 너무 깊으면 재귀하지 않고 오류를 반환한다.
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err("Segment is nested too deeply".into());
+        }
+        let segment = if self.peek_keyword("not") {
+            self.next();
+            Segment::Not(Box::new(self.parse_unary()?))
+        } else {
+            self.next();
+            let inner = self.parse_or()?;
+            match self.next() {
+                Some(Token::RParen) => inner,
+                _ => return Err("Expected ')'".into())
+            }
+        };
+        self.depth -= 1;
+        Ok(segment)
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, String> {
+        let subject = match self.next() {
+            Some(Token::Word(word)) => word,
+            Some(token) => return Err(format!("Expected a condition, found {:?}", token)),
+            None => return Err("Expected a condition, found the end of the segment".into())
+        };
+        let op = match self.next() {
+            Some(Token::Op(op)) => op,
+            _ => return Err(format!("Expected a comparison operator after '{}'", subject))
+        };
+        let value = match self.next() {
+            Some(Token::Word(word)) => (word, false),
+            Some(Token::Text(text)) => (text, true),
+            _ => return Err(format!("Expected a value after '{}'", subject))
+        };
+        let equality_only = |op: Comparison| -> Result<bool, String> {
+            match op {
+                Comparison::Eq => Ok(false),
+                Comparison::Ne => Ok(true),
+                _ => Err(format!("'{}' only supports '=' and '!='", subject))
+            }
+        };
+        match subject.to_lowercase().as_str() {
+            "status" => Ok(Condition::Status { negated: equality_only(op)?, status: value.0 }),
+            "tag" => Ok(Condition::Tag { negated: equality_only(op)?, tag: value.0 }),
+            "subscribed_at" => Ok(Condition::SubscribedAt { op, at: self.parse_date(&value.0)? }),
+            _ => match subject.strip_prefix("field.") {
+                Some(name) if !name.is_empty() => {
+                    let value = match (value.0.parse::<f64>(), value.1) {
+                        //따옴표로 감싼 값은 숫자처럼 보여도 문자열로 비교한다.
+                        (Ok(number), false) => FieldValue::Number(number),
+                        _ => {
+                            equality_only(op)?;
+                            FieldValue::Text(value.0)
+                        }
+                    };
+                    Ok(Condition::Field { name: name.to_string(), op, value })
+                }
+                _ => Err(format!("Unknown segment attribute '{}'", subject))
+            }
+        }
+    }
+
+    //'2024-01-31' 같은 날짜 또는 'now-30d' 같은 상대 날짜
+    fn parse_date(&self, value: &str) -> Result<DateTime<Utc>, String> {
+        if let Some(days) = value.strip_prefix("now-").and_then(|v| v.strip_suffix('d')) {
+            //20261019 수정 / 음수이거나 너무 큰 일수는 오버플로로 패닉하지 않도록 오류로 처리한다.
+            return days
+                .parse::<i64>()
+                .ok()
+                .filter(|days| *days >= 0)
+                .and_then(chrono::Duration::try_days)
+                .and_then(|days| self.now.checked_sub_signed(days))
+                .ok_or_else(|| format!("'{}' is not a valid relative date", value));
+        }
+        if value == "now" {
+            return Ok(self.now);
+        }
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map(|date| Utc.from_utc_datetime(&date.and_time(chrono::NaiveTime::MIN)))
+            .map_err(|_| format!("'{}' is not a valid date. Use YYYY-MM-DD or now-<N>d", value))
+    }
+}
+
+impl Segment {
+    pub fn parse(input: &str) -> Result<Segment, String> {
+        Self::parse_at(input, Utc::now())
+    }
+
+    //상대 날짜의 기준 시각을 지정할 수 있다. (테스트용)
+    pub fn parse_at(input: &str, now: DateTime<Utc>) -> Result<Segment, String> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err("The segment is empty".into());
+        }
+        let mut parser = Parser { tokens, position: 0, now, depth: 0 };
+        let segment = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(segment),
+            Some(token) => Err(format!("Unexpected {:?} at the end of the segment", token))
+        }
+    }
+
+    //'subscriptions s'를 기준으로 한 SQL 조건을 'query'에 추가한다.
+    pub fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        match self {
+            Segment::And(left, right) | Segment::Or(left, right) => {
+                let keyword = if matches!(self, Segment::And(..)) { " AND " } else { " OR " };
+                query.push("(");
+                left.push_sql(query);
+                query.push(keyword);
+                right.push_sql(query);
+                query.push(")");
+            }
+            Segment::Not(inner) => {
+                query.push("NOT (");
+                inner.push_sql(query);
+                query.push(")");
+            }
+            Segment::Condition(condition) => condition.push_sql(query)
+        }
+    }
+}
+
+impl Condition {
+    fn push_sql(&self, query: &mut QueryBuilder<'_, Postgres>) {
+        match self {
+            Condition::Status { negated, status } => {
+                query.push(if *negated { "s.status <> " } else { "s.status = " });
+                query.push_bind(status.clone());
+            }
+            Condition::Tag { negated, tag } => {
+                query.push(if *negated { "NOT " } else { "" });
+                query.push("EXISTS (SELECT 1 FROM subscriber_tags t WHERE t.subscriber_id = s.id AND t.tag = ");
+                query.push_bind(tag.clone());
+                query.push(")");
+            }
+            Condition::SubscribedAt { op, at } => {
+                query.push("s.subscribed_at ");
+                query.push(op.as_sql());
+                query.push(" ");
+                query.push_bind(*at);
+            }
+            Condition::Field { name, op, value: FieldValue::Text(text) } => {
+                query.push("(s.custom_fields ->> ");
+                query.push_bind(name.clone());
+                query.push(if *op == Comparison::Ne { ") IS DISTINCT FROM " } else { ") = " });
+                query.push_bind(text.clone());
+            }
+            Condition::Field { name, op, value: FieldValue::Number(number) } => {
+                //숫자가 아닌 값이 저장되어 있어도 쿼리가 실패하지 않도록 JSON 타입을 먼저 확인한다.
+                query.push("(CASE WHEN jsonb_typeof(s.custom_fields -> ");
+                query.push_bind(name.clone());
+                query.push(") = 'number' THEN (s.custom_fields ->> ");
+                query.push_bind(name.clone());
+                query.push(")::float8 END) ");
+                query.push(op.as_sql());
+                query.push(" ");
+                query.push_bind(*number);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Comparison, Condition, FieldValue, Segment};
+    use chrono::{TimeZone, Utc};
+    use claim::assert_err;
+    use sqlx::{Postgres, QueryBuilder};
+
+    fn compile(segment: &Segment) -> String {
+        let mut query = QueryBuilder::<Postgres>::new("");
+        segment.push_sql(&mut query);
+        query.sql().to_string()
+    }
+
+    #[test]
+    fn a_single_tag_condition_is_parsed() {
+        let segment = Segment::parse("tag = beta").unwrap();
+        assert_eq!(
+            segment,
+            Segment::Condition(Condition::Tag { negated: false, tag: "beta".into() })
+        );
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let segment = Segment::parse("tag = a OR tag = b AND status = confirmed").unwrap();
+        assert!(matches!(segment, Segment::Or(_, ref right) if matches!(**right, Segment::And(..))));
+    }
+
+    #[test]
+    fn relative_dates_are_resolved_against_now() {
+        let now = Utc.with_ymd_and_hms(2024, 3, 31, 12, 0, 0).unwrap();
+        let segment = Segment::parse_at("subscribed_at >= now-30d", now).unwrap();
+        assert_eq!(
+            segment,
+            Segment::Condition(Condition::SubscribedAt {
+                op: Comparison::Ge,
+                at: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap()
+            })
+        );
+    }
+
+    #[test]
+    fn out_of_range_and_negative_relative_dates_are_rejected() {
+        for input in ["now-100000000d", "now-9223372036854775807d", "now--5d"] {
+            let result = Segment::parse(&format!("subscribed_at >= {}", input));
+            assert_eq!(result, Err(format!("'{}' is not a valid relative date", input)));
+        }
+    }
+
+    #[test]
+    fn deeply_nested_segments_are_rejected() {
+        let parens = format!("{}status = confirmed{}", "(".repeat(5000), ")".repeat(5000));
+        let nots = format!("{}status = confirmed", "not ".repeat(5000));
+        for input in [parens, nots] {
+            assert_eq!(Segment::parse(&input), Err("Segment is nested too deeply".to_string()));
+        }
+        let nested = format!("{}status = confirmed{}", "(".repeat(32), ")".repeat(32));
+        assert!(Segment::parse(&nested).is_ok());
+    }
+
+    #[test]
+    fn numeric_and_quoted_field_values_are_distinguished() {
+        let number = Segment::parse("field.age > 30").unwrap();
+        let text = Segment::parse(r#"field.zip = "01234""#).unwrap();
+        assert!(matches!(number, Segment::Condition(Condition::Field { value: FieldValue::Number(_), .. })));
+        assert!(matches!(text, Segment::Condition(Condition::Field { value: FieldValue::Text(_), .. })));
+    }
+
+    #[test]
+    fn values_are_compiled_to_bind_parameters() {
+        let segment = Segment::parse(r#"NOT (tag = beta OR field.country = "KR'; DROP TABLE subscriptions; --")"#).unwrap();
+        let sql = compile(&segment);
+        assert_eq!(
+            sql,
+            "NOT ((EXISTS (SELECT 1 FROM subscriber_tags t WHERE t.subscriber_id = s.id AND t.tag = $1) \
+            OR (s.custom_fields ->> $2) = $3))"
+        );
+        assert!(!sql.contains("DROP TABLE"));
+    }
+
+    #[test]
+    fn invalid_segments_are_rejected() {
+        assert_err!(Segment::parse(""));
+        assert_err!(Segment::parse("tag beta"));
+        assert_err!(Segment::parse("tag > beta"));
+        assert_err!(Segment::parse("colour = red"));
+        assert_err!(Segment::parse("(tag = beta"));
+        assert_err!(Segment::parse("subscribed_at > yesterday"));
+        assert_err!(Segment::parse("tag = beta status = confirmed"));
+        assert_err!(Segment::parse(r#"field.name > "abc""#));
+    }
+}
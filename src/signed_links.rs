@@ -0,0 +1,165 @@
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+use uuid::Uuid;
+
+//20261019 추가 / 이메일에 넣는 링크를 HMAC으로 서명한다.
+//토큰은 '<payload>.<hex 서명>' 형태이며, 용도(purpose)를 서명에 포함해서 다른 용도의 토큰으로 재사용할 수 없게 한다.
+#[derive(Clone)]
+pub struct LinkSigner {
+    base_url: String,
+    hmac_secret: Secret<String>
+}
+
+impl LinkSigner {
+    pub fn new(base_url: String, hmac_secret: Secret<String>) -> Self {
+        Self { base_url, hmac_secret }
+    }
+
+    fn mac(&self, purpose: &str, payload: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(self.hmac_secret.expose_secret().as_bytes())
+            .expect("HMAC can take a key of any size");
+        mac.update(purpose.as_bytes());
+        mac.update(b":");
+        mac.update(payload.as_bytes());
+        mac
+    }
+
+    pub fn sign(&self, purpose: &str, payload: &str) -> String {
+        let tag = self.mac(purpose, payload).finalize().into_bytes();
+        format!("{}.{}", payload, hex::encode(tag))
+    }
+
+    //서명이 올바르면 payload를 반환한다. 비교는 상수 시간에 이루어진다.
+    pub fn verify(&self, purpose: &str, token: &str) -> Option<String> {
+        let (payload, tag) = token.rsplit_once('.')?;
+        let tag = hex::decode(tag).ok()?;
+        self.mac(purpose, payload).verify_slice(&tag).ok()?;
+        Some(payload.to_string())
+    }
+
+    //구독자가 자신의 구독 설정을 바꿀 수 있는 링크. 모든 이슈 이메일에 포함된다.
+    pub fn preferences_link(&self, subscriber_id: Uuid) -> String {
+        format!(
+            "{}/preferences?token={}",
+            self.base_url,
+            self.sign("preferences", &subscriber_id.to_string())
+        )
+    }
+
+    pub fn verify_preferences_token(&self, token: &str) -> Option<Uuid> {
+        self.verify("preferences", token)
+            .and_then(|payload| Uuid::parse_str(&payload).ok())
+    }
+
+    //20261019 추가 / 이슈 열람 추적 이미지. 이슈와 구독자를 함께 서명한다.
+    pub fn open_pixel_link(&self, issue_id: Uuid, subscriber_id: Uuid) -> String {
+        format!(
+            "{}/o/{}",
+            self.base_url,
+            self.sign("open", &format!("{}:{}", issue_id, subscriber_id))
+        )
+    }
+
+    pub fn verify_open_token(&self, token: &str) -> Option<(Uuid, Uuid)> {
+        let payload = self.verify("open", token)?;
+        let (issue_id, subscriber_id) = payload.split_once(':')?;
+        Some((Uuid::parse_str(issue_id).ok()?, Uuid::parse_str(subscriber_id).ok()?))
+    }
+
+    //20261019 추가 / '브라우저에서 보기' 링크. 구독자 전용 이슈는 이슈와 구독자를 서명한 토큰이 있어야 볼 수 있다.
+    pub fn archive_link(&self, slug: &str, issue_id: Uuid, subscriber_id: Option<Uuid>) -> String {
+        let link = format!("{}/archive/{}", self.base_url, urlencoding::encode(slug));
+        match subscriber_id {
+            Some(subscriber_id) => format!(
+                "{}?token={}",
+                link,
+                self.sign("archive", &format!("{}:{}", issue_id, subscriber_id))
+            ),
+            None => link
+        }
+    }
+
+    pub fn verify_archive_token(&self, token: &str) -> Option<(Uuid, Uuid)> {
+        let payload = self.verify("archive", token)?;
+        let (issue_id, subscriber_id) = payload.split_once(':')?;
+        Some((Uuid::parse_str(issue_id).ok()?, Uuid::parse_str(subscriber_id).ok()?))
+    }
+
+    //20261019 추가 / 클릭 추적 리다이렉트. URL 대신 등록된 링크 ID를 서명하므로 임의의 주소로 리다이렉트할 수 없다.
+    pub fn click_link(&self, issue_id: Uuid, subscriber_id: Uuid, link_id: Uuid) -> String {
+        format!(
+            "{}/r/{}",
+            self.base_url,
+            self.sign("click", &format!("{}:{}:{}", issue_id, subscriber_id, link_id))
+        )
+    }
+
+    pub fn verify_click_token(&self, token: &str) -> Option<(Uuid, Uuid, Uuid)> {
+        let payload = self.verify("click", token)?;
+        let mut ids = payload.split(':').map(Uuid::parse_str);
+        match (ids.next(), ids.next(), ids.next(), ids.next()) {
+            (Some(Ok(issue_id)), Some(Ok(subscriber_id)), Some(Ok(link_id)), None) => {
+                Some((issue_id, subscriber_id, link_id))
+            }
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LinkSigner;
+    use secrecy::Secret;
+    use uuid::Uuid;
+
+    fn signer(secret: &str) -> LinkSigner {
+        LinkSigner::new("http://127.0.0.1".into(), Secret::new(secret.into()))
+    }
+
+    #[test]
+    fn a_signed_token_is_verified() {
+        let token = signer("secret").sign("preferences", "payload");
+        assert_eq!(signer("secret").verify("preferences", &token), Some("payload".into()));
+    }
+
+    #[test]
+    fn a_token_signed_with_another_secret_is_rejected() {
+        let token = signer("secret").sign("preferences", "payload");
+        assert_eq!(signer("another-secret").verify("preferences", &token), None);
+    }
+
+    #[test]
+    fn a_token_signed_for_another_purpose_is_rejected() {
+        let token = signer("secret").sign("click", "payload");
+        assert_eq!(signer("secret").verify("preferences", &token), None);
+    }
+
+    #[test]
+    fn an_open_token_identifies_the_issue_and_the_subscriber() {
+        let (issue_id, subscriber_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let link = signer("secret").open_pixel_link(issue_id, subscriber_id);
+        let token = link.strip_prefix("http://127.0.0.1/o/").unwrap();
+        assert_eq!(signer("secret").verify_open_token(token), Some((issue_id, subscriber_id)));
+        assert_eq!(signer("secret").verify_preferences_token(token), None);
+    }
+
+    #[test]
+    fn a_click_token_identifies_the_issue_the_subscriber_and_the_link() {
+        let (issue_id, subscriber_id, link_id) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let link = signer("secret").click_link(issue_id, subscriber_id, link_id);
+        let token = link.strip_prefix("http://127.0.0.1/r/").unwrap();
+        assert_eq!(signer("secret").verify_click_token(token), Some((issue_id, subscriber_id, link_id)));
+        assert_eq!(signer("secret").verify_open_token(token), None);
+    }
+
+    #[test]
+    fn a_tampered_payload_is_rejected() {
+        let subscriber_id = Uuid::new_v4();
+        let token = signer("secret").sign("preferences", &subscriber_id.to_string());
+        let (_, tag) = token.rsplit_once('.').unwrap();
+        let tampered = format!("{}.{}", Uuid::new_v4(), tag);
+        assert_eq!(signer("secret").verify_preferences_token(&tampered), None);
+        assert_eq!(signer("secret").verify_preferences_token(&token), Some(subscriber_id));
+    }
+}
@@ -10,6 +10,7 @@ use tracing_actix_web::TracingLogger;
 use crate::email_client::EmailClient;
 use crate::configuration::Settings;
 use crate::configuration::DatabaseSettings;
+use crate::email_templates::{ConfirmationEmailTemplate, EmailChangeTemplate};
 use sqlx::postgres::PgPoolOptions;
 //20250211 추가 -> 20250214 수정
 use crate::routes::{
@@ -27,6 +28,27 @@ use actix_web::cookie::Key;
 use actix_session::SessionMiddleware;
 //20250301 추가
 use crate::routes::log_out;
+//20261019 추가
+use crate::routes::{create_list, lists_page};
+use crate::routes::{
+    add_subscriber_tag, preview_recipient_count, remove_subscriber_tag, subscriber_details,
+    subscribers_page
+};
+use crate::routes::{create_field, fields_page, update_subscriber_fields};
+use crate::routes::{preferences_page, request_email_change, unsubscribe, update_preferences};
+use crate::routes::{import_report, imports_page, start_import, upload_import};
+use crate::routes::export_subscribers;
+use crate::routes::{data_requests_page, erase_subscriber_data, export_personal_data};
+use crate::routes::{download_personal_data, erase_my_data};
+use crate::routes::postmark_webhook;
+use crate::routes::{add_suppression, delete_suppression, suppressions_page};
+use crate::routes::{issue_report, issues_page, track_click, track_open};
+use crate::routes::{archive_index, archive_issue, update_issue_visibility};
+use crate::routes::{atom_feed, rss_feed};
+use crate::routes::send_issue;
+use crate::routes::{add_sequence_step, create_sequence, sequence_details, sequences_page, set_sequence_active};
+use actix_multipart::form::MultipartFormConfig;
+use crate::signed_links::LinkSigner;
 //20250302 추가
 use crate::authentication::reject_anonymous_users;
 use actix_web_lab::middleware::from_fn;
@@ -42,16 +64,27 @@ impl Application {
     pub async fn build(configuration: Settings) -> Result<Self, anyhow::Error> {
         //'build' 함수를 'Application'에 대한 생성자로 변환했다
         let connection_pool = get_connection_pool(&configuration.database);
-        let email_client = configuration.email_client.client();
+        //20261019 수정 / 모든 발송에서 억제 목록을 확인한다.
+        let email_client = configuration.email_client.clone().client().with_suppression_list(connection_pool.clone());
         let address = format!(
             "{}:{}",
             configuration.application.host, configuration.application.port
         );
+        //20261019 추가 / 템플릿이 잘못되었으면 요청을 받기 전에 실패한다.
+        let confirmation_email_template = ConfirmationEmailTemplate::load(
+            &std::path::Path::new(&configuration.application.email_templates_directory)
+                .join("confirmation_email")
+        )?;
+        let email_change_template = EmailChangeTemplate(ConfirmationEmailTemplate::load(
+            &std::path::Path::new(&configuration.application.email_templates_directory)
+                .join("email_change")
+        )?);
         let listener = TcpListener::bind(&address)?;
         let port = listener.local_addr().unwrap().port();
+        //20261019 수정 / 인자가 계속 늘어나서 나머지 구성값은 'Settings'에서 직접 꺼낸다.
         let server = run(
-            listener, connection_pool, email_client, configuration.application.base_url, 
-            configuration.application.hmac_secret, configuration.redis_uri
+            listener, connection_pool, email_client, confirmation_email_template, email_change_template,
+            configuration
         ).await?;
 
          //바운드된 포트를 'Application'의 필드 중 하나로 저장한다.
@@ -80,13 +113,26 @@ pub fn get_connection_pool(
 pub struct ApplicationBaseUrl(pub String);
 //20250226 수정 / 비동기로 변경
 async fn run(
-    listener: TcpListener, db_pool: PgPool, email_client: EmailClient, 
-    base_url: String, hmac_secret: Secret<String>, redis_uri: Secret<String>
+    listener: TcpListener, db_pool: PgPool, email_client: EmailClient,
+    confirmation_email_template: ConfirmationEmailTemplate, email_change_template: EmailChangeTemplate,
+    configuration: Settings
 ) -> Result<Server, anyhow::Error> {
+    let base_url = configuration.application.base_url;
+    let hmac_secret = configuration.application.hmac_secret;
+    let redis_uri = configuration.redis_uri;
     //web::Data로 pool을 감싼다. Arc 스마트 포인터로 요약된다.
     let db_pool = web::Data::new(db_pool);
     let email_client = web::Data::new(email_client);
+    //20261019 추가 / 이메일에 넣는 서명된 링크를 만들고 검증한다.
+    let link_signer = web::Data::new(LinkSigner::new(base_url.clone(), hmac_secret.clone()));
     let base_url = web::Data::new(ApplicationBaseUrl(base_url));
+    //20261019 추가 / 확인 토큰의 유효 시간을 핸들러에서 사용한다.
+    let subscription_settings = web::Data::new(configuration.subscriptions);
+    //20261019 추가
+    let webhook_settings = web::Data::new(configuration.webhooks);
+    let feed_settings = web::Data::new(configuration.feeds);
+    let confirmation_email_template = web::Data::new(confirmation_email_template);
+    let email_change_template = web::Data::new(email_change_template);
     //20250226 추가 / 플래시 메시지를 조작하기 위해 미들웨어로 등록해야됨
     let secret_key = Key::from(hmac_secret.expose_secret().as_bytes());
     let message_store = CookieMessageStore::builder(secret_key.clone()).build();
@@ -112,6 +158,25 @@ async fn run(
             .route("/subscriptions", web::post().to(subscribe))
             //confrim 요청에 대한 라우팅 테이블의 새 엔트리 포인트
             .route("/subscriptions/confirm", web::get().to(confirm))
+            //20261019 추가 -> 구독 설정 페이지
+            .route("/preferences", web::get().to(preferences_page))
+            .route("/preferences", web::post().to(update_preferences))
+            .route("/preferences/unsubscribe", web::post().to(unsubscribe))
+            .route("/preferences/email", web::post().to(request_email_change))
+            .route("/preferences/data", web::get().to(download_personal_data))
+            .route("/preferences/erase", web::post().to(erase_my_data))
+            //20261019 추가 / 이메일 서비스의 반송 및 스팸 신고 알림
+            .route("/webhooks/postmark", web::post().to(postmark_webhook))
+            //20261019 추가 / 이슈 열람 추적 이미지
+            .route("/o/{token}", web::get().to(track_open))
+            //20261019 추가 / 이슈 링크 클릭 추적 리다이렉트
+            .route("/r/{token}", web::get().to(track_click))
+            //20261019 추가 / 발행한 이슈의 웹 아카이브
+            .route("/archive", web::get().to(archive_index))
+            .route("/archive/{slug}", web::get().to(archive_issue))
+            //20261019 추가 / 공개 이슈의 RSS, Atom 피드
+            .route("/feed.rss", web::get().to(rss_feed))
+            .route("/feed.atom", web::get().to(atom_feed))
             //20250224 추가 -> 더미 홈 페이지 엔드 포인트
             .route("/", web::get().to(home))
             //20250224 추가 -> 로그인 폼 / get
@@ -135,11 +200,52 @@ async fn run(
                     .route("/password", web::post().to(change_password))
                     //20250301 추가 -> admin/logout 로그아웃 엔트리 포인트 추가
                     .route("/logout", web::post().to(log_out))
+                    //20261019 추가 -> 메일링 리스트 관리
+                    .route("/lists", web::get().to(lists_page))
+                    .route("/lists", web::post().to(create_list))
+                    //20261019 추가 -> 수신자 수 미리보기, 구독자 태그와 사용자 정의 필드 관리
+                    .route("/newsletters/preview", web::post().to(preview_recipient_count))
+                    .route("/subscribers", web::get().to(subscribers_page))
+                    //'{subscriber_id}'보다 먼저 등록해야 한다.
+                    .route("/subscribers/export", web::get().to(export_subscribers))
+                    .route("/subscribers/{subscriber_id}", web::get().to(subscriber_details))
+                    .route("/subscribers/{subscriber_id}/tags", web::post().to(add_subscriber_tag))
+                    .route("/subscribers/{subscriber_id}/tags/delete", web::post().to(remove_subscriber_tag))
+                    .route("/subscribers/{subscriber_id}/fields", web::post().to(update_subscriber_fields))
+                    .route("/fields", web::get().to(fields_page))
+                    .route("/fields", web::post().to(create_field))
+                    .route("/imports", web::get().to(imports_page))
+                    .route("/imports", web::post().to(upload_import))
+                    .route("/imports/{import_id}", web::get().to(import_report))
+                    .route("/imports/{import_id}/start", web::post().to(start_import))
+                    .route("/data-requests", web::get().to(data_requests_page))
+                    .route("/data-requests/export", web::get().to(export_personal_data))
+                    .route("/data-requests/erase", web::post().to(erase_subscriber_data))
+                    .route("/suppressions", web::get().to(suppressions_page))
+                    .route("/suppressions", web::post().to(add_suppression))
+                    .route("/suppressions/delete", web::post().to(delete_suppression))
+                    .route("/issues", web::get().to(issues_page))
+                    .route("/issues/{issue_id}", web::get().to(issue_report))
+                    .route("/issues/{issue_id}/visibility", web::post().to(update_issue_visibility))
+                    .route("/issues/{issue_id}/send", web::post().to(send_issue))
+                    .route("/sequences", web::get().to(sequences_page))
+                    .route("/sequences", web::post().to(create_sequence))
+                    .route("/sequences/{sequence_id}", web::get().to(sequence_details))
+                    .route("/sequences/{sequence_id}/steps", web::post().to(add_sequence_step))
+                    .route("/sequences/{sequence_id}/active", web::post().to(set_sequence_active))
+                    //CSV 파일은 메모리에서 읽는다. 기본 한도(2MiB)로는 수만 명의 구독자를 올릴 수 없다.
+                    .app_data(MultipartFormConfig::default().memory_limit(32 * 1024 * 1024))
             )
             //커넥션을 애플리케이션 상테의 일부로 등록한다.
             .app_data(db_pool.clone())
             .app_data(email_client.clone())
             .app_data(base_url.clone())
+            .app_data(subscription_settings.clone())
+            .app_data(webhook_settings.clone())
+            .app_data(feed_settings.clone())
+            .app_data(confirmation_email_template.clone())
+            .app_data(email_change_template.clone())
+            .app_data(link_signer.clone())
             .app_data(web::Data::new(HmacSecret(hmac_secret.clone())))
     })
     .listen(listener)?
@@ -0,0 +1,521 @@
+use crate::configuration::Settings;
+use crate::domain::{NewSubscriber, SubscriberEmail, SubscriberName};
+use crate::email_client::EmailClient;
+use crate::email_templates::ConfirmationEmailTemplate;
+use crate::suppression::{hash_email, is_suppressed};
+use crate::routes::{generate_subscription_token, send_confirmation_email, store_token};
+use crate::startup::get_connection_pool;
+use crate::subscription_events::{record_event, EventContext, EventSource, EventType};
+use chrono::{DateTime, NaiveDate, NaiveTime, TimeZone, Utc};
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashSet;
+use std::time::Duration;
+use uuid::Uuid;
+
+//20261019 추가 / 다른 서비스에서 구독자를 옮겨오기 위한 CSV 가져오기.
+//업로드한 파일은 먼저 검증 결과(잘못된 행, 중복)를 보여주는 초안으로 저장되고,
+//관리자가 시작하면 백그라운드 작업이 한 행씩 구독자를 추가한다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportMode {
+    //구독 동의를 이미 받은 구독자를 확인된 상태로 바로 추가한다.
+    Confirmed,
+    //확인 이메일을 보내고 더블 옵트인을 거치게 한다.
+    ConfirmationEmail
+}
+
+impl ImportMode {
+    pub fn parse(s: &str) -> Result<ImportMode, String> {
+        match s {
+            "confirmed" => Ok(ImportMode::Confirmed),
+            "confirmation_email" => Ok(ImportMode::ConfirmationEmail),
+            other => Err(format!("{} is not a valid import mode.", other))
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportMode::Confirmed => "confirmed",
+            ImportMode::ConfirmationEmail => "confirmation_email"
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RowOutcome {
+    Valid,
+    Invalid(String),
+    //같은 파일에서 앞서 나온 주소
+    Duplicate
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportRow {
+    //헤더를 포함한 CSV 파일의 줄 번호
+    pub line: i32,
+    pub email: String,
+    pub name: String,
+    pub consented_at: Option<DateTime<Utc>>,
+    pub outcome: RowOutcome
+}
+
+fn parse_consent_date(s: &str) -> Result<DateTime<Utc>, String> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map(|date| Utc.from_utc_datetime(&date.and_time(NaiveTime::MIN)))
+        .map_err(|_| format!("{} is not a valid consent date (YYYY-MM-DD).", s))
+}
+
+//CSV 파일을 읽어 각 행을 검증한다. 'email'과 'name' 열은 필수이고, 'consent_date' 열은 선택이다.
+//행에 동의 날짜가 없으면 'default_consent_date'를 사용한다.
+pub fn parse_import_csv(
+    data: &[u8],
+    mode: ImportMode,
+    default_consent_date: Option<&str>
+) -> Result<Vec<ImportRow>, String> {
+    let default_consent = default_consent_date
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(parse_consent_date)
+        .transpose()?;
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(data);
+    let headers = reader
+        .headers()
+        .map_err(|e| format!("The CSV file could not be read: {}", e))?
+        .clone();
+    let column = |name: &str| headers.iter().position(|h| h.eq_ignore_ascii_case(name));
+    let (email_column, name_column) = match (column("email"), column("name")) {
+        (Some(email), Some(name)) => (email, name),
+        _ => return Err("The CSV file must have 'email' and 'name' columns.".into())
+    };
+    let consent_column = column("consent_date");
+
+    let mut rows = Vec::new();
+    let mut seen = HashSet::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("The CSV file could not be read: {}", e))?;
+        let line = record.position().map(|p| p.line()).unwrap_or_default() as i32;
+        let field = |index: usize| record.get(index).unwrap_or_default().to_string();
+        let (email, name) = (field(email_column), field(name_column));
+        if email.is_empty() && name.is_empty() {
+            continue;
+        }
+        let validated = SubscriberEmail::parse(email.clone())
+            .and_then(|email| SubscriberName::parse(name.clone()).map(|name| (email, name)))
+            .and_then(|(email, name)| {
+                let consented_at = match consent_column.map(field).filter(|s| !s.is_empty()) {
+                    Some(date) => Some(parse_consent_date(&date)?),
+                    None => default_consent
+                };
+                if mode == ImportMode::Confirmed && consented_at.is_none() {
+                    return Err("A consent date is required to import confirmed subscribers.".to_string());
+                }
+                Ok((email, name, consented_at))
+            });
+        let row = match validated {
+            Ok((email, name, consented_at)) => {
+                let outcome = if seen.insert(email.as_ref().to_lowercase()) {
+                    RowOutcome::Valid
+                } else {
+                    RowOutcome::Duplicate
+                };
+                ImportRow { line, email: email.as_ref().to_string(), name: name.as_ref().to_string(), consented_at, outcome }
+            }
+            Err(e) => ImportRow { line, email, name, consented_at: None, outcome: RowOutcome::Invalid(e) }
+        };
+        rows.push(row);
+    }
+    if rows.is_empty() {
+        return Err("The CSV file has no rows.".into());
+    }
+    Ok(rows)
+}
+
+//검증한 행을 초안 상태의 가져오기 작업으로 저장한다. 이미 구독자인 주소는 'existing', 억제된 주소는 'suppressed'로 표시한다.
+#[tracing::instrument(skip(pool, rows))]
+pub async fn create_import(
+    pool: &PgPool,
+    list_id: Uuid,
+    mode: ImportMode,
+    source: &str,
+    rows: &[ImportRow]
+) -> Result<Uuid, anyhow::Error> {
+    let import_id = Uuid::new_v4();
+    let mut transaction = pool.begin().await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO subscriber_imports (import_id, list_id, mode, source, status)
+        VALUES ($1, $2, $3, $4, 'draft')
+        "#,
+        import_id,
+        list_id,
+        mode.as_str(),
+        source
+    )
+    .execute(&mut transaction)
+    .await?;
+    let valid_emails: Vec<String> = rows
+        .iter()
+        .filter(|r| r.outcome == RowOutcome::Valid)
+        .map(|r| r.email.to_lowercase())
+        .collect();
+    let existing: HashSet<String> = sqlx::query!(
+        r#"SELECT lower(email) AS "email!" FROM subscriptions WHERE lower(email) = ANY($1)"#,
+        &valid_emails
+    )
+    .fetch_all(&mut transaction)
+    .await?
+    .into_iter()
+    .map(|r| r.email)
+    .collect();
+    //개인정보 삭제를 요청한 주소는 다시 가져오지 않는다.
+    let hashes: Vec<String> = valid_emails.iter().map(|email| hash_email(email)).collect();
+    let suppressed: HashSet<String> = sqlx::query!(
+        r#"SELECT email_hash FROM suppressed_emails WHERE email_hash = ANY($1)"#,
+        &hashes
+    )
+    .fetch_all(&mut transaction)
+    .await?
+    .into_iter()
+    .map(|r| r.email_hash)
+    .collect();
+
+    let mut lines = Vec::with_capacity(rows.len());
+    let mut emails = Vec::with_capacity(rows.len());
+    let mut names = Vec::with_capacity(rows.len());
+    let mut consents = Vec::with_capacity(rows.len());
+    let mut outcomes = Vec::with_capacity(rows.len());
+    let mut errors = Vec::with_capacity(rows.len());
+    for row in rows {
+        let (outcome, error) = match &row.outcome {
+            RowOutcome::Valid if suppressed.contains(&hash_email(&row.email)) => ("suppressed", None),
+            RowOutcome::Valid if existing.contains(&row.email.to_lowercase()) => ("existing", None),
+            RowOutcome::Valid => ("pending", None),
+            RowOutcome::Duplicate => ("duplicate", None),
+            RowOutcome::Invalid(e) => ("invalid", Some(e.clone()))
+        };
+        lines.push(row.line);
+        emails.push(row.email.clone());
+        names.push(row.name.clone());
+        consents.push(row.consented_at);
+        outcomes.push(outcome.to_string());
+        errors.push(error);
+    }
+    //4만 행도 한 번의 쿼리로 저장할 수 있도록 배열로 전달한다.
+    sqlx::query!(
+        r#"
+        INSERT INTO subscriber_import_rows (import_id, line, email, name, consented_at, outcome, error)
+        SELECT $1, * FROM UNNEST($2::int4[], $3::text[], $4::text[], $5::timestamptz[], $6::text[], $7::text[])
+        "#,
+        import_id,
+        &lines,
+        &emails,
+        &names,
+        &consents as &[Option<DateTime<Utc>>],
+        &outcomes,
+        &errors as &[Option<String>]
+    )
+    .execute(&mut transaction)
+    .await?;
+    transaction.commit().await?;
+    Ok(import_id)
+}
+
+pub enum ImportOutcome {
+    RowProcessed,
+    //확인 이메일을 보냈다. 워커는 발송 속도를 제한하기 위해 잠시 쉰다.
+    ConfirmationEmailSent,
+    EmptyQueue
+}
+
+struct PendingRow {
+    import_id: Uuid,
+    line: i32,
+    email: String,
+    name: String,
+    consented_at: Option<DateTime<Utc>>,
+    list_id: Uuid,
+    mode: String,
+    source: String
+}
+
+type PgTransaction = Transaction<'static, Postgres>;
+
+#[tracing::instrument(
+    skip_all,
+    fields(import_id = tracing::field::Empty, line = tracing::field::Empty),
+    err
+)]
+pub async fn try_import_next_row(
+    pool: &PgPool,
+    email_client: &EmailClient,
+    template: &ConfirmationEmailTemplate,
+    base_url: &str
+) -> Result<ImportOutcome, anyhow::Error> {
+    let mut transaction = pool.begin().await?;
+    let row = sqlx::query_as!(
+        PendingRow,
+        r#"
+        SELECT r.import_id, r.line, r.email, r.name, r.consented_at, i.list_id, i.mode, i.source
+        FROM subscriber_import_rows r
+        JOIN subscriber_imports i ON i.import_id = r.import_id
+        WHERE i.status = 'queued' AND r.outcome = 'pending'
+        ORDER BY i.created_at, r.line
+        FOR UPDATE OF r
+        SKIP LOCKED
+        LIMIT 1
+        "#
+    )
+    .fetch_optional(&mut transaction)
+    .await?;
+    let row = match row {
+        Some(row) => row,
+        None => {
+            complete_finished_imports(&mut transaction).await?;
+            transaction.commit().await?;
+            return Ok(ImportOutcome::EmptyQueue);
+        }
+    };
+    tracing::Span::current()
+        .record("import_id", tracing::field::display(row.import_id))
+        .record("line", row.line);
+    let new_subscriber = match SubscriberEmail::parse(row.email.clone())
+        .and_then(|email| SubscriberName::parse(row.name.clone()).map(|name| NewSubscriber { email, name }))
+    {
+        Ok(new_subscriber) => new_subscriber,
+        Err(e) => {
+            set_row_outcome(&mut transaction, &row, "invalid", Some(&e)).await?;
+            transaction.commit().await?;
+            return Ok(ImportOutcome::RowProcessed);
+        }
+    };
+    //검증 이후에 같은 주소로 구독했을 수 있다. 기존 구독자는 건드리지 않는다.
+    let existing = sqlx::query!(
+        r#"SELECT id FROM subscriptions WHERE lower(email) = lower($1) FOR UPDATE"#,
+        new_subscriber.email.as_ref()
+    )
+    .fetch_optional(&mut transaction)
+    .await?;
+    if existing.is_some() {
+        set_row_outcome(&mut transaction, &row, "existing", None).await?;
+        transaction.commit().await?;
+        return Ok(ImportOutcome::RowProcessed);
+    }
+    if is_suppressed(&mut transaction, new_subscriber.email.as_ref()).await? {
+        set_row_outcome(&mut transaction, &row, "suppressed", None).await?;
+        transaction.commit().await?;
+        return Ok(ImportOutcome::RowProcessed);
+    }
+    let mode = ImportMode::parse(&row.mode).map_err(anyhow::Error::msg)?;
+    let status = match mode {
+        ImportMode::Confirmed => "confirmed",
+        ImportMode::ConfirmationEmail => "pending_confirmation"
+    };
+    let subscriber_id = insert_imported_subscriber(&mut transaction, &row, &new_subscriber, status).await?;
+    //20261019 추가 / 가져오기도 구독 동의 기록에 남긴다.
+    let event_context = EventContext::without_request(EventSource::Import);
+    record_event(
+        &mut transaction,
+        subscriber_id,
+        EventType::Subscribed,
+        &event_context,
+        serde_json::json!({ "import_id": row.import_id, "source": row.source })
+    )
+    .await?;
+    if mode == ImportMode::Confirmed {
+        record_event(
+            &mut transaction,
+            subscriber_id,
+            EventType::Confirmed,
+            &event_context,
+            serde_json::json!({ "consented_at": row.consented_at.map(|t| t.to_rfc3339()) })
+        )
+        .await?;
+        set_row_outcome(&mut transaction, &row, "imported", None).await?;
+        transaction.commit().await?;
+        return Ok(ImportOutcome::RowProcessed);
+    }
+    let subscription_token = generate_subscription_token();
+    store_token(&mut transaction, subscriber_id, &subscription_token, None).await?;
+    if let Err(e) = send_confirmation_email(email_client, template, new_subscriber, base_url, &subscription_token).await {
+        tracing::error!(
+            error.cause_chain = ?e,
+            error.message = %e,
+            "Failed to send a confirmation email to an imported subscriber. Skipping."
+        );
+        //추가한 구독자는 되돌리고 실패한 행으로 표시한다.
+        transaction.rollback().await?;
+        let mut transaction = pool.begin().await?;
+        set_row_outcome(&mut transaction, &row, "failed", Some(&e.to_string())).await?;
+        transaction.commit().await?;
+        return Ok(ImportOutcome::ConfirmationEmailSent);
+    }
+    record_event(
+        &mut transaction,
+        subscriber_id,
+        EventType::ConfirmationEmailSent,
+        &event_context,
+        serde_json::json!({})
+    )
+    .await?;
+    set_row_outcome(&mut transaction, &row, "imported", None).await?;
+    transaction.commit().await?;
+    Ok(ImportOutcome::ConfirmationEmailSent)
+}
+
+#[tracing::instrument(skip_all)]
+async fn insert_imported_subscriber(
+    transaction: &mut PgTransaction,
+    row: &PendingRow,
+    new_subscriber: &NewSubscriber,
+    status: &str
+) -> Result<Uuid, sqlx::Error> {
+    let subscriber_id = Uuid::new_v4();
+    sqlx::query!(
+        r#"
+        INSERT INTO subscriptions (id, email, name, subscribed_at, status, source, consented_at, confirmed_at)
+        VALUES ($1, $2, $3, now(), $4, $5, $6, CASE WHEN $4 = 'confirmed' THEN now() END)
+        "#,
+        subscriber_id,
+        new_subscriber.email.as_ref(),
+        new_subscriber.name.as_ref(),
+        status,
+        row.source,
+        row.consented_at
+    )
+    .execute(&mut *transaction)
+    .await?;
+    sqlx::query!(
+        r#"
+        INSERT INTO list_memberships (list_id, subscriber_id, status)
+        VALUES ($1, $2, $3)
+        "#,
+        row.list_id,
+        subscriber_id,
+        status
+    )
+    .execute(transaction)
+    .await?;
+    Ok(subscriber_id)
+}
+
+async fn set_row_outcome(
+    transaction: &mut PgTransaction,
+    row: &PendingRow,
+    outcome: &str,
+    error: Option<&str>
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE subscriber_import_rows SET outcome = $3, error = $4
+        WHERE import_id = $1 AND line = $2
+        "#,
+        row.import_id,
+        row.line,
+        outcome,
+        error
+    )
+    .execute(&mut *transaction)
+    .await?;
+    complete_finished_imports(transaction).await
+}
+
+//처리할 행이 남지 않은 작업을 완료 상태로 바꾼다.
+async fn complete_finished_imports(transaction: &mut PgTransaction) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        UPDATE subscriber_imports i SET status = 'completed', completed_at = now()
+        WHERE i.status = 'queued'
+          AND NOT EXISTS (
+              SELECT 1 FROM subscriber_import_rows r
+              WHERE r.import_id = i.import_id AND r.outcome = 'pending'
+          )
+        "#
+    )
+    .execute(transaction)
+    .await?;
+    Ok(())
+}
+
+async fn import_loop(
+    pool: PgPool,
+    email_client: EmailClient,
+    template: ConfirmationEmailTemplate,
+    base_url: String,
+    email_interval: Duration
+) -> Result<(), anyhow::Error> {
+    loop {
+        match try_import_next_row(&pool, &email_client, &template, &base_url).await {
+            Ok(ImportOutcome::EmptyQueue) => {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+            }
+            Ok(ImportOutcome::ConfirmationEmailSent) => {
+                tokio::time::sleep(email_interval).await;
+            }
+            Ok(ImportOutcome::RowProcessed) => {}
+            Err(_) => {
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+}
+
+pub async fn run_import_worker_until_stopped(
+    configuration: Settings
+) -> Result<(), anyhow::Error> {
+    let connection_pool = get_connection_pool(&configuration.database);
+    let email_client = configuration.email_client.client().with_suppression_list(connection_pool.clone());
+    let template = ConfirmationEmailTemplate::load(
+        &std::path::Path::new(&configuration.application.email_templates_directory)
+            .join("confirmation_email")
+    )?;
+    import_loop(
+        connection_pool,
+        email_client,
+        template,
+        configuration.application.base_url,
+        configuration.subscriptions.import_email_interval()
+    )
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_import_csv, ImportMode, RowOutcome};
+    use claim::assert_err;
+
+    #[test]
+    fn valid_rows_are_accepted() {
+        let csv = "email,name,consent_date\nursula@example.com,Ursula,2024-01-31\n";
+        let rows = parse_import_csv(csv.as_bytes(), ImportMode::Confirmed, None).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].outcome, RowOutcome::Valid);
+        assert_eq!(rows[0].line, 2);
+        assert!(rows[0].consented_at.is_some());
+    }
+
+    #[test]
+    fn invalid_rows_and_duplicates_are_reported() {
+        let csv = "Name,Email\nUrsula,ursula@example.com\nNobody,not-an-email\nUrsula again,URSULA@example.com\n";
+        let rows = parse_import_csv(csv.as_bytes(), ImportMode::ConfirmationEmail, None).unwrap();
+        assert_eq!(rows[0].outcome, RowOutcome::Valid);
+        assert!(matches!(rows[1].outcome, RowOutcome::Invalid(_)));
+        assert_eq!(rows[2].outcome, RowOutcome::Duplicate);
+    }
+
+    #[test]
+    fn confirmed_imports_require_a_consent_date() {
+        let csv = "email,name\nursula@example.com,Ursula\n";
+        let rows = parse_import_csv(csv.as_bytes(), ImportMode::Confirmed, None).unwrap();
+        assert!(matches!(rows[0].outcome, RowOutcome::Invalid(_)));
+        let rows = parse_import_csv(csv.as_bytes(), ImportMode::Confirmed, Some("2024-01-31")).unwrap();
+        assert_eq!(rows[0].outcome, RowOutcome::Valid);
+    }
+
+    #[test]
+    fn files_without_the_required_columns_are_rejected() {
+        assert_err!(parse_import_csv(b"address,name\na@example.com,A\n", ImportMode::ConfirmationEmail, None));
+        assert_err!(parse_import_csv(b"email,name\n", ImportMode::ConfirmationEmail, None));
+    }
+}
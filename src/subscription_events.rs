@@ -0,0 +1,144 @@
+use actix_web::http::header::USER_AGENT;
+use actix_web::HttpRequest;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+//20261019 추가 / 구독 동의와 구독 상태 변경의 추가 전용 기록.
+//구독자가 언제, 어떤 경로로 구독에 동의했는지 증명할 수 있도록 모든 흐름에서 이벤트를 남긴다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventType {
+    Subscribed,
+    ConfirmationEmailSent,
+    Confirmed,
+    Unsubscribed,
+    Bounced,
+    //20261019 추가 / 스팸 신고
+    Complained,
+    PreferencesChanged,
+    Erased
+}
+
+impl EventType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventType::Subscribed => "subscribed",
+            EventType::ConfirmationEmailSent => "confirmation_email_sent",
+            EventType::Confirmed => "confirmed",
+            EventType::Unsubscribed => "unsubscribed",
+            EventType::Bounced => "bounced",
+            EventType::Complained => "complained",
+            EventType::PreferencesChanged => "preferences_changed",
+            EventType::Erased => "erased"
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventSource {
+    //HTML 구독 폼과 구독 설정 페이지
+    Form,
+    //JSON을 요청한 API 클라이언트
+    Api,
+    //이메일로 받은 확인 링크
+    EmailLink,
+    Import,
+    Admin,
+    //백그라운드 작업과 외부 웹훅
+    System
+}
+
+impl EventSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventSource::Form => "form",
+            EventSource::Api => "api",
+            EventSource::EmailLink => "email_link",
+            EventSource::Import => "import",
+            EventSource::Admin => "admin",
+            EventSource::System => "system"
+        }
+    }
+}
+
+//이벤트를 일으킨 요청의 정보. 백그라운드 작업에는 IP와 User-Agent가 없다.
+#[derive(Debug, Clone)]
+pub struct EventContext {
+    pub source: EventSource,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>
+}
+
+impl EventContext {
+    pub fn from_request(request: &HttpRequest, source: EventSource) -> Self {
+        Self {
+            source,
+            ip_address: request.connection_info().realip_remote_addr().map(String::from),
+            user_agent: request
+                .headers()
+                .get(USER_AGENT)
+                .and_then(|value| value.to_str().ok())
+                .map(String::from)
+        }
+    }
+
+    pub fn without_request(source: EventSource) -> Self {
+        Self { source, ip_address: None, user_agent: None }
+    }
+}
+
+#[tracing::instrument(skip(executor, context, details))]
+pub async fn record_event(
+    executor: impl sqlx::PgExecutor<'_>,
+    subscriber_id: Uuid,
+    event_type: EventType,
+    context: &EventContext,
+    details: Value
+) -> Result<(), sqlx::Error> {
+    sqlx::query!(
+        r#"
+        INSERT INTO subscription_events
+            (event_id, subscriber_id, event_type, source, ip_address, user_agent, details)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+        "#,
+        Uuid::new_v4(),
+        subscriber_id,
+        event_type.as_str(),
+        context.source.as_str(),
+        context.ip_address,
+        context.user_agent,
+        details
+    )
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+pub struct SubscriptionEvent {
+    pub event_type: String,
+    pub source: String,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub details: Value,
+    pub occurred_at: DateTime<Utc>
+}
+
+#[tracing::instrument(skip(pool))]
+pub async fn get_events(
+    pool: &PgPool,
+    subscriber_id: Uuid
+) -> Result<Vec<SubscriptionEvent>, sqlx::Error> {
+    sqlx::query_as!(
+        SubscriptionEvent,
+        r#"
+        SELECT event_type, source, ip_address, user_agent, details, occurred_at
+        FROM subscription_events
+        WHERE subscriber_id = $1
+        ORDER BY occurred_at, event_id
+        "#,
+        subscriber_id
+    )
+    .fetch_all(pool)
+    .await
+}
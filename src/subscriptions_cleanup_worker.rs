@@ -0,0 +1,74 @@
+use crate::configuration::Settings;
+use crate::startup::get_connection_pool;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use std::time::Duration;
+
+//20261019 추가 / 오래된 확인 토큰과 확인되지 않은 구독자를 주기적으로 정리한다.
+pub struct PurgeOutcome {
+    pub deleted_tokens: u64,
+    pub deleted_subscribers: u64
+}
+
+#[tracing::instrument(skip(pool), err)]
+pub async fn purge_stale_subscriptions(
+    pool: &PgPool,
+    cutoff: DateTime<Utc>
+) -> Result<PurgeOutcome, anyhow::Error> {
+    let mut transaction = pool.begin().await?;
+    //구독자 행이 삭제되기 전에 그 구독자를 참조하는 토큰을 먼저 지운다.
+    let deleted_tokens = sqlx::query!(
+        r#"
+        DELETE FROM subscription_tokens
+        WHERE created_at < $1
+           OR subscriber_id IN (
+                SELECT id FROM subscriptions
+                WHERE status = 'pending_confirmation' AND subscribed_at < $1
+           )
+        "#,
+        cutoff
+    )
+    .execute(&mut transaction)
+    .await?
+    .rows_affected();
+    let deleted_subscribers = sqlx::query!(
+        r#"
+        DELETE FROM subscriptions
+        WHERE status = 'pending_confirmation' AND subscribed_at < $1
+        "#,
+        cutoff
+    )
+    .execute(&mut transaction)
+    .await?
+    .rows_affected();
+    transaction.commit().await?;
+    Ok(PurgeOutcome { deleted_tokens, deleted_subscribers })
+}
+
+async fn cleanup_loop(
+    pool: PgPool,
+    retention: chrono::Duration
+) -> Result<(), anyhow::Error> {
+    loop {
+        match purge_stale_subscriptions(&pool, Utc::now() - retention).await {
+            Ok(outcome) => {
+                tracing::info!(
+                    deleted_tokens = outcome.deleted_tokens,
+                    deleted_subscribers = outcome.deleted_subscribers,
+                    "Purged stale subscriptions"
+                );
+                tokio::time::sleep(Duration::from_secs(60 * 60)).await;
+            }
+            Err(_) => {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+        }
+    }
+}
+
+pub async fn run_cleanup_worker_until_stopped(
+    configuration: Settings
+) -> Result<(), anyhow::Error> {
+    let connection_pool = get_connection_pool(&configuration.database);
+    cleanup_loop(connection_pool, configuration.subscriptions.unconfirmed_retention()).await
+}
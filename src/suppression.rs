@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+//20261019 추가 / 구독 여부와 관계없이 다시는 이메일을 보내지 않을 주소의 목록.
+//주소 자체는 저장하지 않고 정규화한 주소의 다이제스트만 저장하므로 구독자를 삭제한 뒤에도 남겨둘 수 있다.
+//'EmailClient'가 발송 직전에 이 목록을 확인한다.
+
+//관리자 화면과 CLI에서 선택할 수 있는 사유. 반송과 삭제 요청은 자동으로 추가된다.
+pub const MANUAL_REASONS: [&str; 4] = ["complaint", "role_address", "legal_request", "manual"];
+
+//억제 목록의 키. 대소문자와 앞뒤 공백이 달라도 같은 주소로 본다.
+pub fn hash_email(email: &str) -> String {
+    hex::encode(Sha256::digest(email.trim().to_lowercase().as_bytes()))
+}
+
+#[tracing::instrument(skip(executor, email))]
+pub async fn is_suppressed(
+    executor: impl sqlx::PgExecutor<'_>,
+    email: &str
+) -> Result<bool, sqlx::Error> {
+    let row = sqlx::query!(
+        r#"SELECT email_hash FROM suppressed_emails WHERE email_hash = $1"#,
+        hash_email(email)
+    )
+    .fetch_optional(executor)
+    .await?;
+    Ok(row.is_some())
+}
+
+//주소를 억제한다. 이미 억제된 주소는 처음 억제한 사유를 유지하고 'false'를 반환한다.
+#[tracing::instrument(skip(executor, email))]
+pub async fn suppress_email(
+    executor: impl sqlx::PgExecutor<'_>,
+    email: &str,
+    reason: &str
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO suppressed_emails (email_hash, reason)
+        VALUES ($1, $2)
+        ON CONFLICT (email_hash) DO NOTHING
+        "#,
+        hash_email(email),
+        reason
+    )
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+//억제를 해제한다. 억제되어 있지 않았으면 'false'를 반환한다.
+#[tracing::instrument(skip(executor, email))]
+pub async fn remove_suppression(
+    executor: impl sqlx::PgExecutor<'_>,
+    email: &str
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"DELETE FROM suppressed_emails WHERE email_hash = $1"#,
+        hash_email(email)
+    )
+    .execute(executor)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+pub struct Suppression {
+    pub email_hash: String,
+    pub reason: String,
+    pub created_at: DateTime<Utc>
+}
+
+#[tracing::instrument(skip(pool))]
+pub async fn get_suppression(
+    pool: &PgPool,
+    email: &str
+) -> Result<Option<Suppression>, sqlx::Error> {
+    sqlx::query_as!(
+        Suppression,
+        r#"SELECT email_hash, reason, created_at FROM suppressed_emails WHERE email_hash = $1"#,
+        hash_email(email)
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+//최근에 억제된 주소부터 반환한다.
+#[tracing::instrument(skip(pool))]
+pub async fn list_suppressions(
+    pool: &PgPool,
+    limit: i64
+) -> Result<Vec<Suppression>, sqlx::Error> {
+    sqlx::query_as!(
+        Suppression,
+        r#"
+        SELECT email_hash, reason, created_at
+        FROM suppressed_emails
+        ORDER BY created_at DESC, email_hash
+        LIMIT $1
+        "#,
+        limit
+    )
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::hash_email;
+
+    #[test]
+    fn addresses_are_normalized_before_hashing() {
+        assert_eq!(hash_email(" Ursula@Example.com "), hash_email("ursula@example.com"));
+        assert_ne!(hash_email("ursula@example.com"), hash_email("octavia@example.com"));
+    }
+}
@@ -0,0 +1,317 @@
+use sqlx::PgPool;
+use std::collections::HashMap;
+use uuid::Uuid;
+use crate::email_templates::RenderedEmail;
+
+//20261019 추가 / 이슈 열람 추적.
+//발송 워커가 이슈마다, 구독자마다 서명된 1x1 이미지 URL을 HTML 본문에 넣고, 이미지를 요청하면 열람으로 기록한다.
+//이슈를 발행할 때 추적을 켠 경우에만 넣으며, 추적을 거부한 구독자에게는 넣지도 기록하지도 않는다.
+//20261019 추가 / 링크 클릭 추적. 본문의 링크를 서명된 리다이렉트 URL로 바꾸고, 리다이렉트할 때 클릭으로 기록한다.
+//추적을 거부한 구독자에게는 링크를 바꾸지 않는다.
+
+//투명한 1x1 GIF
+pub const TRANSPARENT_GIF: &[u8] = &[
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00, 0x00, 0x00, 0x00, 0x00,
+    0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00, 0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00,
+    0x01, 0x00, 0x01, 0x00, 0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b
+];
+
+//User-Agent 원문 대신 저장하는 분류. 메일 서비스의 이미지 프록시는 구독자의 기기를 알려주지 않는다.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UserAgentClass {
+    ImageProxy,
+    Mobile,
+    Desktop,
+    Unknown
+}
+
+impl UserAgentClass {
+    pub fn classify(user_agent: Option<&str>) -> Self {
+        let user_agent = match user_agent {
+            Some(user_agent) if !user_agent.trim().is_empty() => user_agent.to_lowercase(),
+            _ => return UserAgentClass::Unknown
+        };
+        if ["googleimageproxy", "yahoomailproxy", "ymailimageproxy"]
+            .iter()
+            .any(|proxy| user_agent.contains(proxy))
+        {
+            UserAgentClass::ImageProxy
+        } else if ["iphone", "ipad", "android", "mobile"].iter().any(|m| user_agent.contains(m)) {
+            UserAgentClass::Mobile
+        } else if ["windows", "macintosh", "mac os x", "linux", "x11"].iter().any(|d| user_agent.contains(d)) {
+            UserAgentClass::Desktop
+        } else {
+            UserAgentClass::Unknown
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UserAgentClass::ImageProxy => "image_proxy",
+            UserAgentClass::Mobile => "mobile",
+            UserAgentClass::Desktop => "desktop",
+            UserAgentClass::Unknown => "unknown"
+        }
+    }
+}
+
+//'</body>'가 있으면 그 앞에, 없으면 본문 끝에 이미지를 넣는다.
+pub fn inject_open_pixel(html: &str, pixel_url: &str) -> String {
+    let pixel = format!(
+        r#"<img src="{}" width="1" height="1" alt="" style="display:none">"#,
+        htmlescape::encode_minimal(pixel_url)
+    );
+    match html.to_ascii_lowercase().rfind("</body>") {
+        Some(index) => format!("{}{}{}", &html[..index], pixel, &html[index..]),
+        None => format!("{}{}", html, pixel)
+    }
+}
+
+//열람을 기록한다. 추적하지 않는 이슈이거나 구독자가 추적을 거부했으면 기록하지 않고 'false'를 반환한다.
+#[tracing::instrument(skip(pool))]
+pub async fn record_open(
+    pool: &PgPool,
+    issue_id: Uuid,
+    subscriber_id: Uuid,
+    user_agent_class: UserAgentClass
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query!(
+        r#"
+        INSERT INTO issue_opens (newsletter_issue_id, subscriber_id, user_agent_class)
+        SELECT i.newsletter_issue_id, s.id, $3
+        FROM newsletter_issues i, subscriptions s
+        WHERE i.newsletter_issue_id = $1 AND i.track_opens
+          AND s.id = $2 AND NOT s.tracking_opt_out
+        ON CONFLICT (newsletter_issue_id, subscriber_id) DO UPDATE
+        SET open_count = issue_opens.open_count + 1, last_opened_at = now()
+        "#,
+        issue_id,
+        subscriber_id,
+        user_agent_class.as_str()
+    )
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected() == 1)
+}
+
+//추적할 수 있는 링크는 'http'와 'https' 절대 URL뿐이다. ('mailto:'나 페이지 내 앵커는 제외)
+fn is_trackable(url: &str) -> bool {
+    let url = url.to_ascii_lowercase();
+    url.starts_with("http://") || url.starts_with("https://")
+}
+
+//소문자로 바꾼 '<a ...>' 태그 안에서 따옴표로 감싼 'href' 값의 범위를 찾는다.
+fn find_href(tag: &str) -> Option<(usize, usize)> {
+    let mut from = 0;
+    while let Some(found) = tag[from..].find("href") {
+        let start = from + found;
+        from = start + 4;
+        if !tag[..start].ends_with(|c: char| c.is_ascii_whitespace()) {
+            continue;
+        }
+        let rest = tag[from..].trim_start();
+        let rest = match rest.strip_prefix('=') {
+            Some(rest) => rest.trim_start(),
+            None => continue
+        };
+        let quote = match rest.chars().next() {
+            Some(quote @ ('"' | '\'')) => quote,
+            _ => return None
+        };
+        let value_start = tag.len() - rest.len() + 1;
+        let value_end = value_start + tag[value_start..].find(quote)?;
+        return Some((value_start, value_end));
+    }
+    None
+}
+
+//'<a>' 태그의 'href' 값을 'redirect'가 돌려준 URL로 바꾼다. 'None'을 돌려주면 그대로 둔다.
+pub fn rewrite_html_links(html: &str, mut redirect: impl FnMut(&str) -> Option<String>) -> String {
+    //ASCII 문자만 바꾸므로 소문자 사본의 위치를 원문에 그대로 쓸 수 있다.
+    let lower = html.to_ascii_lowercase();
+    let mut output = String::with_capacity(html.len());
+    let mut copied = 0;
+    let mut from = 0;
+    while let Some(found) = lower[from..].find("<a") {
+        let tag_start = from + found;
+        from = tag_start + 2;
+        //'<abbr>'이나 '<address>' 같은 다른 태그는 건너뛴다.
+        if !lower[from..].starts_with(|c: char| c.is_ascii_whitespace()) {
+            continue;
+        }
+        let tag_end = match lower[tag_start..].find('>') {
+            Some(end) => tag_start + end,
+            None => break
+        };
+        from = tag_end;
+        let (value_start, value_end) = match find_href(&lower[tag_start..tag_end]) {
+            Some((start, end)) => (tag_start + start, tag_start + end),
+            None => continue
+        };
+        let raw = &html[value_start..value_end];
+        let url = htmlescape::decode_html(raw).unwrap_or_else(|_| raw.to_string());
+        if let Some(replacement) = redirect(&url) {
+            output.push_str(&html[copied..value_start]);
+            output.push_str(&htmlescape::encode_minimal(&replacement));
+            copied = value_end;
+        }
+    }
+    output.push_str(&html[copied..]);
+    output
+}
+
+//텍스트 본문의 'http://', 'https://' URL을 바꾼다. 문장 부호로 끝나면 문장 부호는 URL에 포함하지 않는다.
+pub fn rewrite_text_links(text: &str, mut redirect: impl FnMut(&str) -> Option<String>) -> String {
+    let lower = text.to_ascii_lowercase();
+    let mut output = String::with_capacity(text.len());
+    let mut copied = 0;
+    while let Some(found) = [lower[copied..].find("http://"), lower[copied..].find("https://")]
+        .into_iter()
+        .flatten()
+        .min()
+    {
+        let start = copied + found;
+        let end = text[start..]
+            .find(|c: char| c.is_whitespace() || matches!(c, '<' | '>' | '"'))
+            .map(|end| start + end)
+            .unwrap_or(text.len());
+        let url = text[start..end].trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '\'']);
+        output.push_str(&text[copied..start]);
+        match redirect(url) {
+            Some(replacement) => output.push_str(&replacement),
+            None => output.push_str(url)
+        }
+        copied = start + url.len();
+    }
+    output.push_str(&text[copied..]);
+    output
+}
+
+//이메일의 두 본문에 있는 추적할 수 있는 링크. 중복은 제거한다.
+pub fn trackable_links(email: &RenderedEmail) -> Vec<String> {
+    let mut links: Vec<String> = Vec::new();
+    let mut collect = |url: &str| -> Option<String> {
+        if is_trackable(url) && !links.iter().any(|link| link == url) {
+            links.push(url.to_string());
+        }
+        None
+    };
+    rewrite_html_links(&email.html_body, &mut collect);
+    rewrite_text_links(&email.text_body, &mut collect);
+    links
+}
+
+//두 본문의 링크를 모두 바꾼다. 'redirect'가 모르는 링크는 그대로 둔다.
+pub fn rewrite_links(email: RenderedEmail, redirect: impl Fn(&str) -> Option<String>) -> RenderedEmail {
+    RenderedEmail {
+        html_body: rewrite_html_links(&email.html_body, &redirect),
+        text_body: rewrite_text_links(&email.text_body, &redirect),
+        subject: email.subject
+    }
+}
+
+//이슈의 링크를 등록하고 URL별 링크 ID를 반환한다. 이미 등록된 링크는 기존 ID를 사용한다.
+#[tracing::instrument(skip(pool, urls))]
+pub async fn register_links(
+    pool: &PgPool,
+    issue_id: Uuid,
+    urls: &[String]
+) -> Result<HashMap<String, Uuid>, sqlx::Error> {
+    let link_ids: Vec<Uuid> = urls.iter().map(|_| Uuid::new_v4()).collect();
+    sqlx::query!(
+        r#"
+        INSERT INTO issue_links (link_id, newsletter_issue_id, url)
+        SELECT link_id, $1, url FROM UNNEST($2::uuid[], $3::text[]) AS l(link_id, url)
+        ON CONFLICT (newsletter_issue_id, url) DO NOTHING
+        "#,
+        issue_id,
+        &link_ids,
+        urls
+    )
+    .execute(pool)
+    .await?;
+    let rows = sqlx::query!(
+        r#"SELECT link_id, url FROM issue_links WHERE newsletter_issue_id = $1 AND url = ANY($2)"#,
+        issue_id,
+        urls
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(rows.into_iter().map(|r| (r.url, r.link_id)).collect())
+}
+
+//클릭을 기록하고 리다이렉트할 URL을 반환한다. 이슈의 링크가 아니면 'None'이다.
+//추적을 거부한 구독자나 삭제된 구독자의 클릭은 기록하지 않지만 리다이렉트는 한다.
+#[tracing::instrument(skip(pool))]
+pub async fn record_click(
+    pool: &PgPool,
+    issue_id: Uuid,
+    subscriber_id: Uuid,
+    link_id: Uuid
+) -> Result<Option<String>, sqlx::Error> {
+    let url = sqlx::query_scalar!(
+        r#"SELECT url FROM issue_links WHERE link_id = $1 AND newsletter_issue_id = $2"#,
+        link_id,
+        issue_id
+    )
+    .fetch_optional(pool)
+    .await?;
+    if url.is_some() {
+        sqlx::query!(
+            r#"
+            INSERT INTO link_clicks (link_id, subscriber_id)
+            SELECT $1, id FROM subscriptions WHERE id = $2 AND NOT tracking_opt_out
+            ON CONFLICT (link_id, subscriber_id) DO UPDATE
+            SET click_count = link_clicks.click_count + 1, last_clicked_at = now()
+            "#,
+            link_id,
+            subscriber_id
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{inject_open_pixel, rewrite_html_links, rewrite_text_links, UserAgentClass};
+
+    #[test]
+    fn user_agents_are_reduced_to_a_class() {
+        let gmail = "Mozilla/5.0 (Windows NT 5.1; rv:11.0) Gecko Firefox/11.0 (via ggpht.com GoogleImageProxy)";
+        let iphone = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko)";
+        let mac = "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko)";
+        assert_eq!(UserAgentClass::classify(Some(gmail)), UserAgentClass::ImageProxy);
+        assert_eq!(UserAgentClass::classify(Some(iphone)), UserAgentClass::Mobile);
+        assert_eq!(UserAgentClass::classify(Some(mac)), UserAgentClass::Desktop);
+        assert_eq!(UserAgentClass::classify(Some("curl/8.0")), UserAgentClass::Unknown);
+        assert_eq!(UserAgentClass::classify(None), UserAgentClass::Unknown);
+    }
+
+    #[test]
+    fn the_pixel_is_placed_before_the_closing_body_tag() {
+        assert_eq!(
+            inject_open_pixel("<html><BODY><p>Hi</p></BODY></html>", "http://x/o/a?b=1&c=2"),
+            r#"<html><BODY><p>Hi</p><img src="http://x/o/a?b=1&amp;c=2" width="1" height="1" alt="" style="display:none"></BODY></html>"#
+        );
+        assert!(inject_open_pixel("<p>Hi</p>", "http://x/o/a").starts_with("<p>Hi</p><img"));
+    }
+
+    #[test]
+    fn only_href_values_of_anchor_tags_are_rewritten() {
+        let html = r#"<link href="https://x/style.css"><abbr title="a">A</abbr><A class="b" HREF='https://x/a?b=1&amp;c=2'>a</A><a href="mailto:y">y</a>"#;
+        let rewritten = rewrite_html_links(html, |url| url.starts_with("https").then(|| format!("[{}]", url)));
+        assert_eq!(
+            rewritten,
+            r#"<link href="https://x/style.css"><abbr title="a">A</abbr><A class="b" HREF='[https://x/a?b=1&amp;c=2]'>a</A><a href="mailto:y">y</a>"#
+        );
+    }
+
+    #[test]
+    fn trailing_punctuation_is_not_part_of_a_text_link() {
+        let text = "Read https://x/a. Or (see http://x/b?c=1), then HTTPS://x/c";
+        let rewritten = rewrite_text_links(text, |url| Some(format!("[{}]", url)));
+        assert_eq!(rewritten, "Read [https://x/a]. Or (see [http://x/b?c=1]), then [HTTPS://x/c]");
+    }
+}
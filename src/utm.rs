@@ -0,0 +1,105 @@
+use reqwest::Url;
+use crate::configuration::UtmSettings;
+use crate::email_templates::title_slug;
+
+//20261019 추가 / 외부 링크에 UTM 파라미터를 붙인다.
+//이슈를 발행할 때 켠 경우에만 붙이며, 이미 UTM 파라미터가 있는 링크와 제외한 도메인(우리 사이트 포함)의 링크는 그대로 둔다.
+#[derive(Debug, Clone)]
+pub struct UtmTagger {
+    source: String,
+    medium: String,
+    excluded_domains: Vec<String>
+}
+
+impl UtmTagger {
+    //애플리케이션의 'base_url' 도메인은 외부 링크가 아니므로 항상 제외한다.
+    pub fn new(settings: &UtmSettings, base_url: &str) -> Self {
+        let mut excluded_domains: Vec<String> = settings
+            .excluded_domains
+            .iter()
+            .map(|domain| domain.trim().trim_start_matches('.').to_lowercase())
+            .filter(|domain| !domain.is_empty())
+            .collect();
+        if let Some(host) = Url::parse(base_url).ok().and_then(|url| url.host_str().map(str::to_lowercase)) {
+            excluded_domains.push(host);
+        }
+        Self {
+            source: settings.source.clone(),
+            medium: settings.medium.clone(),
+            excluded_domains
+        }
+    }
+
+    //하위 도메인도 제외한다.
+    fn is_excluded(&self, host: &str) -> bool {
+        let host = host.to_lowercase();
+        self.excluded_domains
+            .iter()
+            .any(|domain| host == *domain || host.ends_with(&format!(".{}", domain)))
+    }
+
+    //파라미터를 붙인 URL을 반환한다. 붙이지 않을 링크는 'None'이다.
+    pub fn tag(&self, url: &str, campaign: &str) -> Option<String> {
+        let mut url = Url::parse(url).ok()?;
+        if !matches!(url.scheme(), "http" | "https") || self.is_excluded(url.host_str()?) {
+            return None;
+        }
+        if url.query_pairs().any(|(key, _)| key.to_lowercase().starts_with("utm_")) {
+            return None;
+        }
+        url.query_pairs_mut()
+            .append_pair("utm_source", &self.source)
+            .append_pair("utm_medium", &self.medium)
+            .append_pair("utm_campaign", campaign);
+        Some(url.into())
+    }
+}
+
+//이슈 제목으로 만든 기본 캠페인 이름
+pub fn campaign_slug(title: &str) -> String {
+    let slug = title_slug(title);
+    if slug.is_empty() {
+        "newsletter".to_string()
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{campaign_slug, UtmTagger};
+    use crate::configuration::UtmSettings;
+
+    fn tagger() -> UtmTagger {
+        let settings = UtmSettings {
+            source: "newsletter".into(),
+            medium: "email".into(),
+            excluded_domains: vec!["partner.com".into()]
+        };
+        UtmTagger::new(&settings, "https://news.example.com")
+    }
+
+    #[test]
+    fn external_links_are_tagged() {
+        assert_eq!(
+            tagger().tag("https://blog.org/post?id=1#top", "october").as_deref(),
+            Some("https://blog.org/post?id=1&utm_source=newsletter&utm_medium=email&utm_campaign=october#top")
+        );
+    }
+
+    #[test]
+    fn tagged_and_excluded_links_are_left_alone() {
+        assert_eq!(tagger().tag("https://blog.org/?UTM_source=x", "october"), None);
+        assert_eq!(tagger().tag("https://partner.com/", "october"), None);
+        assert_eq!(tagger().tag("https://shop.partner.com/", "october"), None);
+        assert_eq!(tagger().tag("https://news.example.com/archive", "october"), None);
+        assert_eq!(tagger().tag("mailto:editor@blog.org", "october"), None);
+        assert!(tagger().tag("https://notpartner.com/", "october").is_some());
+    }
+
+    #[test]
+    fn the_campaign_defaults_to_a_slug_of_the_title() {
+        assert_eq!(campaign_slug("October Update: {{ subscriber_name }}'s 10 picks!"), "october-update-s-10-picks");
+        assert_eq!(campaign_slug("{{ subscriber_name }}"), "newsletter");
+    }
+}
@@ -0,0 +1,186 @@
+use crate::helpers::{assert_is_redirect_to, spawn_app, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn subscribe(app: &TestApp) -> Uuid {
+    app.test_user.login(app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    subscriber_id
+}
+
+async fn publish(app: &TestApp, title: &str, visibility: &str) -> reqwest::Response {
+    let response = app.post_publish_newsletter(&serde_json::json!({
+        "title": title,
+        "text_content": "Hello {{ subscriber_name }}",
+        "html_content": "<p>Hello {{ subscriber_name }}</p>",
+        "visibility": visibility,
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    app.dispatch_all_pending_emails().await;
+    response
+}
+
+//마지막으로 발송된 이메일의 '브라우저에서 보기' 링크
+async fn view_in_browser_link(app: &TestApp) -> Option<reqwest::Url> {
+    let email_request = app.email_server.received_requests().await.unwrap().pop().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+    linkify::LinkFinder::new()
+        .links(body["TextBody"].as_str().unwrap())
+        .find(|l| l.as_str().contains("/archive/"))
+        .map(|l| {
+            let mut link = reqwest::Url::parse(l.as_str()).unwrap();
+            link.set_port(Some(app.port)).unwrap();
+            link
+        })
+}
+
+#[tokio::test]
+async fn public_issues_are_listed_and_rendered_for_anyone() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    publish(&app, "October Update", "public").await;
+
+    //Assert
+    let link = view_in_browser_link(&app).await.unwrap();
+    assert_eq!(link.path(), "/archive/october-update");
+    assert_eq!(link.query(), None);
+    let index = reqwest::get(format!("{}/archive", app.address)).await.unwrap().text().await.unwrap();
+    assert!(index.contains(r#"<a href="/archive/october-update">October Update</a>"#));
+    let response = reqwest::get(link).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let page = response.text().await.unwrap();
+    assert!(page.contains("<p>Hello reader</p>"));
+}
+
+#[tokio::test]
+async fn subscribers_only_issues_need_the_signed_link_from_the_email() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    publish(&app, "Members news", "subscribers").await;
+
+    //Assert
+    let link = view_in_browser_link(&app).await.unwrap();
+    assert!(link.query().unwrap().starts_with("token="));
+    let response = reqwest::get(link.clone()).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("<p>Hello le guin</p>"));
+
+    let index = reqwest::get(format!("{}/archive", app.address)).await.unwrap().text().await.unwrap();
+    assert!(!index.contains("Members news"));
+    let response = reqwest::get(format!("{}/archive/members-news", app.address)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn a_signed_link_for_another_issue_does_not_unlock_a_subscribers_only_issue() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "First", "subscribers").await;
+    let first_link = view_in_browser_link(&app).await.unwrap();
+    publish(&app, "Second", "subscribers").await;
+
+    //Act
+    let response = reqwest::get(format!(
+        "{}/archive/second?{}",
+        app.address,
+        first_link.query().unwrap()
+    ))
+    .await
+    .unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn hidden_issues_are_not_on_the_web() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    publish(&app, "Internal", "hidden").await;
+
+    //Assert
+    assert_eq!(view_in_browser_link(&app).await, None);
+    let response = reqwest::get(format!("{}/archive/internal", app.address)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[tokio::test]
+async fn issues_with_the_same_title_get_distinct_slugs() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    publish(&app, "Weekly notes", "public").await;
+    publish(&app, "Weekly notes", "public").await;
+
+    //Assert
+    let mut slugs: Vec<String> = sqlx::query!("SELECT slug FROM newsletter_issues")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.slug)
+        .collect();
+    slugs.sort();
+    assert_eq!(slugs, vec!["weekly-notes", "weekly-notes-2"]);
+}
+
+#[tokio::test]
+async fn an_invalid_visibility_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    let response = publish(&app, "October Update", "everyone").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(response.text().await.unwrap().contains("everyone is not a valid visibility."));
+}
+
+#[tokio::test]
+async fn admins_can_change_the_visibility_after_publishing() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "October Update", "hidden").await;
+    let issue_id = sqlx::query!("SELECT newsletter_issue_id FROM newsletter_issues")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .newsletter_issue_id;
+
+    //Act
+    let response = app.api_client
+        .post(format!("{}/admin/issues/{}/visibility", app.address, issue_id))
+        .form(&[("visibility", "public")])
+        .send()
+        .await
+        .unwrap();
+
+    //Assert
+    assert_is_redirect_to(&response, &format!("/admin/issues/{}", issue_id));
+    let html_page = app.get_issue_report_html(issue_id).await;
+    assert!(html_page.contains("<p><i>The issue is now public on the web archive.</i></p>"));
+    assert!(html_page.contains(r#"<option value="public" selected>"#));
+    let response = reqwest::get(format!("{}/archive/october-update", app.address)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+}
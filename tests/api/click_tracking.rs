@@ -0,0 +1,210 @@
+use crate::helpers::{spawn_app, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+const HTML_CONTENT: &str = r#"<p>Read <a href="https://example.com/post?a=1&amp;b=2">the post</a> or <a href="mailto:editor@example.com">write to us</a>.</p>"#;
+const TEXT_CONTENT: &str = "Read the post: https://example.com/post?a=1&b=2.";
+
+async fn publish_issue(app: &TestApp) -> Uuid {
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": TEXT_CONTENT,
+        "html_content": HTML_CONTENT,
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    app.dispatch_all_pending_emails().await;
+    sqlx::query!("SELECT newsletter_issue_id FROM newsletter_issues")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .newsletter_issue_id
+}
+
+//마지막으로 발송된 이메일의 HTML 본문과 텍스트 본문
+async fn last_bodies(app: &TestApp) -> (String, String) {
+    let email_request = app.email_server.received_requests().await.unwrap().pop().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+    (body["HtmlBody"].as_str().unwrap().into(), body["TextBody"].as_str().unwrap().into())
+}
+
+//본문에 있는 클릭 추적 리다이렉트 링크
+fn redirect_links(app: &TestApp, body: &str) -> Vec<reqwest::Url> {
+    linkify::LinkFinder::new()
+        .links(body)
+        .filter(|l| *l.kind() == linkify::LinkKind::Url && l.as_str().contains("/r/"))
+        .map(|l| {
+            let mut link = reqwest::Url::parse(l.as_str()).unwrap();
+            link.set_port(Some(app.port)).unwrap();
+            link
+        })
+        .collect()
+}
+
+async fn clicks(app: &TestApp) -> Vec<(String, i32)> {
+    sqlx::query!("SELECT l.url, c.click_count FROM link_clicks c JOIN issue_links l ON l.link_id = c.link_id")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.url, r.click_count))
+        .collect()
+}
+
+async fn subscribe_and_publish(app: &TestApp) -> (Uuid, Uuid) {
+    app.test_user.login(app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    (publish_issue(app).await, subscriber_id)
+}
+
+#[tokio::test]
+async fn links_in_both_bodies_are_rewritten_into_signed_redirects() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    subscribe_and_publish(&app).await;
+
+    //Assert
+    let (html_body, text_body) = last_bodies(&app).await;
+    assert!(!html_body.contains("https://example.com/post"));
+    assert!(html_body.contains(r#"href="mailto:editor@example.com""#));
+    assert!(text_body.starts_with("Read the post: http://127.0.0.1/r/"));
+    assert_eq!(redirect_links(&app, &html_body).len(), 1);
+    //텍스트 본문의 같은 URL은 같은 링크로 등록된다.
+    assert_eq!(redirect_links(&app, &html_body), redirect_links(&app, &text_body));
+    //구독 설정 링크는 바꾸지 않는다.
+    assert!(html_body.contains("/preferences?token="));
+}
+
+#[tokio::test]
+async fn following_a_redirect_records_the_click() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe_and_publish(&app).await;
+    let (html_body, _) = last_bodies(&app).await;
+    let link = redirect_links(&app, &html_body).pop().unwrap();
+
+    for _ in 0..2 {
+        //Act
+        let response = app.api_client.get(link.clone()).send().await.unwrap();
+
+        //Assert
+        assert_eq!(response.status().as_u16(), 302);
+        assert_eq!(response.headers()["Location"], "https://example.com/post?a=1&b=2");
+    }
+    assert_eq!(clicks(&app).await, vec![("https://example.com/post?a=1&b=2".to_string(), 2)]);
+}
+
+#[tokio::test]
+async fn a_tampered_redirect_token_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    let (issue_id, subscriber_id) = subscribe_and_publish(&app).await;
+    let (html_body, _) = last_bodies(&app).await;
+    let mut link = redirect_links(&app, &html_body).pop().unwrap();
+    let tampered = link.path().replacen(&issue_id.to_string(), &Uuid::new_v4().to_string(), 1);
+    link.set_path(&tampered);
+    //이슈에 등록되지 않은 링크 ID로 서명을 위조할 수 없다.
+    let mut forged = link.clone();
+    forged.set_path(&format!("/r/{}:{}:{}.00", issue_id, subscriber_id, Uuid::new_v4()));
+
+    for link in [link, forged] {
+        //Act
+        let response = app.api_client.get(link).send().await.unwrap();
+
+        //Assert
+        assert_eq!(response.status().as_u16(), 401);
+        assert!(response.headers().get("Location").is_none());
+    }
+    assert!(clicks(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn links_are_not_rewritten_for_subscribers_who_opted_out_of_tracking() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    app.opt_out_of_tracking(subscriber_id).await;
+
+    //Act
+    publish_issue(&app).await;
+
+    //Assert
+    let (html_body, text_body) = last_bodies(&app).await;
+    assert!(html_body.starts_with(HTML_CONTENT));
+    assert!(text_body.starts_with(TEXT_CONTENT));
+}
+
+#[tokio::test]
+async fn the_issue_report_shows_clicks_per_link() {
+    //Arrange
+    let app = spawn_app().await;
+    let (issue_id, _) = subscribe_and_publish(&app).await;
+    let (html_body, _) = last_bodies(&app).await;
+    let link = redirect_links(&app, &html_body).pop().unwrap();
+    app.api_client.get(link).send().await.unwrap();
+
+    //Act
+    let html_page = app.get_issue_report_html(issue_id).await;
+
+    //Assert
+    assert!(html_page.contains(
+        r#"<tr><td><a href="https://example.com/post?a=1&amp;b=2">https://example.com/post?a=1&amp;b=2</a></td><td>1</td><td>1</td></tr>"#
+    ));
+}
+
+//20261019 추가 / 구독자가 입력한 값은 이슈의 링크가 아니므로 리다이렉트로 바꾸지 않는다.
+#[tokio::test]
+async fn urls_from_custom_field_values_are_not_rewritten() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.api_client
+        .post(format!("{}/admin/fields", &app.address))
+        .form(&serde_json::json!({"label": "Company", "key": "company", "field_type": "text"}))
+        .send()
+        .await
+        .unwrap();
+    app.subscribe_and_confirm(
+        "name=le%20guin&email=ursula_le_guin%40gmail.com&company=https%3A%2F%2Fevil.example%2Fphish"
+    )
+    .await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": format!("{} Your company: {{{{ fields.company }}}}", TEXT_CONTENT),
+        "html_content": format!(r#"{}<p><a href="{{{{ fields.company }}}}">Your company</a></p>"#, HTML_CONTENT),
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let (html_body, text_body) = last_bodies(&app).await;
+    assert!(text_body.contains("Your company: https://evil.example/phish"));
+    assert!(html_body.contains(r#"<a href="https://evil.example/phish">Your company</a>"#));
+    assert_eq!(redirect_links(&app, &text_body).len(), 1);
+    assert_eq!(redirect_links(&app, &html_body).len(), 1);
+    let links = sqlx::query!("SELECT url FROM issue_links")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].url, "https://example.com/post?a=1&b=2");
+}
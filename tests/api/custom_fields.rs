@@ -0,0 +1,241 @@
+use crate::helpers::{spawn_app, assert_is_redirect_to, TestApp};
+use wiremock::matchers::{any, method, path};
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn get_fields_html(app: &TestApp) -> String {
+    app.api_client
+        .get(format!("{}/admin/fields", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .unwrap()
+}
+
+async fn post_create_field<Body>(app: &TestApp, body: &Body) -> reqwest::Response
+where
+    Body: serde::Serialize {
+    app.api_client
+        .post(format!("{}/admin/fields", &app.address))
+        .form(body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn post_subscriber_fields<Body>(app: &TestApp, subscriber_id: Uuid, body: &Body) -> reqwest::Response
+where
+    Body: serde::Serialize {
+    app.api_client
+        .post(format!("{}/admin/subscribers/{}/fields", &app.address, subscriber_id))
+        .form(body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+//'company'(필수 텍스트), 'age'(숫자), 'language'(선택형) 필드를 만든다.
+async fn create_fields(app: &TestApp) {
+    for body in [
+        serde_json::json!({"label": "Company", "key": "company", "field_type": "text", "required": "true"}),
+        serde_json::json!({"label": "Age", "key": "age", "field_type": "number"}),
+        serde_json::json!({"label": "Language", "key": "language", "field_type": "choice", "choices": "en, ko"})
+    ] {
+        let response = post_create_field(app, &body).await;
+        assert_is_redirect_to(&response, "/admin/fields");
+    }
+}
+
+async fn stored_custom_fields(app: &TestApp) -> serde_json::Value {
+    sqlx::query!("SELECT custom_fields FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .custom_fields
+}
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_manage_custom_fields() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = post_create_field(&app, &serde_json::json!({
+        "label": "Company",
+        "key": "company",
+        "field_type": "text"
+    }))
+    .await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/login");
+}
+
+#[tokio::test]
+async fn an_admin_can_define_custom_fields() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    create_fields(&app).await;
+
+    //Assert
+    let html_page = get_fields_html(&app).await;
+    assert!(html_page.contains("<p><i>The field 'language' has been created.</i></p>"));
+    assert!(html_page.contains("<td>Company</td><td>company</td><td>text</td><td>yes</td>"));
+}
+
+#[tokio::test]
+async fn a_choice_field_without_choices_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    post_create_field(&app, &serde_json::json!({
+        "label": "Language",
+        "key": "language",
+        "field_type": "choice"
+    }))
+    .await;
+
+    //Assert
+    let html_page = get_fields_html(&app).await;
+    assert!(html_page.contains("A choice field needs at least one choice."));
+}
+
+#[tokio::test]
+async fn custom_fields_are_stored_at_signup() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_fields(&app).await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app
+        .post_subscriptions("name=le%20guin&email=ursula_le_guin%40gmail.com&company=Acme&age=42&language=ko&ignored=1".into())
+        .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        stored_custom_fields(&app).await,
+        serde_json::json!({"company": "Acme", "age": 42.0, "language": "ko"})
+    );
+}
+
+#[tokio::test]
+async fn subscribe_returns_a_400_when_custom_fields_are_invalid() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_fields(&app).await;
+    let test_cases = vec![
+        ("name=le%20guin&email=ursula_le_guin%40gmail.com", "missing a required field"),
+        ("name=le%20guin&email=ursula_le_guin%40gmail.com&company=Acme&age=old", "not a number"),
+        ("name=le%20guin&email=ursula_le_guin%40gmail.com&company=Acme&language=fr", "not one of the choices")
+    ];
+
+    for (body, description) in test_cases {
+        //Act
+        let response = app.post_subscriptions(body.into()).await;
+
+        //Assert
+        assert_eq!(
+            400,
+            response.status().as_u16(),
+            "The API did not return a 400 Bad Request when the payload was {}.",
+            description
+        );
+    }
+}
+
+#[tokio::test]
+async fn an_admin_can_edit_the_custom_fields_of_a_subscriber() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_fields(&app).await;
+    let subscriber_id = app
+        .subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com&company=Acme&age=42")
+        .await;
+
+    //Act
+    let response = post_subscriber_fields(&app, subscriber_id, &serde_json::json!({
+            "company": "Initech",
+            "age": "",
+            "language": "en"
+        }))
+        .await;
+
+    //Assert
+    assert_is_redirect_to(&response, &format!("/admin/subscribers/{}", subscriber_id));
+    assert_eq!(
+        stored_custom_fields(&app).await,
+        serde_json::json!({"company": "Initech", "language": "en"})
+    );
+    let html_page = app.get_subscriber_details_html(subscriber_id).await;
+    assert!(html_page.contains(r#"<option value="en" selected>en</option>"#));
+}
+
+#[tokio::test]
+async fn newsletters_are_personalized_with_custom_fields() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_fields(&app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com&company=Acme").await;
+
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_publish_newsletter(&serde_json::json!({
+        "title": "News for {{ subscriber_name }}",
+        "text_content": "Hello {{ fields.company }}",
+        "html_content": "<p>Hello {{ fields.company }}</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    assert_is_redirect_to(&response, "/admin/newsletters");
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let email_requests = app.email_server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_requests.last().unwrap().body).unwrap();
+    assert_eq!(body["Subject"], "News for le guin");
+    assert!(body["TextBody"].as_str().unwrap().starts_with("Hello Acme"));
+    assert!(body["HtmlBody"].as_str().unwrap().starts_with("<p>Hello Acme</p>"));
+}
+
+#[tokio::test]
+async fn an_issue_referencing_an_unknown_field_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    let response = app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Hello {{ fields.company }}",
+        "html_content": "<p>Hello</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+    let html_page = response.text().await.unwrap();
+    assert!(html_page.contains("Invalid placeholder"));
+}
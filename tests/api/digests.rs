@@ -0,0 +1,210 @@
+use crate::helpers::{spawn_app, TestApp};
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use zero2prod::digests::send_due_digests;
+
+const IMMEDIATE: &str = "ursula_le_guin@gmail.com";
+const WEEKLY: &str = "octavia_butler@gmail.com";
+
+//즉시 받는 구독자와 주간 다이제스트를 받는 구독자를 만든다. 주간 구독자의 id를 반환한다.
+async fn subscribe(app: &TestApp) -> Uuid {
+    app.test_user.login(app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let subscriber_id = app.subscribe_and_confirm("name=butler&email=octavia_butler%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    set_preferences(app, subscriber_id, "weekly", "").await;
+    subscriber_id
+}
+
+async fn set_preferences(app: &TestApp, subscriber_id: Uuid, delivery_frequency: &str, pause: &str) {
+    let token = app.preferences_token(subscriber_id);
+    let list_id = sqlx::query!("SELECT list_id FROM lists WHERE slug = 'newsletter'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .list_id
+        .to_string();
+    let response = app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "butler"),
+        ("lists", &list_id),
+        ("delivery_frequency", delivery_frequency),
+        ("pause", pause)
+    ])
+    .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+async fn publish(app: &TestApp, title: &str) {
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": title,
+        "text_content": format!("{} as plain text", title),
+        "html_content": format!("<p>{} as HTML</p>", title),
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    app.dispatch_all_pending_emails().await;
+}
+
+async fn send_digests(app: &TestApp, now: DateTime<Utc>) -> u64 {
+    send_due_digests(
+        &app.db_pool, &app.email_client, &app.link_signer, &app.utm_tagger, Duration::days(7), now
+    )
+    .await
+    .unwrap()
+}
+
+//구독 확인 이메일을 제외하고 한 주소로 발송된 이메일
+async fn emails_to(app: &TestApp, address: &str) -> Vec<serde_json::Value> {
+    app.email_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|r| serde_json::from_slice::<serde_json::Value>(&r.body).unwrap())
+        .filter(|body| body["To"] == address && !body["Subject"].as_str().unwrap().starts_with("Welcome"))
+        .collect()
+}
+
+fn subjects(emails: &[serde_json::Value]) -> Vec<&str> {
+    emails.iter().map(|e| e["Subject"].as_str().unwrap()).collect()
+}
+
+#[tokio::test]
+async fn weekly_subscribers_receive_the_week_s_issues_in_one_email() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    let start = Utc::now();
+
+    //Act - Part 1
+    publish(&app, "First issue").await;
+    publish(&app, "Second issue").await;
+    let sent_early = send_digests(&app, start + Duration::days(6)).await;
+
+    //Assert - Part 1
+    assert_eq!(subjects(&emails_to(&app, IMMEDIATE).await), vec!["First issue", "Second issue"]);
+    assert!(emails_to(&app, WEEKLY).await.is_empty());
+    assert_eq!(sent_early, 0);
+
+    //Act - Part 2
+    let sent = send_digests(&app, start + Duration::days(7) + Duration::minutes(1)).await;
+    let sent_again = send_digests(&app, start + Duration::days(7) + Duration::minutes(2)).await;
+
+    //Assert - Part 2
+    assert_eq!((sent, sent_again), (1, 0));
+    let emails = emails_to(&app, WEEKLY).await;
+    assert_eq!(subjects(&emails), vec!["Weekly digest: First issue and 1 more"]);
+    let html_body = emails[0]["HtmlBody"].as_str().unwrap();
+    assert!(html_body.starts_with(
+        "<h2>First issue</h2>\n<p>First issue as HTML</p>\
+        <p><a href=\"http://127.0.0.1/archive/first-issue\">View this issue in your browser</a></p>\n<hr>\n\
+        <h2>Second issue</h2>\n<p>Second issue as HTML</p>"
+    ));
+    assert!(html_body.contains("Manage your subscription preferences or unsubscribe"));
+    let text_body = emails[0]["TextBody"].as_str().unwrap();
+    assert!(text_body.starts_with(
+        "First issue\n\nFirst issue as plain text\n\n\
+        View this issue in your browser: http://127.0.0.1/archive/first-issue\n\n----------\n\n\
+        Second issue\n\nSecond issue as plain text"
+    ));
+    assert_eq!(emails_to(&app, IMMEDIATE).await.len(), 2);
+}
+
+#[tokio::test]
+async fn the_next_digest_waits_for_a_full_interval_after_the_last_one() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    let start = Utc::now();
+    publish(&app, "First issue").await;
+    send_digests(&app, start + Duration::days(7) + Duration::minutes(1)).await;
+
+    //Act
+    publish(&app, "Second issue").await;
+    let sent_early = send_digests(&app, start + Duration::days(14)).await;
+    let sent = send_digests(&app, start + Duration::days(14) + Duration::minutes(1)).await;
+
+    //Assert
+    assert_eq!((sent_early, sent), (0, 1));
+    assert_eq!(
+        subjects(&emails_to(&app, WEEKLY).await),
+        vec!["Weekly digest: First issue", "Weekly digest: Second issue"]
+    );
+}
+
+#[tokio::test]
+async fn unsubscribed_weekly_subscribers_do_not_receive_a_digest() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = subscribe(&app).await;
+    publish(&app, "First issue").await;
+
+    //Act
+    app.post_unsubscribe(&app.preferences_token(subscriber_id)).await;
+    send_digests(&app, Utc::now() + Duration::days(7)).await;
+
+    //Assert
+    assert!(emails_to(&app, WEEKLY).await.is_empty());
+    let waiting = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM digest_queue")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(waiting, 0);
+}
+
+#[tokio::test]
+async fn digests_are_held_while_the_subscriber_is_paused() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = subscribe(&app).await;
+    publish(&app, "First issue").await;
+
+    //Act - Part 1
+    set_preferences(&app, subscriber_id, "weekly", "30").await;
+    send_digests(&app, Utc::now() + Duration::days(7)).await;
+
+    //Assert - Part 1
+    assert!(emails_to(&app, WEEKLY).await.is_empty());
+
+    //Act - Part 2
+    set_preferences(&app, subscriber_id, "weekly", "").await;
+    send_digests(&app, Utc::now() + Duration::days(7)).await;
+
+    //Assert - Part 2
+    assert_eq!(subjects(&emails_to(&app, WEEKLY).await), vec!["Weekly digest: First issue"]);
+}
+
+#[tokio::test]
+async fn the_issue_report_counts_weekly_digest_recipients_separately() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "First issue").await;
+    let issue_id = sqlx::query!("SELECT newsletter_issue_id FROM newsletter_issues")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .newsletter_issue_id;
+
+    //Act - Part 1
+    let html_page = app.get_issue_report_html(issue_id).await;
+
+    //Assert - Part 1
+    assert!(html_page.contains("<li>Recipients: 1</li>"));
+    assert!(html_page.contains("<li>Weekly digest recipients: 1</li>"));
+    assert!(html_page.contains("<li>Waiting for the weekly digest: 1</li>"));
+
+    //Act - Part 2
+    send_digests(&app, Utc::now() + Duration::days(7)).await;
+    let html_page = app.get_issue_report_html(issue_id).await;
+
+    //Assert - Part 2
+    assert!(html_page.contains("<li>Waiting for the weekly digest: 0</li>"));
+}
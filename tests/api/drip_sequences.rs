@@ -0,0 +1,387 @@
+use crate::helpers::{assert_is_redirect_to, spawn_app, TestApp};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use zero2prod::drip_sequences::run_drip_scheduler_once;
+
+async fn default_list_id(app: &TestApp) -> Uuid {
+    sqlx::query!("SELECT list_id FROM lists WHERE slug = 'newsletter'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .list_id
+}
+
+//기본 리스트에 시퀀스를 만들고 (지연 일수, 제목) 단계를 추가한다.
+async fn create_sequence(app: &TestApp, steps: &[(i32, &str)]) -> Uuid {
+    let list_id = default_list_id(app).await;
+    let response = app.api_client
+        .post(format!("{}/admin/sequences", app.address))
+        .form(&serde_json::json!({
+            "name": "Welcome",
+            "list_id": list_id
+        }))
+        .send()
+        .await
+        .unwrap();
+    let sequence_id = sqlx::query!("SELECT sequence_id FROM drip_sequences")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .sequence_id;
+    assert_is_redirect_to(&response, &format!("/admin/sequences/{}", sequence_id));
+    for (delay_days, title) in steps {
+        add_step(app, sequence_id, *delay_days, title).await;
+    }
+    sequence_id
+}
+
+async fn post_step(app: &TestApp, sequence_id: Uuid, delay_days: i32, title: &str) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/admin/sequences/{}/steps", app.address, sequence_id))
+        .form(&serde_json::json!({
+            "delay_days": delay_days,
+            "title": title,
+            "text_content": "Hello {{ subscriber_name }}!",
+            "html_content": "<p>Hello {{ subscriber_name }}!</p>"
+        }))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn add_step(app: &TestApp, sequence_id: Uuid, delay_days: i32, title: &str) {
+    let response = post_step(app, sequence_id, delay_days, title).await;
+    assert_is_redirect_to(&response, &format!("/admin/sequences/{}", sequence_id));
+}
+
+async fn get_sequence_html(app: &TestApp, sequence_id: Uuid) -> String {
+    app.api_client
+        .get(format!("{}/admin/sequences/{}", app.address, sequence_id))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap()
+}
+
+async fn subscribe(app: &TestApp) -> Uuid {
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    subscriber_id
+}
+
+//구독 확인 이메일을 제외하고 발송된 이메일의 제목
+async fn sent_subjects(app: &TestApp) -> Vec<String> {
+    app.email_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .skip(1)
+        .map(|r| {
+            let body: serde_json::Value = serde_json::from_slice(&r.body).unwrap();
+            body["Subject"].as_str().unwrap().to_string()
+        })
+        .collect()
+}
+
+async fn run_scheduler(app: &TestApp, days_later: i64) {
+    run_drip_scheduler_once(&app.db_pool, Utc::now() + Duration::days(days_later))
+        .await
+        .unwrap();
+    app.dispatch_all_pending_emails().await;
+}
+
+async fn stop_reason(app: &TestApp) -> Option<String> {
+    sqlx::query!("SELECT stop_reason FROM drip_enrollments")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .stop_reason
+}
+
+#[tokio::test]
+async fn confirmed_subscribers_receive_each_step_once_when_it_is_due() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let sequence_id = create_sequence(&app, &[(0, "Welcome"), (3, "Best of"), (10, "Feedback")]).await;
+    subscribe(&app).await;
+
+    //Act - Part 1
+    run_scheduler(&app, 0).await;
+    run_scheduler(&app, 0).await;
+
+    //Assert - Part 1
+    assert_eq!(sent_subjects(&app).await, vec!["Welcome"]);
+
+    //Act - Part 2
+    run_scheduler(&app, 2).await;
+    run_scheduler(&app, 3).await;
+    run_scheduler(&app, 4).await;
+
+    //Assert - Part 2
+    assert_eq!(sent_subjects(&app).await, vec!["Welcome", "Best of"]);
+    let html_page = get_sequence_html(&app, sequence_id).await;
+    assert!(html_page.contains("<li>Active enrollments: 1</li>"));
+
+    //Act - Part 3
+    run_scheduler(&app, 10).await;
+
+    //Assert - Part 3
+    assert_eq!(sent_subjects(&app).await, vec!["Welcome", "Best of", "Feedback"]);
+    let html_page = get_sequence_html(&app, sequence_id).await;
+    assert!(html_page.contains("<li>Active enrollments: 0</li>"));
+    assert!(html_page.contains("<li>Completed enrollments: 1</li>"));
+    //단계는 발행된 이슈 목록에 나오지 않는다.
+    assert!(!app.get_issues_html().await.contains("Welcome"));
+}
+
+#[tokio::test]
+async fn unsubscribing_stops_the_sequence() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_sequence(&app, &[(0, "Welcome"), (3, "Best of")]).await;
+    let subscriber_id = subscribe(&app).await;
+    run_scheduler(&app, 0).await;
+
+    //Act
+    let response = app.post_unsubscribe(&app.preferences_token(subscriber_id)).await;
+    assert_eq!(response.status().as_u16(), 200);
+    run_scheduler(&app, 3).await;
+
+    //Assert
+    assert_eq!(sent_subjects(&app).await, vec!["Welcome"]);
+    assert_eq!(stop_reason(&app).await.as_deref(), Some("unsubscribed"));
+}
+
+#[tokio::test]
+async fn pausing_stops_the_sequence() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let sequence_id = create_sequence(&app, &[(0, "Welcome"), (3, "Best of")]).await;
+    let subscriber_id = subscribe(&app).await;
+    run_scheduler(&app, 0).await;
+    let token = app.preferences_token(subscriber_id);
+    let list_id = default_list_id(&app).await.to_string();
+
+    //Act
+    app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "le guin"),
+        ("lists", &list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "30")
+    ])
+    .await;
+    run_scheduler(&app, 3).await;
+    //일시 중지가 끝나도 이어서 보내지 않는다.
+    app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "le guin"),
+        ("lists", &list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "")
+    ])
+    .await;
+    run_scheduler(&app, 4).await;
+
+    //Assert
+    assert_eq!(sent_subjects(&app).await, vec!["Welcome"]);
+    assert_eq!(stop_reason(&app).await.as_deref(), Some("paused"));
+    let html_page = get_sequence_html(&app, sequence_id).await;
+    assert!(html_page.contains("<li>Stopped enrollments: 1</li>"));
+}
+
+//20261019 추가 / 발송 큐에 이미 적재된 단계도 일시 중지하거나 리스트에서 빠지면 보내지 않는다.
+#[tokio::test]
+async fn steps_already_queued_are_not_sent_after_pausing_or_leaving_the_list() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_sequence(&app, &[(0, "Getting started")]).await;
+    let beta_list_id = app.create_list("Beta testers", "beta").await.to_string();
+    let list_id = default_list_id(&app).await.to_string();
+    let paused_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let leaving_id = app.subscribe_and_confirm("name=butler&email=octavia_butler%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    run_drip_scheduler_once(&app.db_pool, Utc::now()).await.unwrap();
+
+    //Act
+    let paused_token = app.preferences_token(paused_id);
+    app.post_preferences(&[
+        ("token", paused_token.as_str()),
+        ("name", "le guin"),
+        ("lists", &list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "30")
+    ])
+    .await;
+    let leaving_token = app.preferences_token(leaving_id);
+    app.post_preferences(&[
+        ("token", leaving_token.as_str()),
+        ("name", "butler"),
+        ("lists", &beta_list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "")
+    ])
+    .await;
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    assert!(!sent_subjects(&app).await.contains(&"Getting started".to_string()));
+    let queued = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM issue_delivery_queue")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(queued, 0);
+}
+
+#[tokio::test]
+async fn inactive_sequences_do_not_enroll_or_send() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let sequence_id = create_sequence(&app, &[(0, "Welcome")]).await;
+
+    //Act
+    let response = app.api_client
+        .post(format!("{}/admin/sequences/{}/active", app.address, sequence_id))
+        .form(&[("active", "false")])
+        .send()
+        .await
+        .unwrap();
+    let html_page = get_sequence_html(&app, sequence_id).await;
+    subscribe(&app).await;
+    run_scheduler(&app, 0).await;
+
+    //Assert
+    assert_is_redirect_to(&response, &format!("/admin/sequences/{}", sequence_id));
+    assert!(html_page.contains("<p><i>The sequence has been deactivated.</i></p>"));
+    assert!(html_page.contains("Welcome / List: Newsletter / Inactive"));
+    assert!(sent_subjects(&app).await.is_empty());
+    let enrollments = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM drip_enrollments")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(enrollments, 0);
+}
+
+#[tokio::test]
+async fn steps_added_later_are_only_sent_to_subscribers_who_have_not_reached_them() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let sequence_id = create_sequence(&app, &[(0, "Welcome")]).await;
+    subscribe(&app).await;
+    run_scheduler(&app, 0).await;
+
+    //Act
+    //이미 지난 날의 단계는 보내지 않고, 아직 이르지 않은 날의 단계는 보낸다.
+    add_step(&app, sequence_id, 0, "Late welcome").await;
+    add_step(&app, sequence_id, 3, "Best of").await;
+    run_scheduler(&app, 3).await;
+
+    //Assert
+    assert_eq!(sent_subjects(&app).await, vec!["Welcome", "Best of"]);
+}
+
+#[tokio::test]
+async fn invalid_steps_are_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let sequence_id = create_sequence(&app, &[]).await;
+    let cases = vec![
+        (-1, "Welcome", "The delay must be between 0 and 365 days."),
+        (0, "", "The title and both contents must not be empty.")
+    ];
+
+    for (delay_days, title, message) in cases {
+        //Act
+        let response = post_step(&app, sequence_id, delay_days, title).await;
+
+        //Assert
+        assert_is_redirect_to(&response, &format!("/admin/sequences/{}", sequence_id));
+        let html_page = get_sequence_html(&app, sequence_id).await;
+        assert!(html_page.contains(&format!("<p><i>{}</i></p>", message)), "{}", message);
+    }
+
+    //Act - 알 수 없는 치환자
+    let response = app.api_client
+        .post(format!("{}/admin/sequences/{}/steps", app.address, sequence_id))
+        .form(&serde_json::json!({
+            "delay_days": 0,
+            "title": "Welcome",
+            "text_content": "Hello {{ fields.unknown }}",
+            "html_content": "<p>Hello</p>"
+        }))
+        .send()
+        .await
+        .unwrap();
+
+    //Assert
+    assert_is_redirect_to(&response, &format!("/admin/sequences/{}", sequence_id));
+    let html_page = get_sequence_html(&app, sequence_id).await;
+    assert!(html_page.contains("Invalid placeholder"));
+    let steps = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM drip_steps")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(steps, 0);
+}
+
+#[tokio::test]
+async fn sequences_are_listed_on_the_sequences_page() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let sequence_id = create_sequence(&app, &[(0, "Welcome"), (3, "Best of")]).await;
+    subscribe(&app).await;
+
+    //Act
+    let html_page = app.api_client
+        .get(format!("{}/admin/sequences", app.address))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+
+    //Assert
+    assert!(html_page.contains(&format!(
+        r#"<tr><td><a href="/admin/sequences/{}">Welcome</a></td><td>Newsletter</td><td>2</td><td>1</td><td>Active</td></tr>"#,
+        sequence_id
+    )));
+}
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_manage_sequences() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.api_client
+        .get(format!("{}/admin/sequences", app.address))
+        .send()
+        .await
+        .unwrap();
+
+    //Assert
+    assert_is_redirect_to(&response, "/login");
+}
@@ -0,0 +1,185 @@
+use crate::helpers::{spawn_app, TestApp};
+use wiremock::matchers::{any, method, path};
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn post_email_change(app: &TestApp, token: &str, email: &str) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/preferences/email", &app.address))
+        .form(&[("token", token), ("email", email)])
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn stored_email(app: &TestApp) -> String {
+    sqlx::query!("SELECT email FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .email
+}
+
+//이메일 변경을 요청하고, 새 주소로 발송된 확인 링크를 반환한다.
+async fn request_email_change(app: &TestApp, subscriber_id: Uuid, new_email: &str) -> reqwest::Url {
+    let _mock_guard = Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount_as_scoped(&app.email_server)
+        .await;
+    let token = app.preferences_token(subscriber_id);
+    let response = post_email_change(app, &token, new_email).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let email_request = app.email_server.received_requests().await.unwrap().pop().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+    assert_eq!(body["To"], new_email);
+    app.get_confirmation_links(&email_request).html
+}
+
+#[tokio::test]
+async fn requesting_an_email_change_does_not_change_the_email_yet() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    request_email_change(&app, subscriber_id, "ursula@earthsea.com").await;
+
+    //Assert
+    assert_eq!(stored_email(&app).await, "ursula_le_guin@gmail.com");
+}
+
+#[tokio::test]
+async fn confirming_the_new_address_changes_the_email() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let confirmation_link = request_email_change(&app, subscriber_id, "ursula@earthsea.com").await;
+
+    //Act
+    let response = reqwest::get(confirmation_link.clone()).await.unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("Email address updated"));
+    assert_eq!(stored_email(&app).await, "ursula@earthsea.com");
+    //같은 링크를 다시 클릭해도 결과는 같다.
+    let html_page = reqwest::get(confirmation_link).await.unwrap().text().await.unwrap();
+    assert!(html_page.contains("Email address updated"));
+}
+
+#[tokio::test]
+async fn issues_queued_before_the_change_are_delivered_to_the_new_address() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let confirmation_link = request_email_change(&app, subscriber_id, "ursula@earthsea.com").await;
+    //발송되기 전에 큐에 적재한다.
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Newsletter body as plain text",
+        "html_content": "<p>Newsletter body as HTML</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    reqwest::get(confirmation_link).await.unwrap().error_for_status().unwrap();
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let email_request = app.email_server.received_requests().await.unwrap().pop().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+    assert_eq!(body["To"], "ursula@earthsea.com");
+    assert_eq!(body["Subject"], "Newsletter title");
+}
+
+//20261019 수정 / 주소가 구독 중인지 드러나지 않도록 성공했을 때와 같은 응답을 보여준다.
+#[tokio::test]
+async fn an_address_used_by_another_subscriber_gets_the_same_response_but_no_email() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    app.subscribe_and_confirm("name=tolkien&email=tolkien%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+    let _mock_guard = Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount_as_scoped(&app.email_server)
+        .await;
+
+    //Act
+    let response = post_email_change(&app, &token, "Tolkien@gmail.com").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains(
+        "We have sent a confirmation link to Tolkien@gmail.com. Your email address will change once you click it."
+    ));
+    assert_eq!(stored_email(&app).await, "ursula_le_guin@gmail.com");
+}
+
+//20261019 추가
+#[tokio::test]
+async fn an_address_taken_after_the_request_is_not_applied() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let confirmation_link = request_email_change(&app, subscriber_id, "ursula@earthsea.com").await;
+    app.subscribe_and_confirm("name=ged&email=Ursula%40earthsea.com").await;
+
+    //Act
+    let response = reqwest::get(confirmation_link).await.unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 409);
+    assert!(response.text().await.unwrap().contains("Email address already in use"));
+    let saved = sqlx::query!("SELECT email FROM subscriptions WHERE id = $1", subscriber_id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(saved.email, "ursula_le_guin@gmail.com");
+}
+
+//20261019 추가 / 대소문자만 바꾸는 것은 자신의 주소를 바꾸는 것이므로 허용한다.
+#[tokio::test]
+async fn subscribers_can_change_the_case_of_their_own_address() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let confirmation_link = request_email_change(&app, subscriber_id, "Ursula_Le_Guin@gmail.com").await;
+
+    //Act
+    let response = reqwest::get(confirmation_link).await.unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(stored_email(&app).await, "Ursula_Le_Guin@gmail.com");
+}
+
+#[tokio::test]
+async fn an_invalid_new_address_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = post_email_change(&app, &token, "definitely-not-an-email").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(stored_email(&app).await, "ursula_le_guin@gmail.com");
+}
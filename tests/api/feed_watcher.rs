@@ -0,0 +1,313 @@
+use crate::helpers::{assert_is_redirect_to, spawn_app, TestApp};
+use chrono::{Duration, Utc};
+use wiremock::matchers::{any, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+use zero2prod::configuration::{FeedMode, FeedWatcherSettings, WatchedFeedSettings};
+use zero2prod::feed_watcher::FeedWatcher;
+
+//블로그 피드. 최근 글부터 나온다.
+fn rss(posts: &[(&str, &str)]) -> String {
+    let mut items = String::new();
+    for (id, title) in posts.iter().rev() {
+        let title = htmlescape::encode_minimal(title);
+        items.push_str(&format!(
+            "<item><title>{title}</title><link>https://blog.example.com/{id}</link><guid>{id}</guid>\
+            <description>&lt;p&gt;About {title}&lt;/p&gt;</description></item>"
+        ));
+    }
+    format!(r#"<?xml version="1.0"?><rss version="2.0"><channel><title>The blog</title>{items}</channel></rss>"#)
+}
+
+async fn serve_feed(blog: &MockServer, posts: &[(&str, &str)]) {
+    blog.reset().await;
+    Mock::given(method("GET"))
+        .and(path("/feed.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(rss(posts)))
+        .mount(blog)
+        .await;
+}
+
+fn watcher(blog: &MockServer, mode: FeedMode) -> FeedWatcher {
+    FeedWatcher::new(&FeedWatcherSettings {
+        poll_interval_seconds: 60,
+        feeds: vec![WatchedFeedSettings {
+            url: format!("{}/feed.xml", blog.uri()),
+            mode,
+            send_delay_minutes: 60,
+            digest_interval_hours: 24,
+            visibility: String::new(),
+            title_template: None,
+            html_template: None,
+            text_template: None
+        }]
+    })
+    .unwrap()
+}
+
+async fn subscribe(app: &TestApp) {
+    app.test_user.login(app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+}
+
+//제목과 상태
+async fn issues(app: &TestApp) -> Vec<(String, String)> {
+    sqlx::query!("SELECT title, status FROM newsletter_issues ORDER BY published_at, title")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.title, r.status))
+        .collect()
+}
+
+//구독 확인 이메일을 제외하고 발송된 이슈 이메일
+async fn sent_issue_emails(app: &TestApp) -> Vec<serde_json::Value> {
+    app.email_server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .skip(1)
+        .map(|r| serde_json::from_slice(&r.body).unwrap())
+        .collect()
+}
+
+#[tokio::test]
+async fn posts_already_in_the_feed_on_the_first_check_are_not_sent() {
+    //Arrange
+    let app = spawn_app().await;
+    let blog = MockServer::start().await;
+    serve_feed(&blog, &[("old-1", "Old post"), ("old-2", "Older post")]).await;
+    let watcher = watcher(&blog, FeedMode::Draft);
+
+    //Act
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+
+    //Assert
+    assert!(issues(&app).await.is_empty());
+    let seen = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM watched_feed_items")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(seen, 2);
+}
+
+#[tokio::test]
+async fn new_posts_become_drafts_that_an_admin_can_send() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    let blog = MockServer::start().await;
+    let watcher = watcher(&blog, FeedMode::Draft);
+    serve_feed(&blog, &[("old", "Old post")]).await;
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+    serve_feed(&blog, &[("old", "Old post"), ("new", "Fish & chips")]).await;
+
+    //Act - Part 1
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+    //같은 글을 다시 봐도 이슈를 또 만들지 않는다.
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+    app.dispatch_all_pending_emails().await;
+
+    //Assert - Part 1
+    assert_eq!(issues(&app).await, vec![("Fish & chips".to_string(), "draft".to_string())]);
+    assert!(sent_issue_emails(&app).await.is_empty());
+    let response = reqwest::get(format!("{}/archive/fish-chips", app.address)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 404);
+    let issue_id = sqlx::query!("SELECT newsletter_issue_id FROM newsletter_issues")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .newsletter_issue_id;
+    let html_page = app.get_issue_report_html(issue_id).await;
+    assert!(html_page.contains("<p>Fish &amp; chips / Draft</p>"));
+
+    //Act - Part 2
+    let response = app.api_client
+        .post(format!("{}/admin/issues/{}/send", app.address, issue_id))
+        .send()
+        .await
+        .unwrap();
+    app.dispatch_all_pending_emails().await;
+
+    //Assert - Part 2
+    assert_is_redirect_to(&response, &format!("/admin/issues/{}", issue_id));
+    let html_page = app.get_issue_report_html(issue_id).await;
+    assert!(html_page.contains("<p><i>The issue has been accepted - emails will go out shortly.</i></p>"));
+    let emails = sent_issue_emails(&app).await;
+    assert_eq!(emails.len(), 1);
+    assert_eq!(emails[0]["Subject"], "Fish & chips");
+    assert!(emails[0]["HtmlBody"].as_str().unwrap().starts_with(
+        "<h1>Fish &amp; chips</h1>\n<p>About Fish &amp; chips</p>\n<p><a href=\"http://127.0.0.1/r/"
+    ));
+    let response = reqwest::get(format!("{}/archive/fish-chips", app.address)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+
+    //Act - Part 3 - 두 번 발송하지 않는다.
+    app.api_client
+        .post(format!("{}/admin/issues/{}/send", app.address, issue_id))
+        .send()
+        .await
+        .unwrap();
+
+    //Assert - Part 3
+    let html_page = app.get_issue_report_html(issue_id).await;
+    assert!(html_page.contains("<p><i>The issue has already been sent.</i></p>"));
+}
+
+#[tokio::test]
+async fn scheduled_issues_are_sent_once_their_time_has_come() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    let blog = MockServer::start().await;
+    let watcher = watcher(&blog, FeedMode::Scheduled);
+    serve_feed(&blog, &[]).await;
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+    serve_feed(&blog, &[("new", "New post")]).await;
+
+    //Act - Part 1
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+    app.dispatch_all_pending_emails().await;
+
+    //Assert - Part 1
+    assert_eq!(issues(&app).await, vec![("New post".to_string(), "scheduled".to_string())]);
+    assert!(sent_issue_emails(&app).await.is_empty());
+    assert!(app.get_issues_html().await.contains("Scheduled for "));
+
+    //Act - Part 2
+    watcher.run_once(&app.db_pool, Utc::now() + Duration::minutes(61)).await.unwrap();
+    app.dispatch_all_pending_emails().await;
+
+    //Assert - Part 2
+    assert_eq!(issues(&app).await, vec![("New post".to_string(), "published".to_string())]);
+    assert_eq!(sent_issue_emails(&app).await.len(), 1);
+}
+
+#[tokio::test]
+async fn new_posts_are_collected_into_a_digest_on_a_cadence() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    let blog = MockServer::start().await;
+    let watcher = watcher(&blog, FeedMode::Digest);
+    let start = Utc::now();
+    serve_feed(&blog, &[("old", "Old post")]).await;
+    watcher.run_once(&app.db_pool, start).await.unwrap();
+    serve_feed(&blog, &[("old", "Old post"), ("first", "First post")]).await;
+    watcher.run_once(&app.db_pool, start + Duration::hours(1)).await.unwrap();
+    serve_feed(&blog, &[("old", "Old post"), ("first", "First post"), ("second", "Second post")]).await;
+
+    //Act - Part 1
+    watcher.run_once(&app.db_pool, start + Duration::hours(2)).await.unwrap();
+
+    //Assert - Part 1
+    assert!(issues(&app).await.is_empty());
+
+    //Act - Part 2
+    watcher.run_once(&app.db_pool, start + Duration::hours(25)).await.unwrap();
+    watcher.run_once(&app.db_pool, start + Duration::hours(26)).await.unwrap();
+    app.dispatch_all_pending_emails().await;
+
+    //Assert - Part 2
+    assert_eq!(issues(&app).await, vec![("New from The blog".to_string(), "published".to_string())]);
+    assert_eq!(sent_issue_emails(&app).await.len(), 1);
+    //발송된 이메일의 링크는 클릭 추적 리다이렉트로 바뀌므로 이슈 본문을 확인한다.
+    let text_content = sqlx::query!("SELECT text_content FROM newsletter_issues")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .text_content;
+    assert_eq!(
+        text_content,
+        "New from The blog\n\n\
+        - First post\n  https://blog.example.com/first\n  About First post\n\
+        - Second post\n  https://blog.example.com/second\n  About Second post"
+    );
+}
+
+#[tokio::test]
+async fn a_broken_feed_does_not_stop_the_others() {
+    //Arrange
+    let app = spawn_app().await;
+    let blog = MockServer::start().await;
+    let broken = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&broken)
+        .await;
+    serve_feed(&blog, &[("old", "Old post")]).await;
+    let mut settings = FeedWatcherSettings {
+        poll_interval_seconds: 60,
+        feeds: Vec::new()
+    };
+    for url in [format!("{}/feed.xml", broken.uri()), format!("{}/feed.xml", blog.uri())] {
+        settings.feeds.push(WatchedFeedSettings {
+            url,
+            mode: FeedMode::Draft,
+            send_delay_minutes: 0,
+            digest_interval_hours: 24,
+            visibility: String::new(),
+            title_template: Some("[Blog] {{ item.title }}".into()),
+            html_template: None,
+            text_template: None
+        });
+    }
+    let watcher = FeedWatcher::new(&settings).unwrap();
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+    serve_feed(&blog, &[("old", "Old post"), ("new", "New post")]).await;
+
+    //Act
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+
+    //Assert
+    assert_eq!(issues(&app).await, vec![("[Blog] New post".to_string(), "draft".to_string())]);
+}
+
+//20261019 추가
+#[tokio::test]
+async fn feeds_over_the_size_limit_are_not_read() {
+    //Arrange
+    let app = spawn_app().await;
+    let blog = MockServer::start().await;
+    let watcher = watcher(&blog, FeedMode::Draft);
+    let padding = format!("<!--{}-->", "x".repeat(32 * 1024 * 1024));
+    Mock::given(method("GET"))
+        .and(path("/feed.xml"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(rss(&[("old", "Old post")]) + &padding))
+        .mount(&blog)
+        .await;
+
+    //Act
+    watcher.run_once(&app.db_pool, Utc::now()).await.unwrap();
+
+    //Assert
+    let checked = sqlx::query!("SELECT feed_url FROM watched_feeds")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert!(checked.is_empty());
+}
+
+#[test]
+fn templates_with_unknown_placeholders_are_rejected_at_startup() {
+    let result = FeedWatcher::new(&FeedWatcherSettings {
+        poll_interval_seconds: 60,
+        feeds: vec![WatchedFeedSettings {
+            url: "http://127.0.0.1/feed.xml".into(),
+            mode: FeedMode::Draft,
+            send_delay_minutes: 0,
+            digest_interval_hours: 24,
+            visibility: String::new(),
+            title_template: Some("{{ subscriber_name }}".into()),
+            html_template: None,
+            text_template: None
+        }]
+    });
+    assert!(result.is_err());
+}
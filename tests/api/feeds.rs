@@ -0,0 +1,197 @@
+use crate::helpers::{spawn_app, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn subscribe(app: &TestApp) {
+    app.test_user.login(app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+}
+
+async fn publish(app: &TestApp, title: &str, visibility: &str) {
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": title,
+        "text_content": "Hello {{ subscriber_name }}",
+        "html_content": "<p>Hello {{ subscriber_name }} & friends</p>",
+        "visibility": visibility,
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    app.dispatch_all_pending_emails().await;
+}
+
+async fn get_feed(app: &TestApp, path: &str, headers: &[(&str, &str)]) -> reqwest::Response {
+    let mut request = app.api_client.get(format!("{}{}", app.address, path));
+    for (name, value) in headers {
+        request = request.header(*name, *value);
+    }
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn feeds_list_public_issues_newest_first() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "First post", "public").await;
+    publish(&app, "Members news", "subscribers").await;
+    publish(&app, "Internal", "hidden").await;
+    publish(&app, "Second post", "public").await;
+
+    for (path, content_type) in [
+        ("/feed.rss", "application/rss+xml; charset=utf-8"),
+        ("/feed.atom", "application/atom+xml; charset=utf-8")
+    ] {
+        //Act
+        let response = get_feed(&app, path, &[]).await;
+
+        //Assert
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.headers()["Content-Type"], content_type);
+        let body = response.text().await.unwrap();
+        let first = body.find("First post").unwrap();
+        let second = body.find("Second post").unwrap();
+        assert!(second < first);
+        assert!(!body.contains("Members news"));
+        assert!(!body.contains("Internal"));
+        assert!(body.contains("/archive/first-post"));
+        //본문은 이스케이프된 HTML로 들어가고 개인화 자리는 기본값으로 채워진다.
+        assert!(body.contains("&lt;p&gt;Hello reader &amp; friends&lt;/p&gt;"));
+    }
+}
+
+#[tokio::test]
+async fn a_matching_etag_returns_not_modified() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "First post", "public").await;
+
+    for path in ["/feed.rss", "/feed.atom"] {
+        let response = get_feed(&app, path, &[]).await;
+        let etag = response.headers()["ETag"].to_str().unwrap().to_string();
+
+        //Act
+        let response = get_feed(&app, path, &[("If-None-Match", &etag)]).await;
+
+        //Assert
+        assert_eq!(response.status().as_u16(), 304);
+        assert_eq!(response.headers()["ETag"], etag.as_str());
+        assert!(response.text().await.unwrap().is_empty());
+    }
+}
+
+#[tokio::test]
+async fn an_unchanged_feed_since_the_last_modified_date_returns_not_modified() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "First post", "public").await;
+    let response = get_feed(&app, "/feed.rss", &[]).await;
+    let last_modified = response.headers()["Last-Modified"].to_str().unwrap().to_string();
+
+    //Act
+    let response = get_feed(&app, "/feed.rss", &[("If-Modified-Since", &last_modified)]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 304);
+
+    //Act - 더 이전 날짜
+    let response = get_feed(&app, "/feed.rss", &[("If-Modified-Since", "Mon, 01 Jan 2024 00:00:00 GMT")]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+//20261019 추가 / 이전 이슈를 숨겨도 가장 최근 발행 시각은 그대로이므로 이슈가 바뀐 시각으로 판단해야 한다.
+#[tokio::test]
+async fn hiding_an_older_issue_changes_the_last_modified_date() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "First post", "public").await;
+    publish(&app, "Second post", "public").await;
+    //HTTP 날짜는 초 단위이므로 기다리지 않도록 한 시간 전에 발행한 것으로 만든다.
+    sqlx::query!("UPDATE newsletter_issues SET published_at = published_at - interval '1 hour'")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    sqlx::query!("UPDATE newsletter_issues SET updated_at = updated_at - interval '1 hour'")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    let response = get_feed(&app, "/feed.rss", &[]).await;
+    let last_modified = response.headers()["Last-Modified"].to_str().unwrap().to_string();
+    let issue_id = sqlx::query!("SELECT newsletter_issue_id FROM newsletter_issues WHERE slug = 'first-post'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .newsletter_issue_id;
+
+    //Act
+    app.api_client
+        .post(format!("{}/admin/issues/{}/visibility", app.address, issue_id))
+        .form(&[("visibility", "hidden")])
+        .send()
+        .await
+        .unwrap();
+
+    //Assert
+    let response = get_feed(&app, "/feed.rss", &[("If-Modified-Since", &last_modified)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_ne!(response.headers()["Last-Modified"].to_str().unwrap(), last_modified);
+    assert!(!response.text().await.unwrap().contains("First post"));
+}
+
+#[tokio::test]
+async fn the_etag_changes_when_the_public_issues_change() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+    publish(&app, "First post", "public").await;
+    let etag = get_feed(&app, "/feed.atom", &[]).await.headers()["ETag"].to_str().unwrap().to_string();
+
+    //Act - Part 1
+    publish(&app, "Second post", "public").await;
+
+    //Assert - Part 1
+    let response = get_feed(&app, "/feed.atom", &[("If-None-Match", &etag)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let etag = response.headers()["ETag"].to_str().unwrap().to_string();
+
+    //Act - Part 2
+    let issue_id = sqlx::query!("SELECT newsletter_issue_id FROM newsletter_issues WHERE slug = 'second-post'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .newsletter_issue_id;
+    app.api_client
+        .post(format!("{}/admin/issues/{}/visibility", app.address, issue_id))
+        .form(&[("visibility", "hidden")])
+        .send()
+        .await
+        .unwrap();
+
+    //Assert - Part 2
+    let response = get_feed(&app, "/feed.atom", &[("If-None-Match", &etag)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(!response.text().await.unwrap().contains("Second post"));
+}
+
+#[tokio::test]
+async fn an_empty_feed_is_still_valid() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = get_feed(&app, "/feed.atom", &[]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers().get("Last-Modified").is_none());
+    assert!(response.text().await.unwrap().contains("<feed xmlns=\"http://www.w3.org/2005/Atom\">"));
+}
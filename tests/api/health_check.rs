@@ -9,7 +9,7 @@ async fn health_check_works() {
 
     //Act(조작)
     let response = client
-        .get(&format!("{}/health_check", &app.address))
+        .get(format!("{}/health_check", &app.address))
         .send()
         .await
         .expect("Faules to exectue request");
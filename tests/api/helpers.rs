@@ -7,13 +7,16 @@ use once_cell::sync::Lazy;
 use sqlx::{Connection, Executor, PgConnection, PgPool};
 //use std::net::TcpListener;
 use uuid::Uuid;
-use zero2prod::configuration::{get_configuration, DatabaseSettings};
+use zero2prod::configuration::{get_configuration, DatabaseSettings, WebhookSettings};
 //use zero2prod::email_client::EmailClient;
 //use zero2prod::startup::run;
 use zero2prod::telemetry::{get_subscriber, init_subscriber};
 use zero2prod::startup::{Application, get_connection_pool};
 //20250206 추가 mock서버를 실행해서 Postmark의 API를 대신하게 하고 밖으로 전송되는 요청을 가로채야 됨.
 use wiremock::MockServer;
+//20261019 추가
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
 //20250220 추가 비밀번호 저장 시 암호화 해시 작업으로 추가 sha3에서 argon2로 변경
 //use sha3::Digest;
 use argon2::password_hash::SaltString;
@@ -21,6 +24,11 @@ use argon2::{Argon2, PasswordHasher, Algorithm, Params, Version};
 //20250317 추가
 use zero2prod::email_client::EmailClient;
 use zero2prod::issue_delivery_worker::{try_execute_task, ExecutionOutcome};
+//20261019 추가
+use zero2prod::signed_links::LinkSigner;
+use zero2prod::email_templates::ConfirmationEmailTemplate;
+use zero2prod::subscriber_import::{try_import_next_row, ImportOutcome};
+use zero2prod::utm::UtmTagger;
 
 //'once_cell' 을 사용해서 'TRACING' 스택이 한 번만 초기화되는 것을 보장한다.
 static TRACING: Lazy<()> = Lazy::new(|| {
@@ -66,14 +74,20 @@ pub struct TestApp {
     //20250226 추가
     pub api_client: reqwest::Client,
     //20250317 추가
-    pub email_client: EmailClient
+    pub email_client: EmailClient,
+    //20261019 추가
+    pub link_signer: LinkSigner,
+    pub base_url: String,
+    pub confirmation_email_template: ConfirmationEmailTemplate,
+    pub webhook_settings: WebhookSettings,
+    pub utm_tagger: UtmTagger
 }
 
 impl TestApp {
     //20250226 수정 -> spawn_app()에서 reqwest::Client 인스턴스 사용
     pub async fn post_subscriptions(&self, body: String) -> reqwest::Response {
         self.api_client
-            .post(&format!("{}/subscriptions", &self.address))
+            .post(format!("{}/subscriptions", &self.address))
             .header("Content-Type", "application/x-www-form-urlencoded")
             .body(body)
             .send()
@@ -104,8 +118,8 @@ impl TestApp {
             confirmation_link
         };
 
-        let html = get_link(&body["HtmlBody"].as_str().unwrap());
-        let plain_text = get_link(&body["TextBody"].as_str().unwrap());
+        let html = get_link(body["HtmlBody"].as_str().unwrap());
+        let plain_text = get_link(body["TextBody"].as_str().unwrap());
         ConfirmationLinks {
             html,
             plain_text
@@ -114,7 +128,7 @@ impl TestApp {
 
     pub async fn get_publish_newsletter(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/admin/newsletters", &self.address))
+            .get(format!("{}/admin/newsletters", &self.address))
             .send()
             .await
             .expect("Failed to execute request.")
@@ -128,7 +142,7 @@ impl TestApp {
     where
         Body: serde::Serialize, {
         self.api_client
-            .post(&format!("{}/admin/newsletters", &self.address))
+            .post(format!("{}/admin/newsletters", &self.address))
             .form(body)
             .send()
             .await
@@ -140,7 +154,7 @@ impl TestApp {
     where
         Body: serde::Serialize, {
             self.api_client
-                .post(&format!("{}/login", &self.address))
+                .post(format!("{}/login", &self.address))
                 //이 'reqwest' 메서드는 바디가 URL인코딩되어 있으며 'Content-Type' 헤더가 그에 따라 설정되어 있음을 보장한다.
                 .form(body)
                 .send()
@@ -150,7 +164,7 @@ impl TestApp {
     //20250226 추가 / 테스트 케이스는 HTML페이지만 확인한다. 따라서 기반 reqwest::Response는 노출하지 않는다.
     pub async fn get_login_html(&self) -> String {
         self.api_client
-            .get(&format!("{}/login", &self.address))
+            .get(format!("{}/login", &self.address))
             .send()
             .await
             .expect("Failed to execute request.")
@@ -161,7 +175,7 @@ impl TestApp {
     //20250226 추가 / 로그인 성공 시 /admin/dashboard로 이동
     pub async fn get_admin_dashboard(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/admin/dashboard", &self.address))
+            .get(format!("{}/admin/dashboard", &self.address))
             .send()
             .await
             .expect("Failed to execute request")
@@ -174,7 +188,7 @@ impl TestApp {
     //20250228 추가 / 비밀버호 변경
     pub async fn get_change_password(&self) -> reqwest::Response {
         self.api_client
-            .get(&format!("{}/admin/password", &self.address))
+            .get(format!("{}/admin/password", &self.address))
             .send()
             .await
             .expect("Failed to execute request")
@@ -190,7 +204,7 @@ impl TestApp {
     where 
         Body: serde::Serialize {
             self.api_client
-                .post(&format!("{}/admin/password", &self.address))
+                .post(format!("{}/admin/password", &self.address))
                 .form(body)
                 .send()
                 .await
@@ -200,16 +214,244 @@ impl TestApp {
     //20250301 추가 / 로그아웃
     pub async fn post_logout(&self) ->reqwest::Response {
         self.api_client
-            .post(&format!("{}/admin/logout", &self.address))
+            .post(format!("{}/admin/logout", &self.address))
             .send()
             .await
             .expect("Failed to execute request.")
     }
 
+    //20261019 추가 / 메일링 리스트 관리
+    pub async fn post_create_list<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize {
+            self.api_client
+                .post(format!("{}/admin/lists", &self.address))
+                .form(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+
+    //20261019 추가 / 리스트를 만들고 그 id를 반환한다.
+    pub async fn create_list(&self, name: &str, slug: &str) -> Uuid {
+        let response = self.post_create_list(&serde_json::json!({
+            "name": name,
+            "slug": slug
+        }))
+        .await;
+        assert_is_redirect_to(&response, "/admin/lists");
+        sqlx::query!("SELECT list_id FROM lists WHERE slug = $1", slug)
+            .fetch_one(&self.db_pool)
+            .await
+            .unwrap()
+            .list_id
+    }
+
+    //20261019 추가 / 구독하고 확인 링크를 클릭한다. 구독자의 id를 반환한다.
+    pub async fn subscribe_and_confirm(&self, body: &str) -> Uuid {
+        let _mock_guard = Mock::given(path("/email"))
+            .and(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount_as_scoped(&self.email_server)
+            .await;
+        self.post_subscriptions(body.into())
+            .await
+            .error_for_status()
+            .unwrap();
+        let email_request = self.email_server.received_requests().await.unwrap().pop().unwrap();
+        let confirmation_links = self.get_confirmation_links(&email_request);
+        reqwest::get(confirmation_links.html)
+            .await
+            .unwrap()
+            .error_for_status()
+            .unwrap();
+        let request_body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+        sqlx::query!(
+            "SELECT id FROM subscriptions WHERE email = $1",
+            request_body["To"].as_str().unwrap()
+        )
+        .fetch_one(&self.db_pool)
+        .await
+        .unwrap()
+        .id
+    }
+
+    //20261019 추가 / 구독자 관리와 세그먼트
+    pub async fn get_subscriber_details_html(&self, subscriber_id: Uuid) -> String {
+        self.api_client
+            .get(format!("{}/admin/subscribers/{}", &self.address, subscriber_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap()
+    }
+
+    pub async fn post_subscriber_tag(&self, subscriber_id: Uuid, tag: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/admin/subscribers/{}/tags", &self.address, subscriber_id))
+            .form(&[("tag", tag)])
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    //20261019 추가 / 구독 설정 페이지
+    pub fn preferences_link(&self, subscriber_id: Uuid) -> reqwest::Url {
+        let mut link = reqwest::Url::parse(&self.link_signer.preferences_link(subscriber_id)).unwrap();
+        link.set_port(Some(self.port)).unwrap();
+        link
+    }
+
+    pub fn preferences_token(&self, subscriber_id: Uuid) -> String {
+        self.preferences_link(subscriber_id)
+            .query_pairs()
+            .find(|(key, _)| key == "token")
+            .map(|(_, value)| value.into_owned())
+            .unwrap()
+    }
+
+    pub async fn post_preferences<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize {
+            self.api_client
+                .post(format!("{}/preferences", &self.address))
+                .form(body)
+                .send()
+                .await
+                .expect("Failed to execute request.")
+        }
+
+    pub async fn post_unsubscribe(&self, token: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/preferences/unsubscribe", &self.address))
+            .form(&[("token", token)])
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    //20261019 추가 / 개인정보 열람 및 삭제 요청
+    pub async fn get_personal_data_export(&self, email: &str) -> reqwest::Response {
+        self.api_client
+            .get(format!("{}/admin/data-requests/export", &self.address))
+            .query(&[("email", email)])
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn post_erase_personal_data(&self, email: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}/admin/data-requests/erase", &self.address))
+            .form(&[("email", email)])
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    //20261019 추가 / 열람 추적
+    //기본 리스트의 구독은 유지한 채로 추적을 거부한다.
+    pub async fn opt_out_of_tracking(&self, subscriber_id: Uuid) {
+        let list_id = sqlx::query!("SELECT list_id FROM lists WHERE slug = 'newsletter'")
+            .fetch_one(&self.db_pool)
+            .await
+            .unwrap()
+            .list_id
+            .to_string();
+        let token = self.preferences_token(subscriber_id);
+        let response = self.post_preferences(&[
+            ("token", token.as_str()),
+            ("name", "le guin"),
+            ("lists", &list_id),
+            ("delivery_frequency", "immediate"),
+            ("pause", ""),
+            ("no_tracking", "on")
+        ])
+        .await;
+        assert_eq!(response.status().as_u16(), 200);
+        assert!(response.text().await.unwrap().contains(r#"name="no_tracking" checked"#));
+    }
+
+    pub async fn get_issues_html(&self) -> String {
+        self.api_client
+            .get(format!("{}/admin/issues", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap()
+    }
+
+    pub async fn get_issue_report_html(&self, issue_id: Uuid) -> String {
+        self.api_client
+            .get(format!("{}/admin/issues/{}", &self.address, issue_id))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap()
+    }
+
+    //20261019 추가 / CSV 가져오기
+    pub async fn post_import(&self, csv: &str, fields: &[(&str, &str)]) -> reqwest::Response {
+        let mut form = reqwest::multipart::Form::new().part(
+            "file",
+            reqwest::multipart::Part::text(csv.to_string()).file_name("subscribers.csv")
+        );
+        for (name, value) in fields {
+            form = form.text(name.to_string(), value.to_string());
+        }
+        self.api_client
+            .post(format!("{}/admin/imports", &self.address))
+            .multipart(form)
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn get_import_report_html(&self, report_path: &str) -> String {
+        self.api_client
+            .get(format!("{}{}", &self.address, report_path))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+            .text()
+            .await
+            .unwrap()
+    }
+
+    pub async fn post_start_import(&self, report_path: &str) -> reqwest::Response {
+        self.api_client
+            .post(format!("{}{}/start", &self.address, report_path))
+            .send()
+            .await
+            .expect("Failed to execute request.")
+    }
+
+    pub async fn import_all_pending_rows(&self) {
+        loop {
+            let outcome = try_import_next_row(
+                &self.db_pool, &self.email_client, &self.confirmation_email_template, &self.base_url
+            )
+            .await
+            .unwrap();
+            if let ImportOutcome::EmptyQueue = outcome {
+                break;
+            }
+        }
+    }
+
     //20250317 추가
     pub async fn dispatch_all_pending_emails(&self) {
         loop {
-            if let ExecutionOutcome::EmptyQueue = try_execute_task(&self.db_pool, &self.email_client).await.unwrap() {
+            if let ExecutionOutcome::EmptyQueue = try_execute_task(
+                &self.db_pool, &self.email_client, &self.link_signer, &self.utm_tagger
+            ).await.unwrap() {
                 break;
             }
         }
@@ -255,7 +497,7 @@ pub async fn spawn_app() -> TestApp {
     let application_port = application.port();
     //포트를 얻은 뒤 애플리케이션을 시작한다.
     //let address = format!("http://127.0.0.1:{}", application.port());
-    let _ = tokio::spawn(application.run_until_stopped());
+    drop(tokio::spawn(application.run_until_stopped()));
 
     //20250226 추가 / 하나의 reqwest::Client를 생성하고 저장
     let client = reqwest::Client::builder()
@@ -277,7 +519,22 @@ pub async fn spawn_app() -> TestApp {
         //20250226 추가
         api_client: client,
         //20250317 추가
-        email_client: configuration.email_client.client()
+        email_client: configuration.email_client
+            .client()
+            .with_suppression_list(get_connection_pool(&configuration.database)),
+        //20261019 추가
+        link_signer: LinkSigner::new(
+            configuration.application.base_url.clone(),
+            configuration.application.hmac_secret.clone()
+        ),
+        base_url: configuration.application.base_url.clone(),
+        confirmation_email_template: ConfirmationEmailTemplate::load(
+            &std::path::Path::new(&configuration.application.email_templates_directory)
+                .join("confirmation_email")
+        )
+        .expect("Failed to load the confirmation email template"),
+        webhook_settings: configuration.webhooks.clone(),
+        utm_tagger: UtmTagger::new(&configuration.utm, &configuration.application.base_url)
     };
     test_app.test_user.store(&test_app.db_pool).await;
     test_app
@@ -0,0 +1,187 @@
+use crate::helpers::{spawn_app, assert_is_redirect_to, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn get_lists_html(app: &TestApp) -> String {
+    app.api_client
+        .get(format!("{}/admin/lists", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_manage_lists() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app.post_create_list(&serde_json::json!({
+        "name": "Beta",
+        "slug": "beta"
+    }))
+    .await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/login");
+}
+
+#[tokio::test]
+async fn an_admin_can_create_a_list() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    app.create_list("Beta testers", "beta").await;
+
+    //Assert
+    let html_page = get_lists_html(&app).await;
+    assert!(html_page.contains("<p><i>The list 'beta' has been created.</i></p>"));
+    assert!(html_page.contains("Beta testers"));
+}
+
+#[tokio::test]
+async fn an_invalid_slug_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    let response = app.post_create_list(&serde_json::json!({
+        "name": "Beta testers",
+        "slug": "Beta Testers"
+    }))
+    .await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/admin/lists");
+    let html_page = get_lists_html(&app).await;
+    assert!(html_page.contains("is not a valid list slug."));
+    let count = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM lists"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(count, 1);
+}
+
+#[tokio::test]
+async fn invalid_slugs_are_escaped_in_the_error_message() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    let response = app.post_create_list(&serde_json::json!({
+        "name": "Beta testers",
+        "slug": "<script>alert(1)</script>"
+    }))
+    .await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/admin/lists");
+    let html_page = get_lists_html(&app).await;
+    assert!(html_page.contains("&lt;script&gt;alert(1)&lt;/script&gt; is not a valid list slug."));
+    assert!(!html_page.contains("<script>"));
+}
+
+#[tokio::test]
+async fn subscribing_to_an_unknown_list_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app
+        .post_subscriptions("name=le%20guin&email=ursula_le_guin%40gmail.com&lists=does-not-exist".into())
+        .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn subscribers_can_pick_several_lists_at_once() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.create_list("Beta testers", "beta").await;
+
+    //Act
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com&lists=newsletter&lists=beta")
+        .await;
+
+    //Assert
+    let memberships = sqlx::query!(
+        r#"
+        SELECT l.slug, m.status
+        FROM list_memberships m JOIN lists l ON l.list_id = m.list_id
+        ORDER BY l.slug
+        "#
+    )
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(memberships.len(), 2);
+    assert_eq!(memberships[0].slug, "beta");
+    assert!(memberships.iter().all(|m| m.status == "confirmed"));
+}
+
+#[tokio::test]
+async fn a_confirmed_subscriber_must_confirm_a_newly_requested_list() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.create_list("Beta testers", "beta").await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com&lists=beta")
+        .await;
+
+    //Assert
+    let memberships = sqlx::query!(r#"SELECT status FROM list_memberships"#)
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(memberships.len(), 2);
+    assert!(memberships.iter().all(|m| m.status == "confirmed"));
+}
+
+#[tokio::test]
+async fn newsletters_are_only_delivered_to_members_of_the_targeted_lists() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let beta_list_id = app.create_list("Beta testers", "beta").await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    app.subscribe_and_confirm("name=tolkien&email=tolkien%40gmail.com&lists=beta").await;
+
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_publish_newsletter(&[
+        ("title", "Newsletter title".to_string()),
+        ("text_content", "Newsletter body as plain text".to_string()),
+        ("html_content", "<p>Newsletter body as HTML</p>".to_string()),
+        ("idempotency_key", Uuid::new_v4().to_string()),
+        ("lists", beta_list_id.to_string())
+    ])
+    .await;
+    assert_is_redirect_to(&response, "/admin/newsletters");
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let email_requests = app.email_server.received_requests().await.unwrap();
+    let newsletter_request = email_requests.last().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&newsletter_request.body).unwrap();
+    assert_eq!(body["To"], "tolkien@gmail.com");
+}
@@ -2,7 +2,27 @@ mod helpers;
 mod health_check;
 mod subscriptions;
 mod subscriptions_confirm;
+mod subscriptions_cleanup;
 mod newsletter;
 mod login;
 mod admin_dashboard;
-mod change_password;
\ No newline at end of file
+mod change_password;
+mod lists;
+mod segments;
+mod custom_fields;
+mod preferences;
+mod email_change;
+mod subscriber_import;
+mod subscriber_export;
+mod personal_data;
+mod subscription_events;
+mod webhooks;
+mod suppressions;
+mod open_tracking;
+mod click_tracking;
+mod utm_tagging;
+mod archive;
+mod feeds;
+mod feed_watcher;
+mod drip_sequences;
+mod digests;
\ No newline at end of file
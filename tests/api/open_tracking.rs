@@ -0,0 +1,178 @@
+use crate::helpers::{spawn_app, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+fn open_pixel_link(app: &TestApp, issue_id: Uuid, subscriber_id: Uuid) -> reqwest::Url {
+    let mut link = reqwest::Url::parse(&app.link_signer.open_pixel_link(issue_id, subscriber_id)).unwrap();
+    link.set_port(Some(app.port)).unwrap();
+    link
+}
+
+const IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko)";
+
+//이슈를 발행하고 발송한 뒤 이슈 ID를 반환한다.
+async fn publish_issue(app: &TestApp, track_opens: bool) -> Uuid {
+    let mut body = serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Newsletter body as plain text",
+        "html_content": "<html><body><p>Newsletter body as HTML</p></body></html>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    });
+    if track_opens {
+        body["track_opens"] = "on".into();
+    }
+    app.post_publish_newsletter(&body).await;
+    app.dispatch_all_pending_emails().await;
+    sqlx::query!("SELECT newsletter_issue_id FROM newsletter_issues ORDER BY published_at DESC LIMIT 1")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .newsletter_issue_id
+}
+
+async fn last_html_body(app: &TestApp) -> String {
+    let email_request = app.email_server.received_requests().await.unwrap().pop().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+    body["HtmlBody"].as_str().unwrap().to_string()
+}
+
+async fn opens(app: &TestApp) -> Vec<(i32, String)> {
+    sqlx::query!("SELECT open_count, user_agent_class FROM issue_opens")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| (r.open_count, r.user_agent_class))
+        .collect()
+}
+
+#[tokio::test]
+async fn the_open_pixel_is_included_only_when_tracking_is_enabled() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act - Part 1
+    publish_issue(&app, false).await;
+
+    //Assert - Part 1
+    assert!(!last_html_body(&app).await.contains("/o/"));
+
+    //Act - Part 2
+    let issue_id = publish_issue(&app, true).await;
+
+    //Assert - Part 2
+    let html_body = last_html_body(&app).await;
+    let pixel_link = app.link_signer.open_pixel_link(issue_id, subscriber_id);
+    let pixel_at = html_body.find(&format!(r#"<img src="{}""#, pixel_link)).unwrap();
+    assert!(pixel_at < html_body.find("</body>").unwrap());
+}
+
+#[tokio::test]
+async fn subscribers_who_opted_out_do_not_get_the_open_pixel() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    app.opt_out_of_tracking(subscriber_id).await;
+
+    //Act
+    let issue_id = publish_issue(&app, true).await;
+    reqwest::get(open_pixel_link(&app, issue_id, subscriber_id)).await.unwrap();
+
+    //Assert
+    let html_body = last_html_body(&app).await;
+    assert!(html_body.starts_with("<html><body><p>Newsletter body as HTML</p>"));
+    assert!(!html_body.contains("/o/"));
+    assert!(opens(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn opening_an_issue_records_the_first_open_and_counts_the_rest() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    let issue_id = publish_issue(&app, true).await;
+    let client = reqwest::Client::new();
+
+    for _ in 0..2 {
+        //Act
+        let response = client
+            .get(open_pixel_link(&app, issue_id, subscriber_id))
+            .header("User-Agent", IPHONE)
+            .send()
+            .await
+            .unwrap();
+
+        //Assert
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.headers()["Content-Type"], "image/gif");
+        assert_eq!(response.headers()["Cache-Control"], "no-store");
+        assert!(response.bytes().await.unwrap().starts_with(b"GIF89a"));
+    }
+    assert_eq!(opens(&app).await, vec![(2, "mobile".to_string())]);
+}
+
+#[tokio::test]
+async fn an_invalid_open_token_still_returns_the_image_but_records_nothing() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    let issue_id = publish_issue(&app, true).await;
+    let mut link = open_pixel_link(&app, issue_id, subscriber_id);
+    let tampered = link.path().replacen(&subscriber_id.to_string(), &Uuid::new_v4().to_string(), 1);
+    link.set_path(&tampered);
+
+    //Act
+    let response = reqwest::get(link).await.unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["Content-Type"], "image/gif");
+    assert!(opens(&app).await.is_empty());
+}
+
+#[tokio::test]
+async fn the_issue_report_shows_the_open_rate() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let ursula = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    app.subscribe_and_confirm("name=octavia&email=octavia_butler%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    let issue_id = publish_issue(&app, true).await;
+    reqwest::get(open_pixel_link(&app, issue_id, ursula)).await.unwrap();
+
+    //Act
+    let html_page = app.get_issue_report_html(issue_id).await;
+
+    //Assert
+    assert!(html_page.contains("<li>Recipients: 2</li>"));
+    assert!(html_page.contains("<li>Pending deliveries: 0</li>"));
+    assert!(html_page.contains("<li>Unique opens: 1</li>"));
+    assert!(html_page.contains("<li>Open rate: 50.0%</li>"));
+    assert!(html_page.contains("<tr><td>unknown</td><td>1</td><td>1</td></tr>"));
+}
@@ -0,0 +1,191 @@
+use crate::helpers::{spawn_app, assert_is_redirect_to, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn get_data_requests_html(app: &TestApp) -> String {
+    app.api_client
+        .get(format!("{}/admin/data-requests", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .unwrap()
+}
+
+const EMAIL: &str = "ursula_le_guin@gmail.com";
+
+async fn create_subscriber(app: &TestApp) -> Uuid {
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    app.post_subscriber_tag(subscriber_id, "beta").await;
+    subscriber_id
+}
+
+async fn subscriber_count(app: &TestApp) -> i64 {
+    sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM subscriptions"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count
+}
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_handle_data_requests() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let export = app.get_personal_data_export(EMAIL).await;
+    let erase = app.post_erase_personal_data(EMAIL).await;
+
+    //Assert
+    assert_is_redirect_to(&export, "/login");
+    assert_is_redirect_to(&erase, "/login");
+}
+
+#[tokio::test]
+async fn the_export_contains_every_row_referencing_the_address() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_subscriber(&app).await;
+
+    //Act
+    let response = app.get_personal_data_export("Ursula_Le_Guin@gmail.com").await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers()["Content-Disposition"].to_str().unwrap().contains("personal-data.json"));
+    let data: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(data["subscriptions"][0]["email"], EMAIL);
+    assert_eq!(data["subscriptions"][0]["status"], "confirmed");
+    assert_eq!(data["list_memberships"][0]["list"], "newsletter");
+    assert_eq!(data["tags"][0]["tag"], "beta");
+    assert_eq!(data["subscription_tokens"].as_array().unwrap().len(), 1);
+    assert!(data["subscription_tokens"][0].get("subscription_token").is_none());
+    assert_eq!(data["suppressed"], false);
+}
+
+#[tokio::test]
+async fn erasure_deletes_the_subscriber_and_suppresses_the_address() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_subscriber(&app).await;
+    //발송 대기 중인 이슈도 함께 삭제되어야 한다.
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Newsletter body as plain text",
+        "html_content": "<p>Newsletter body as HTML</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+
+    //Act
+    let response = app.post_erase_personal_data(EMAIL).await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/admin/data-requests");
+    let html_page = get_data_requests_html(&app).await;
+    assert!(html_page.contains("has been erased and the address has been suppressed."));
+    assert_eq!(subscriber_count(&app).await, 0);
+    for table in ["subscription_tokens", "list_memberships", "subscriber_tags", "issue_delivery_queue"] {
+        let count: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(&app.db_pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0, "{} still has rows", table);
+    }
+    app.dispatch_all_pending_emails().await;
+    let data: serde_json::Value = app.get_personal_data_export(EMAIL).await.json().await.unwrap();
+    assert_eq!(data["subscriptions"], serde_json::json!([]));
+    assert_eq!(data["suppressed"], true);
+}
+
+#[tokio::test]
+async fn erased_addresses_are_not_imported_again() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.post_erase_personal_data(EMAIL).await;
+    let list_id = sqlx::query!("SELECT list_id FROM lists WHERE slug = 'newsletter'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .list_id
+        .to_string();
+
+    //Act
+    let response = app
+        .post_import(
+            "email,name\nURSULA_LE_GUIN@gmail.com,Ursula\noctavia@example.com,Octavia\n",
+            &[("list_id", &list_id), ("mode", "confirmed"), ("source", "old-provider"), ("consent_date", "2024-01-31")]
+        )
+        .await;
+    let report_path = response.headers()["Location"].to_str().unwrap().to_string();
+    app.post_start_import(&report_path).await;
+    app.import_all_pending_rows().await;
+
+    //Assert
+    let html_page = app.get_import_report_html(&report_path).await;
+    assert!(html_page.contains("<li>Suppressed: 1</li>"));
+    let emails: Vec<String> = sqlx::query!("SELECT email FROM subscriptions")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.email)
+        .collect();
+    assert_eq!(emails, vec!["octavia@example.com".to_string()]);
+}
+
+#[tokio::test]
+async fn subscribers_can_download_their_data_from_the_preference_center() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let mut link = app.preferences_link(subscriber_id);
+    link.set_path("/preferences/data");
+
+    //Act
+    let response = reqwest::get(link).await.unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let data: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(data["email"], EMAIL);
+    assert_eq!(data["subscriptions"][0]["name"], "le guin");
+}
+
+#[tokio::test]
+async fn subscribers_can_erase_their_data_from_the_preference_center() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+
+    //Act
+    let response = app
+        .api_client
+        .post(format!("{}/preferences/erase", &app.address))
+        .form(&[("token", &token)])
+        .send()
+        .await
+        .unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("We have deleted all the data we held about you."));
+    assert_eq!(subscriber_count(&app).await, 0);
+    let suppressed = sqlx::query!(r#"SELECT reason FROM suppressed_emails"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(suppressed.reason, "erasure");
+}
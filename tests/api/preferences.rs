@@ -0,0 +1,286 @@
+use crate::helpers::{spawn_app, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn default_list_id(app: &TestApp) -> Uuid {
+    sqlx::query!("SELECT list_id FROM lists WHERE slug = 'newsletter'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .list_id
+}
+
+//이슈를 발행하고 발송한 뒤, 이메일 서버가 받은 요청 수를 반환한다.
+async fn publish_and_count_deliveries(app: &TestApp) -> usize {
+    let before = app.email_server.received_requests().await.unwrap().len();
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Newsletter body as plain text",
+        "html_content": "<p>Newsletter body as HTML</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    app.dispatch_all_pending_emails().await;
+    app.email_server.received_requests().await.unwrap().len() - before
+}
+
+#[tokio::test]
+async fn every_issue_contains_a_link_to_the_preference_center() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    publish_and_count_deliveries(&app).await;
+
+    //Assert
+    let email_request = app.email_server.received_requests().await.unwrap().pop().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+    let expected_link = app.link_signer.preferences_link(subscriber_id);
+    assert!(body["TextBody"].as_str().unwrap().contains(&expected_link));
+    assert!(body["HtmlBody"].as_str().unwrap().contains(&expected_link));
+
+    let response = reqwest::get(app.preferences_link(subscriber_id)).await.unwrap();
+    assert_eq!(response.status().as_u16(), 200);
+    let html_page = response.text().await.unwrap();
+    assert!(html_page.contains("Preferences for ursula_le_guin@gmail.com"));
+}
+
+#[tokio::test]
+async fn a_tampered_preferences_link_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let mut link = app.preferences_link(subscriber_id);
+    let token = app.preferences_token(subscriber_id).replacen(&subscriber_id.to_string(), &Uuid::new_v4().to_string(), 1);
+    link.set_query(Some(&format!("token={}", token)));
+
+    //Act
+    let response = reqwest::get(link).await.unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[tokio::test]
+async fn subscribers_can_change_their_name_and_leave_a_list() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let beta_list_id = app.create_list("Beta testers", "beta").await;
+    let token = app.preferences_token(subscriber_id);
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "Ursula K. Le Guin"),
+        ("lists", &beta_list_id.to_string()),
+        ("delivery_frequency", "weekly"),
+        ("pause", "")
+    ])
+    .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("Your preferences have been saved."));
+    let saved = sqlx::query!("SELECT name, delivery_frequency FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(saved.name, "Ursula K. Le Guin");
+    assert_eq!(saved.delivery_frequency, "weekly");
+    let memberships = sqlx::query!("SELECT list_id, status FROM list_memberships ORDER BY status")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(memberships.len(), 2);
+    assert_eq!((memberships[0].list_id, memberships[0].status.as_str()), (beta_list_id, "confirmed"));
+    assert_eq!(memberships[1].status, "unsubscribed");
+    //기본 리스트로 발행한 이슈는 더 이상 받지 않는다.
+    assert_eq!(publish_and_count_deliveries(&app).await, 0);
+}
+
+#[tokio::test]
+async fn an_invalid_name_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+    let list_id = default_list_id(&app).await.to_string();
+
+    //Act
+    let response = app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "<script>"),
+        ("lists", &list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "")
+    ])
+    .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+    let saved = sqlx::query!("SELECT name FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(saved.name, "le guin");
+}
+
+#[tokio::test]
+async fn paused_subscribers_do_not_receive_issues() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+    let list_id = default_list_id(&app).await.to_string();
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "le guin"),
+        ("lists", &list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "30")
+    ])
+    .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("Paused until"));
+    assert_eq!(publish_and_count_deliveries(&app).await, 0);
+
+    //일시 중지를 해제하면 다시 받는다.
+    app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "le guin"),
+        ("lists", &list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "")
+    ])
+    .await;
+    assert_eq!(publish_and_count_deliveries(&app).await, 1);
+}
+
+//20261019 추가
+#[tokio::test]
+async fn the_chosen_delivery_frequency_applies_to_the_next_published_issue() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+    let list_id = default_list_id(&app).await.to_string();
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "le guin"),
+        ("lists", &list_id),
+        ("delivery_frequency", "weekly"),
+        ("pause", "")
+    ])
+    .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    //주간 다이제스트를 선택하면 이슈가 개별로 발송되지 않고 다이제스트 큐에 쌓인다.
+    assert_eq!(publish_and_count_deliveries(&app).await, 0);
+    let queued = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM digest_queue")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(queued, 1);
+
+    //즉시 발송으로 되돌리면 다음 이슈부터 다시 개별로 받는다.
+    app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "le guin"),
+        ("lists", &list_id),
+        ("delivery_frequency", "immediate"),
+        ("pause", "")
+    ])
+    .await;
+    assert_eq!(publish_and_count_deliveries(&app).await, 1);
+}
+
+#[tokio::test]
+async fn subscribers_can_unsubscribe_entirely() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_unsubscribe(&token).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("Unsubscribed"));
+    let saved = sqlx::query!("SELECT status FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(saved.status, "unsubscribed");
+    assert_eq!(publish_and_count_deliveries(&app).await, 0);
+    let html_page = reqwest::get(app.preferences_link(subscriber_id)).await.unwrap().text().await.unwrap();
+    assert!(html_page.contains("You have been unsubscribed"));
+}
+
+#[tokio::test]
+async fn subscribers_who_unsubscribe_after_an_issue_is_published_do_not_receive_it() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Newsletter body as plain text",
+        "html_content": "<p>Newsletter body as HTML</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+
+    //Act
+    app.post_unsubscribe(&app.preferences_token(subscriber_id)).await;
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let pending = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM issue_delivery_queue")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(pending.count, 0);
+    //Mock은 Drop 시점에 이메일을 보내지 않았는지 검증한다.
+}
@@ -0,0 +1,227 @@
+use crate::helpers::{spawn_app, assert_is_redirect_to, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn post_preview_recipients<Body>(app: &TestApp, body: &Body) -> reqwest::Response
+where
+    Body: serde::Serialize {
+    app.api_client
+        .post(format!("{}/admin/newsletters/preview", &app.address))
+        .form(body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+//발행 폼 바디. 세그먼트만 테스트마다 다르다.
+fn newsletter_form(segment: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("title", "Newsletter title".to_string()),
+        ("text_content", "Newsletter body as plain text".to_string()),
+        ("html_content", "<p>Newsletter body as HTML</p>".to_string()),
+        ("idempotency_key", Uuid::new_v4().to_string()),
+        ("segment", segment.to_string())
+    ]
+}
+
+//두 명의 확인된 구독자를 만들고, 첫 번째 구독자에게만 'beta' 태그를 붙인다.
+async fn two_subscribers_one_tagged(app: &TestApp) -> (Uuid, Uuid) {
+    let tagged = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let untagged = app.subscribe_and_confirm("name=tolkien&email=tolkien%40gmail.com").await;
+    let response = app.post_subscriber_tag(tagged, "beta").await;
+    assert_is_redirect_to(&response, &format!("/admin/subscribers/{}", tagged));
+    (tagged, untagged)
+}
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_tag_subscribers() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    let response = app.post_subscriber_tag(subscriber_id, "beta").await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/login");
+}
+
+#[tokio::test]
+async fn an_admin_can_tag_a_subscriber() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    let (tagged, _) = two_subscribers_one_tagged(&app).await;
+
+    //Assert
+    let html_page = app.get_subscriber_details_html(tagged).await;
+    assert!(html_page.contains("<p><i>The tag 'beta' has been added.</i></p>"));
+    let tags = sqlx::query!("SELECT subscriber_id, tag FROM subscriber_tags")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].subscriber_id, tagged);
+    assert_eq!(tags[0].tag, "beta");
+}
+
+#[tokio::test]
+async fn an_invalid_tag_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    let response = app.post_subscriber_tag(subscriber_id, "Beta Testers").await;
+
+    //Assert
+    assert_is_redirect_to(&response, &format!("/admin/subscribers/{}", subscriber_id));
+    let html_page = app.get_subscriber_details_html(subscriber_id).await;
+    assert!(html_page.contains("is not a valid tag."));
+}
+
+#[tokio::test]
+async fn newsletters_are_only_delivered_to_subscribers_matching_the_segment() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    two_subscribers_one_tagged(&app).await;
+
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_publish_newsletter(&newsletter_form("tag = beta")).await;
+    assert_is_redirect_to(&response, "/admin/newsletters");
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let email_requests = app.email_server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_requests.last().unwrap().body).unwrap();
+    assert_eq!(body["To"], "ursula_le_guin@gmail.com");
+}
+
+#[tokio::test]
+async fn segments_can_filter_on_the_subscription_date() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let (_, untagged) = two_subscribers_one_tagged(&app).await;
+    sqlx::query!(
+        "UPDATE subscriptions SET subscribed_at = now() - interval '90 days' WHERE id = $1",
+        untagged
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_publish_newsletter(&newsletter_form("subscribed_at >= now-30d")).await;
+    assert_is_redirect_to(&response, "/admin/newsletters");
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let email_requests = app.email_server.received_requests().await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_requests.last().unwrap().body).unwrap();
+    assert_eq!(body["To"], "ursula_le_guin@gmail.com");
+}
+
+#[tokio::test]
+async fn segments_can_compare_custom_fields() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let (tagged, untagged) = two_subscribers_one_tagged(&app).await;
+    sqlx::query!(
+        r#"UPDATE subscriptions SET custom_fields = '{"age": 42, "country": "KR"}' WHERE id = $1"#,
+        tagged
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    sqlx::query!(
+        r#"UPDATE subscriptions SET custom_fields = '{"age": "unknown", "country": "NZ"}' WHERE id = $1"#,
+        untagged
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    //Act
+    let response = post_preview_recipients(&app, &newsletter_form(r#"field.age > 30 AND field.country = "KR""#))
+        .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let html_page = response.text().await.unwrap();
+    assert!(html_page.contains("This issue would be sent to 1 subscriber(s)."));
+}
+
+#[tokio::test]
+async fn the_recipient_count_preview_keeps_the_form_values() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    two_subscribers_one_tagged(&app).await;
+
+    //Act
+    let response = post_preview_recipients(&app, &newsletter_form("NOT tag = beta")).await;
+
+    //Assert
+    let html_page = response.text().await.unwrap();
+    assert!(html_page.contains("This issue would be sent to 1 subscriber(s)."));
+    assert!(html_page.contains(&format!(
+        r#"value="{}""#,
+        htmlescape::encode_attribute("Newsletter title")
+    )));
+    assert!(html_page.contains("NOT tag = beta</textarea>"));
+    let issues = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM newsletter_issues"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(issues.count, 0);
+}
+
+#[tokio::test]
+async fn an_invalid_segment_is_rejected_without_publishing() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    two_subscribers_one_tagged(&app).await;
+
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_publish_newsletter(&newsletter_form("tag > beta")).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+    let html_page = response.text().await.unwrap();
+    assert!(html_page.contains("Invalid segment: &#x27;tag&#x27; only supports"));
+    assert!(html_page.contains(&format!(
+        r#"value="{}""#,
+        htmlescape::encode_attribute("Newsletter title")
+    )));
+    let issues = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM newsletter_issues"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(issues.count, 0);
+}
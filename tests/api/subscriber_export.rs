@@ -0,0 +1,147 @@
+use crate::helpers::{spawn_app, assert_is_redirect_to, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+
+async fn get_subscribers_export(app: &TestApp, query: &[(&str, &str)]) -> reqwest::Response {
+    app.api_client
+        .get(format!("{}/admin/subscribers/export", &app.address))
+        .query(query)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+//확인된 구독자 한 명과 확인 대기 중인 구독자 한 명을 만든다.
+async fn create_subscribers(app: &TestApp) {
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    app.post_subscriber_tag(subscriber_id, "beta").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    app.post_subscriptions("name=octavia&email=octavia%40example.com".into()).await;
+}
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_export_subscribers() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = get_subscribers_export(&app, &[]).await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/login");
+}
+
+#[tokio::test]
+async fn subscribers_are_exported_as_csv() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_subscribers(&app).await;
+
+    //Act
+    let response = get_subscribers_export(&app, &[("format", "csv")]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers()["Content-Type"].to_str().unwrap().starts_with("text/csv"));
+    assert!(response.headers()["Content-Disposition"].to_str().unwrap().contains("subscribers.csv"));
+    let body = response.text().await.unwrap();
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    let headers = reader.headers().unwrap().clone();
+    assert_eq!(
+        headers.iter().collect::<Vec<_>>(),
+        vec!["id", "email", "name", "status", "subscribed_at", "confirmed_at", "lists", "tags"]
+    );
+    let records: Vec<_> = reader.records().map(|r| r.unwrap()).collect();
+    assert_eq!(records.len(), 2);
+    let confirmed = records.iter().find(|r| &r[1] == "ursula_le_guin@gmail.com").unwrap();
+    assert_eq!(&confirmed[3], "confirmed");
+    assert!(!confirmed[5].is_empty());
+    assert_eq!(&confirmed[6], "newsletter");
+    assert_eq!(&confirmed[7], "beta");
+    let pending = records.iter().find(|r| &r[1] == "octavia@example.com").unwrap();
+    assert_eq!(&pending[3], "pending_confirmation");
+    assert!(pending[5].is_empty());
+}
+
+#[tokio::test]
+async fn subscribers_can_be_exported_as_ndjson_filtered_by_status() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_subscribers(&app).await;
+
+    //Act
+    let response = get_subscribers_export(&app, &[("format", "ndjson"), ("status", "confirmed")]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.text().await.unwrap();
+    let lines: Vec<serde_json::Value> = body.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(lines.len(), 1);
+    assert_eq!(lines[0]["email"], "ursula_le_guin@gmail.com");
+    assert_eq!(lines[0]["tags"], serde_json::json!(["beta"]));
+    assert_eq!(lines[0]["lists"], serde_json::json!(["newsletter"]));
+    assert_eq!(lines[0]["custom_fields"], serde_json::json!({}));
+}
+
+#[tokio::test]
+async fn subscribers_can_be_filtered_by_segment() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    create_subscribers(&app).await;
+
+    //Act
+    let response = get_subscribers_export(&app, &[("format", "ndjson"), ("segment", "tag != beta")]).await;
+
+    //Assert
+    let body = response.text().await.unwrap();
+    assert_eq!(body.lines().count(), 1);
+    assert!(body.contains("octavia@example.com"));
+}
+
+#[tokio::test]
+async fn an_invalid_segment_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    let response = get_subscribers_export(&app, &[("segment", "tag ~ beta")]).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn exports_larger_than_one_batch_contain_every_subscriber_once() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    sqlx::query!(
+        r#"
+        INSERT INTO subscriptions (id, email, name, subscribed_at, status)
+        SELECT gen_random_uuid(), 'subscriber' || n || '@example.com', 'Subscriber ' || n, now(), 'confirmed'
+        FROM generate_series(1, 1234) AS n
+        "#
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    //Act
+    let response = get_subscribers_export(&app, &[("format", "csv")]).await;
+
+    //Assert
+    let body = response.text().await.unwrap();
+    let mut reader = csv::Reader::from_reader(body.as_bytes());
+    let mut emails: Vec<String> = reader.records().map(|r| r.unwrap()[1].to_string()).collect();
+    assert_eq!(emails.len(), 1234);
+    emails.sort();
+    emails.dedup();
+    assert_eq!(emails.len(), 1234);
+}
@@ -0,0 +1,230 @@
+use crate::helpers::{spawn_app, assert_is_redirect_to, TestApp};
+use wiremock::matchers::{any, method, path};
+use wiremock::{Mock, ResponseTemplate};
+
+async fn default_list_id(app: &TestApp) -> String {
+    sqlx::query!("SELECT list_id FROM lists WHERE slug = 'newsletter'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .list_id
+        .to_string()
+}
+
+//CSV를 업로드하고 검증 보고서의 경로를 반환한다.
+async fn upload(app: &TestApp, csv: &str, mode: &str, consent_date: &str) -> String {
+    let list_id = default_list_id(app).await;
+    let response = app
+        .post_import(
+            csv,
+            &[("list_id", &list_id), ("mode", mode), ("source", "old-provider"), ("consent_date", consent_date)]
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 303);
+    let location = response.headers().get("Location").unwrap().to_str().unwrap().to_string();
+    assert!(location.starts_with("/admin/imports/"));
+    location
+}
+
+async fn subscriber_count(app: &TestApp) -> i64 {
+    sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM subscriptions"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count
+}
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_import_subscribers() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = app
+        .post_import("email,name\nursula@example.com,Ursula\n", &[("mode", "confirmed")])
+        .await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/login");
+}
+
+#[tokio::test]
+async fn the_dry_run_reports_invalid_rows_and_duplicates_without_importing() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let csv = "email,name\n\
+        octavia@example.com,Octavia\n\
+        not-an-email,Nobody\n\
+        OCTAVIA@example.com,Octavia again\n\
+        ursula_le_guin@gmail.com,Ursula\n";
+
+    //Act
+    let report_path = upload(&app, csv, "confirmation_email", "").await;
+
+    //Assert
+    let html_page = app.get_import_report_html(&report_path).await;
+    assert!(html_page.contains("<li>Ready to import: 1</li>"));
+    assert!(html_page.contains("<li>Invalid: 1</li>"));
+    assert!(html_page.contains("<li>Duplicates: 1</li>"));
+    assert!(html_page.contains("<li>Already subscribed: 1</li>"));
+    assert!(html_page.contains("not a valid subscriber email"));
+    assert!(html_page.contains("Import 1 subscriber(s)"));
+    assert_eq!(subscriber_count(&app).await, 1);
+}
+
+#[tokio::test]
+async fn a_file_without_the_required_columns_is_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let list_id = default_list_id(&app).await;
+
+    //Act
+    let response = app
+        .post_import(
+            "address\nursula@example.com\n",
+            &[("list_id", &list_id), ("mode", "confirmed"), ("source", "old-provider")]
+        )
+        .await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/admin/imports");
+    let html_page = app.get_import_report_html("/admin/imports").await;
+    assert!(html_page.contains("The CSV file must have &#x27;email&#x27; and &#x27;name&#x27; columns."));
+}
+
+#[tokio::test]
+async fn rejected_uploads_escape_the_submitted_values_in_the_error_message() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let list_id = default_list_id(&app).await;
+
+    //Act
+    let response = app
+        .post_import(
+            "email,name\nursula@example.com,Ursula\n",
+            &[("list_id", &list_id), ("mode", "<script>alert(1)</script>"), ("source", "old-provider")]
+        )
+        .await;
+
+    //Assert
+    assert_is_redirect_to(&response, "/admin/imports");
+    let html_page = app.get_import_report_html("/admin/imports").await;
+    assert!(html_page.contains("&lt;script&gt;alert(1)&lt;/script&gt; is not a valid import mode."));
+    assert!(!html_page.contains("<script>"));
+}
+
+#[tokio::test]
+async fn confirmed_imports_record_the_source_and_consent_without_sending_emails() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+    let csv = "email,name,consent_date\n\
+        octavia@example.com,Octavia,2023-05-01\n\
+        ursula@example.com,Ursula,\n";
+    let report_path = upload(&app, csv, "confirmed", "2024-01-31").await;
+
+    //Act
+    let response = app.post_start_import(&report_path).await;
+    app.import_all_pending_rows().await;
+
+    //Assert
+    assert_is_redirect_to(&response, &report_path);
+    let saved = sqlx::query!(
+        r#"
+        SELECT s.email, s.status, s.source, s.consented_at, m.status AS membership_status
+        FROM subscriptions s JOIN list_memberships m ON m.subscriber_id = s.id
+        ORDER BY s.email
+        "#
+    )
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(saved.len(), 2);
+    for subscriber in &saved {
+        assert_eq!(subscriber.status, "confirmed");
+        assert_eq!(subscriber.membership_status, "confirmed");
+        assert_eq!(subscriber.source.as_deref(), Some("old-provider"));
+    }
+    assert_eq!(saved[0].consented_at.unwrap().format("%Y-%m-%d").to_string(), "2023-05-01");
+    assert_eq!(saved[1].consented_at.unwrap().format("%Y-%m-%d").to_string(), "2024-01-31");
+    let html_page = app.get_import_report_html(&report_path).await;
+    assert!(html_page.contains("<li>Imported: 2</li>"));
+    assert!(html_page.contains("This import has been completed."));
+}
+
+#[tokio::test]
+async fn imports_can_send_confirmation_emails_instead() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(2)
+        .mount(&app.email_server)
+        .await;
+    let csv = "email,name\noctavia@example.com,Octavia\nursula@example.com,Ursula\n";
+    let report_path = upload(&app, csv, "confirmation_email", "").await;
+
+    //Act
+    app.post_start_import(&report_path).await;
+    app.import_all_pending_rows().await;
+
+    //Assert
+    let statuses = sqlx::query!("SELECT status FROM subscriptions")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(statuses.len(), 2);
+    assert!(statuses.iter().all(|s| s.status == "pending_confirmation"));
+    //확인 링크로 구독을 확인할 수 있다.
+    let email_request = &app.email_server.received_requests().await.unwrap()[0];
+    let confirmation_links = app.get_confirmation_links(email_request);
+    reqwest::get(confirmation_links.html).await.unwrap().error_for_status().unwrap();
+    let confirmed = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM subscriptions WHERE status = 'confirmed'"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(confirmed.count, 1);
+}
+
+#[tokio::test]
+async fn nothing_is_imported_until_the_import_is_started() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    upload(&app, "email,name\nursula@example.com,Ursula\n", "confirmed", "2024-01-31").await;
+
+    //Act
+    app.import_all_pending_rows().await;
+
+    //Assert
+    assert_eq!(subscriber_count(&app).await, 0);
+}
+
+#[tokio::test]
+async fn an_import_can_only_be_started_once() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let report_path = upload(&app, "email,name\nursula@example.com,Ursula\n", "confirmed", "2024-01-31").await;
+    app.post_start_import(&report_path).await;
+    app.import_all_pending_rows().await;
+
+    //Act
+    app.post_start_import(&report_path).await;
+
+    //Assert
+    let html_page = app.get_import_report_html(&report_path).await;
+    assert!(html_page.contains("This import has already been started."));
+    assert_eq!(subscriber_count(&app).await, 1);
+}
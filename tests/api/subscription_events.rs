@@ -0,0 +1,191 @@
+use crate::helpers::{spawn_app, TestApp};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+const EMAIL: &str = "ursula_le_guin@gmail.com";
+
+struct StoredEvent {
+    event_type: String,
+    source: String,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    details: serde_json::Value
+}
+
+async fn events_of(app: &TestApp, subscriber_id: Uuid) -> Vec<StoredEvent> {
+    sqlx::query_as!(
+        StoredEvent,
+        r#"
+        SELECT event_type, source, ip_address, user_agent, details
+        FROM subscription_events
+        WHERE subscriber_id = $1
+        ORDER BY occurred_at, event_id
+        "#,
+        subscriber_id
+    )
+    .fetch_all(&app.db_pool)
+    .await
+    .unwrap()
+}
+
+fn event_types(events: &[StoredEvent]) -> Vec<&str> {
+    events.iter().map(|e| e.event_type.as_str()).collect()
+}
+
+#[tokio::test]
+async fn subscribing_and_confirming_records_the_consent_trail() {
+    //Arrange
+    let app = spawn_app().await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    app.api_client
+        .post(format!("{}/subscriptions", &app.address))
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .header("User-Agent", "events-test/1.0")
+        .body("name=le%20guin&email=ursula_le_guin%40gmail.com")
+        .send()
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+    let email_request = &app.email_server.received_requests().await.unwrap()[0];
+    let confirmation_links = app.get_confirmation_links(email_request);
+    reqwest::get(confirmation_links.html).await.unwrap().error_for_status().unwrap();
+
+    //Assert
+    let subscriber_id = sqlx::query!("SELECT id FROM subscriptions WHERE email = $1", EMAIL)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .id;
+    let events = events_of(&app, subscriber_id).await;
+    assert_eq!(event_types(&events), vec!["subscribed", "confirmation_email_sent", "confirmed"]);
+    assert_eq!(events[0].source, "form");
+    assert_eq!(events[0].user_agent.as_deref(), Some("events-test/1.0"));
+    assert_eq!(events[0].ip_address.as_deref(), Some("127.0.0.1"));
+    assert_eq!(events[0].details["lists"][0], "newsletter");
+    assert_eq!(events[2].source, "email_link");
+}
+
+#[tokio::test]
+async fn preference_changes_and_unsubscribing_are_recorded() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let token = app.preferences_token(subscriber_id);
+
+    //Act
+    app.post_preferences(&[
+        ("token", token.as_str()),
+        ("name", "le guin"),
+        ("delivery_frequency", "weekly"),
+        ("pause", "")
+    ])
+    .await;
+    app.post_unsubscribe(&token).await;
+
+    //Assert
+    let events = events_of(&app, subscriber_id).await;
+    assert_eq!(
+        event_types(&events),
+        vec!["subscribed", "confirmation_email_sent", "confirmed", "preferences_changed", "unsubscribed"]
+    );
+    assert_eq!(events[3].details["delivery_frequency"], "weekly");
+    assert_eq!(events[4].source, "form");
+}
+
+#[tokio::test]
+async fn imported_subscribers_have_their_consent_recorded() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    let list_id = sqlx::query!("SELECT list_id FROM lists WHERE slug = 'newsletter'")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .list_id
+        .to_string();
+    let response = app
+        .post_import(
+            "email,name,consent_date\nursula@example.com,Ursula,2023-05-01\n",
+            &[("list_id", &list_id), ("mode", "confirmed"), ("source", "old-provider"), ("consent_date", "")]
+        )
+        .await;
+    let report_path = response.headers().get("Location").unwrap().to_str().unwrap().to_string();
+
+    //Act
+    app.post_start_import(&report_path).await;
+    app.import_all_pending_rows().await;
+
+    //Assert
+    let subscriber_id = sqlx::query!("SELECT id FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .id;
+    let events = events_of(&app, subscriber_id).await;
+    assert_eq!(event_types(&events), vec!["subscribed", "confirmed"]);
+    assert!(events.iter().all(|e| e.source == "import" && e.ip_address.is_none()));
+    assert_eq!(events[0].details["source"], "old-provider");
+    assert!(events[1].details["consented_at"].as_str().unwrap().starts_with("2023-05-01"));
+}
+
+#[tokio::test]
+async fn the_subscriber_page_shows_the_timeline() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    app.test_user.login(&app).await;
+
+    //Act
+    let html_page = app.get_subscriber_details_html(subscriber_id).await;
+
+    //Assert
+    assert!(html_page.contains("<h2>Timeline</h2>"));
+    assert!(html_page.contains("<td>subscribed</td><td>form</td>"));
+    assert!(html_page.contains("<td>confirmed</td><td>email_link</td>"));
+}
+
+#[tokio::test]
+async fn erasure_removes_the_events_and_leaves_only_an_erased_marker() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    app.test_user.login(&app).await;
+    let export: serde_json::Value = app.get_personal_data_export(EMAIL).await.json().await.unwrap();
+    assert_eq!(export["subscription_events"].as_array().unwrap().len(), 3);
+
+    //Act
+    app.post_erase_personal_data(EMAIL).await;
+
+    //Assert
+    let events = events_of(&app, subscriber_id).await;
+    assert_eq!(event_types(&events), vec!["erased"]);
+    assert_eq!(events[0].source, "admin");
+    assert!(events[0].ip_address.is_none());
+    assert!(events[0].user_agent.is_none());
+}
+
+#[tokio::test]
+async fn events_cannot_be_modified() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    let result = sqlx::query!(
+        "UPDATE subscription_events SET source = 'admin' WHERE subscriber_id = $1",
+        subscriber_id
+    )
+    .execute(&app.db_pool)
+    .await;
+
+    //Assert
+    assert!(result.unwrap_err().to_string().contains("append-only"));
+}
@@ -0,0 +1,86 @@
+use crate::helpers::spawn_app;
+use chrono::Utc;
+use wiremock::{ResponseTemplate, Mock};
+use wiremock::matchers::{path, method};
+use zero2prod::subscriptions_cleanup_worker::purge_stale_subscriptions;
+
+#[tokio::test]
+async fn stale_unconfirmed_subscribers_and_their_tokens_are_purged() {
+    //Arrange
+    let app = spawn_app().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions("name=le%20guin&email=ursula_le_guin%40gmail.com".into()).await;
+    app.post_subscriptions("name=tolkien&email=tolkien%40gmail.com".into()).await;
+    //첫 번째 구독자만 오래된 미확인 구독자로 만든다.
+    sqlx::query!(
+        "UPDATE subscriptions SET subscribed_at = now() - interval '30 days' WHERE email = $1",
+        "ursula_le_guin@gmail.com"
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+
+    //Act
+    let outcome = purge_stale_subscriptions(&app.db_pool, Utc::now() - chrono::Duration::days(7))
+        .await
+        .unwrap();
+
+    //Assert
+    assert_eq!(outcome.deleted_subscribers, 1);
+    assert_eq!(outcome.deleted_tokens, 1);
+    let remaining = sqlx::query!("SELECT email FROM subscriptions")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].email, "tolkien@gmail.com");
+}
+
+#[tokio::test]
+async fn confirmed_subscribers_are_never_purged() {
+    //Arrange
+    let app = spawn_app().await;
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions("name=le%20guin&email=ursula_le_guin%40gmail.com".into()).await;
+    let email_request = &app.email_server.received_requests().await.unwrap()[0];
+    let confirmation_links = app.get_confirmation_links(email_request);
+    reqwest::get(confirmation_links.html)
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+    sqlx::query!("UPDATE subscriptions SET subscribed_at = now() - interval '30 days'")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+    sqlx::query!("UPDATE subscription_tokens SET created_at = now() - interval '30 days'")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    //Act
+    let outcome = purge_stale_subscriptions(&app.db_pool, Utc::now() - chrono::Duration::days(7))
+        .await
+        .unwrap();
+
+    //Assert
+    assert_eq!(outcome.deleted_subscribers, 0);
+    assert_eq!(outcome.deleted_tokens, 1);
+    let saved = sqlx::query!("SELECT status FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(saved.status, "confirmed");
+}
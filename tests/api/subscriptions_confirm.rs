@@ -17,7 +17,7 @@ async fn the_link_returned_by_subscribe_returns_a_200_if_called() {
 
     app.post_subscriptions(body.into()).await;
     let email_request = &app.email_server.received_requests().await.unwrap()[0];
-    let confirmation_links = app.get_confirmation_links(&email_request);
+    let confirmation_links = app.get_confirmation_links(email_request);
 
     //Act
     let response = reqwest::get(confirmation_links.html)
@@ -41,7 +41,7 @@ async fn clicking_on_the_confirmation_link_confirm_a_subscribe() {
 
     app.post_subscriptions(body.into()).await;
     let email_request = &app.email_server.received_requests().await.unwrap()[0];
-    let confirmation_links = app.get_confirmation_links(&email_request);
+    let confirmation_links = app.get_confirmation_links(email_request);
 
     //Act
     reqwest::get(confirmation_links.html)
@@ -59,4 +59,117 @@ async fn clicking_on_the_confirmation_link_confirm_a_subscribe() {
     assert_eq!(saved.email, "ursula_le_guin@gmail.com");
     assert_eq!(saved.name, "le guin");
     assert_eq!(saved.status, "confirmed");
-}
\ No newline at end of file
+}
+#[tokio::test]
+async fn clicking_a_used_confirmation_link_shows_the_already_confirmed_page() {
+    //Arrange
+    let app = spawn_app().await;
+    let body = "name=le%20guin&email=ursula_le_guin%40gmail.com";
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(body.into()).await;
+    let email_request = &app.email_server.received_requests().await.unwrap()[0];
+    let confirmation_links = app.get_confirmation_links(email_request);
+    reqwest::get(confirmation_links.html.clone())
+        .await
+        .unwrap()
+        .error_for_status()
+        .unwrap();
+
+    //Act
+    let response = reqwest::get(confirmation_links.html)
+        .await
+        .unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.text().await.unwrap().contains("Already confirmed"));
+}
+
+#[tokio::test]
+async fn confirming_a_subscription_returns_an_html_confirmation_page() {
+    //Arrange
+    let app = spawn_app().await;
+    let body = "name=le%20guin&email=ursula_le_guin%40gmail.com";
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(body.into()).await;
+    let email_request = &app.email_server.received_requests().await.unwrap()[0];
+    let confirmation_links = app.get_confirmation_links(email_request);
+
+    //Act
+    let response = reqwest::get(confirmation_links.html)
+        .await
+        .unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers().get("Content-Type").unwrap(), "text/html; charset=utf-8");
+    assert!(response.text().await.unwrap().contains("Subscription confirmed"));
+}
+
+#[tokio::test]
+async fn an_unknown_confirmation_token_shows_the_invalid_link_page() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let response = reqwest::get(&format!(
+        "{}/subscriptions/confirm?subscription_token=doesnotexist",
+        app.address
+    ))
+    .await
+    .unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 401);
+    assert!(response.text().await.unwrap().contains("Invalid link"));
+}
+
+#[tokio::test]
+async fn an_expired_confirmation_link_is_rejected_with_a_resend_form() {
+    //Arrange
+    let app = spawn_app().await;
+    let body = "name=le%20guin&email=ursula_le_guin%40gmail.com";
+
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    app.post_subscriptions(body.into()).await;
+    let email_request = &app.email_server.received_requests().await.unwrap()[0];
+    let confirmation_links = app.get_confirmation_links(email_request);
+    //토큰의 생성 시각을 유효 시간보다 과거로 옮긴다.
+    sqlx::query!("UPDATE subscription_tokens SET created_at = now() - interval '30 days'")
+        .execute(&app.db_pool)
+        .await
+        .unwrap();
+
+    //Act
+    let response = reqwest::get(confirmation_links.html)
+        .await
+        .unwrap();
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 410);
+    let html_page = response.text().await.unwrap();
+    assert!(html_page.contains("This confirmation link has expired."));
+    assert!(html_page.contains(&htmlescape::encode_attribute("ursula_le_guin@gmail.com")));
+    let saved = sqlx::query!("SELECT status FROM subscriptions")
+        .fetch_one(&app.db_pool)
+        .await
+        .expect("Failed to fetch saved subscription.");
+    assert_eq!(saved.status, "pending_confirmation");
+}
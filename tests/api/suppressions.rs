@@ -0,0 +1,172 @@
+use crate::helpers::{spawn_app, assert_is_redirect_to, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+use zero2prod::domain::SubscriberEmail;
+use zero2prod::email_client::SendEmailError;
+
+async fn get_suppressions_html(app: &TestApp, email: &str) -> String {
+    app.api_client
+        .get(format!("{}/admin/suppressions", &app.address))
+        .query(&[("email", email)])
+        .send()
+        .await
+        .expect("Failed to execute request.")
+        .text()
+        .await
+        .unwrap()
+}
+
+async fn post_suppression(app: &TestApp, email: &str, reason: &str) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/admin/suppressions", &app.address))
+        .form(&[("email", email), ("reason", reason)])
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+async fn post_delete_suppression(app: &TestApp, email: &str) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/admin/suppressions/delete", &app.address))
+        .form(&[("email", email)])
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+const EMAIL: &str = "ursula_le_guin@gmail.com";
+
+#[tokio::test]
+async fn you_must_be_logged_in_to_manage_the_suppression_list() {
+    //Arrange
+    let app = spawn_app().await;
+
+    //Act
+    let add = post_suppression(&app, EMAIL, "manual").await;
+    let delete = post_delete_suppression(&app, EMAIL).await;
+
+    //Assert
+    assert_is_redirect_to(&add, "/login");
+    assert_is_redirect_to(&delete, "/login");
+}
+
+#[tokio::test]
+async fn an_admin_can_suppress_and_lift_an_address() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act - Part 1 - 억제
+    let response = post_suppression(&app, "Ursula_Le_Guin@gmail.com", "role_address").await;
+    assert_is_redirect_to(&response, "/admin/suppressions");
+
+    //Assert - Part 1
+    let html_page = get_suppressions_html(&app, EMAIL).await;
+    assert!(html_page.contains("Ursula_Le_Guin@gmail.com has been suppressed."));
+    assert!(html_page.contains(&format!("{} is suppressed (role_address)", EMAIL)));
+    assert!(html_page.contains(&zero2prod::suppression::hash_email(EMAIL)));
+
+    //Act - Part 2 - 해제
+    post_delete_suppression(&app, EMAIL).await;
+
+    //Assert - Part 2
+    let html_page = get_suppressions_html(&app, EMAIL).await;
+    assert!(html_page.contains(&format!("The suppression of {} has been lifted.", EMAIL)));
+    assert!(html_page.contains(&format!("{} is not suppressed.", EMAIL)));
+}
+
+#[tokio::test]
+async fn unknown_reasons_and_invalid_addresses_are_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+
+    //Act
+    post_suppression(&app, EMAIL, "because").await;
+    let html_page = get_suppressions_html(&app, "").await;
+    post_suppression(&app, "not-an-email", "manual").await;
+
+    //Assert
+    assert!(html_page.contains("because is not a valid reason."));
+    let count = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM suppressed_emails"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap()
+        .count;
+    assert_eq!(count, 0);
+}
+
+#[tokio::test]
+async fn the_email_client_refuses_suppressed_recipients() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_suppression(&app, EMAIL, "legal_request").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let outcome = app
+        .email_client
+        .send_email(&SubscriberEmail::parse(EMAIL.into()).unwrap(), "Subject", "<p>Body</p>", "Body")
+        .await;
+
+    //Assert
+    assert!(matches!(outcome, Err(SendEmailError::Suppressed)));
+}
+
+#[tokio::test]
+async fn issues_queued_before_an_address_is_suppressed_are_not_sent() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Newsletter body as plain text",
+        "html_content": "<p>Newsletter body as HTML</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+
+    //Act
+    post_suppression(&app, EMAIL, "complaint").await;
+    app.dispatch_all_pending_emails().await;
+
+    //Assert
+    let pending = sqlx::query!(r#"SELECT COUNT(*) AS "count!" FROM issue_delivery_queue"#)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(pending.count, 0);
+    //Mock은 Drop 시점에 이메일을 보내지 않았는지 검증한다.
+}
+
+#[tokio::test]
+async fn a_lifted_address_can_subscribe_again() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    post_suppression(&app, EMAIL, "manual").await;
+    post_delete_suppression(&app, EMAIL).await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    let response = app.post_subscriptions("name=le%20guin&email=ursula_le_guin%40gmail.com".into()).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+}
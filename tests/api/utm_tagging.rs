@@ -0,0 +1,112 @@
+use crate::helpers::{spawn_app, TestApp};
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+const HTML_CONTENT: &str = r#"<p><a href="https://blog.org/post">Post</a> <a href="https://shop.org/?utm_source=partner">Shop</a> <a href="http://127.0.0.1/archive">Archive</a></p>"#;
+const TEXT_CONTENT: &str = "Post: https://blog.org/post";
+
+//구독자를 추가한다. 클릭 추적이 링크를 바꾸지 않도록 추적은 거부한다.
+async fn subscribe(app: &TestApp) {
+    app.test_user.login(app).await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    app.opt_out_of_tracking(subscriber_id).await;
+}
+
+async fn publish_and_get_bodies(app: &TestApp, utm_fields: &[(&str, &str)]) -> (String, String) {
+    let idempotency_key = Uuid::new_v4().to_string();
+    let mut body = vec![
+        ("title", "October Update!"),
+        ("text_content", TEXT_CONTENT),
+        ("html_content", HTML_CONTENT),
+        ("idempotency_key", idempotency_key.as_str())
+    ];
+    body.extend_from_slice(utm_fields);
+    app.post_publish_newsletter(&body).await;
+    app.dispatch_all_pending_emails().await;
+    let email_request = app.email_server.received_requests().await.unwrap().pop().unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&email_request.body).unwrap();
+    (body["HtmlBody"].as_str().unwrap().into(), body["TextBody"].as_str().unwrap().into())
+}
+
+#[tokio::test]
+async fn external_links_are_tagged_with_a_campaign_named_after_the_title() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    let (html_body, text_body) = publish_and_get_bodies(&app, &[("utm_tagging", "on")]).await;
+
+    //Assert
+    let tagged = "https://blog.org/post?utm_source=newsletter&utm_medium=email&utm_campaign=october-update";
+    assert!(html_body.contains(&format!(r#"href="{}""#, htmlescape::encode_minimal(tagged))));
+    assert!(text_body.starts_with(&format!("Post: {}", tagged)));
+    //이미 UTM 파라미터가 있는 링크와 우리 사이트의 링크는 그대로 둔다.
+    assert!(html_body.contains(r#"href="https://shop.org/?utm_source=partner""#));
+    assert!(html_body.contains(r#"href="http://127.0.0.1/archive""#));
+}
+
+#[tokio::test]
+async fn an_explicit_campaign_name_is_used_when_given() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    let (_, text_body) = publish_and_get_bodies(&app, &[("utm_tagging", "on"), ("utm_campaign", "fall sale")]).await;
+
+    //Assert
+    assert!(text_body.starts_with("Post: https://blog.org/post?utm_source=newsletter&utm_medium=email&utm_campaign=fall+sale"));
+}
+
+#[tokio::test]
+async fn links_are_not_tagged_unless_the_issue_asks_for_it() {
+    //Arrange
+    let app = spawn_app().await;
+    subscribe(&app).await;
+
+    //Act
+    let (html_body, text_body) = publish_and_get_bodies(&app, &[("utm_campaign", "ignored")]).await;
+
+    //Assert
+    assert!(html_body.starts_with(HTML_CONTENT));
+    assert!(text_body.starts_with(TEXT_CONTENT));
+}
+
+#[tokio::test]
+async fn click_tracking_redirects_to_the_tagged_link() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    publish_and_get_bodies(&app, &[("utm_tagging", "on")]).await;
+
+    //Assert
+    let mut urls: Vec<String> = sqlx::query!("SELECT url FROM issue_links")
+        .fetch_all(&app.db_pool)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.url)
+        .collect();
+    urls.sort();
+    assert_eq!(
+        urls,
+        vec![
+            "http://127.0.0.1/archive",
+            "https://blog.org/post?utm_source=newsletter&utm_medium=email&utm_campaign=october-update",
+            "https://shop.org/?utm_source=partner"
+        ]
+    );
+}
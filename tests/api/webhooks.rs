@@ -0,0 +1,239 @@
+use crate::helpers::{spawn_app, TestApp};
+use secrecy::ExposeSecret;
+use wiremock::matchers::any;
+use wiremock::{Mock, ResponseTemplate};
+use uuid::Uuid;
+
+async fn post_postmark_webhook(app: &TestApp, body: &serde_json::Value) -> reqwest::Response {
+    app.api_client
+        .post(format!("{}/webhooks/postmark", &app.address))
+        .basic_auth(&app.webhook_settings.username, Some(app.webhook_settings.password.expose_secret()))
+        .json(body)
+        .send()
+        .await
+        .expect("Failed to execute request.")
+}
+
+const EMAIL: &str = "ursula_le_guin@gmail.com";
+
+fn bounce(id: i64, bounce_type: &str) -> serde_json::Value {
+    serde_json::json!({
+        "RecordType": "Bounce",
+        "ID": id,
+        "Type": bounce_type,
+        "TypeCode": 1,
+        "Email": "Ursula_Le_Guin@gmail.com",
+        "BouncedAt": "2026-10-19T08:00:00Z",
+        "Description": "The server was unable to deliver your message.",
+        "MessageStream": "outbound"
+    })
+}
+
+async fn subscriber_status(app: &TestApp, subscriber_id: Uuid) -> (String, i32) {
+    let row = sqlx::query!("SELECT status, soft_bounce_count FROM subscriptions WHERE id = $1", subscriber_id)
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    (row.status, row.soft_bounce_count)
+}
+
+async fn suppression_reason(app: &TestApp) -> Option<String> {
+    sqlx::query!("SELECT reason FROM suppressed_emails")
+        .fetch_optional(&app.db_pool)
+        .await
+        .unwrap()
+        .map(|r| r.reason)
+}
+
+#[tokio::test]
+async fn webhooks_without_valid_credentials_are_rejected() {
+    //Arrange
+    let app = spawn_app().await;
+    let test_cases = vec![
+        (None, "missing credentials"),
+        (Some(("postmark", "wrong-password")), "wrong password"),
+        (Some(("someone", app.webhook_settings.password.expose_secret().as_str())), "wrong username")
+    ];
+
+    for (credentials, description) in test_cases {
+        //Act
+        let mut request = app.api_client
+            .post(format!("{}/webhooks/postmark", &app.address))
+            .json(&bounce(1, "HardBounce"));
+        if let Some((username, password)) = credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+        let response = request.send().await.unwrap();
+
+        //Assert
+        assert_eq!(response.status().as_u16(), 401, "The webhook accepted {}.", description);
+        assert_eq!(response.headers()["WWW-Authenticate"], r#"Basic realm="webhooks""#);
+    }
+    assert_eq!(suppression_reason(&app).await, None);
+}
+
+//20261019 추가 / 바디는 인증한 뒤에 파싱한다.
+#[tokio::test]
+async fn invalid_bodies_are_rejected_only_after_authentication() {
+    //Arrange
+    let app = spawn_app().await;
+    let request = |authenticated: bool| {
+        let request = app.api_client
+            .post(format!("{}/webhooks/postmark", &app.address))
+            .header("Content-Type", "application/json")
+            .body("not json");
+        if authenticated {
+            request.basic_auth(&app.webhook_settings.username, Some(app.webhook_settings.password.expose_secret()))
+        } else {
+            request
+        }
+    };
+
+    //Act
+    let unauthenticated = request(false).send().await.unwrap();
+    let authenticated = request(true).send().await.unwrap();
+
+    //Assert
+    assert_eq!(unauthenticated.status().as_u16(), 401);
+    assert_eq!(authenticated.status().as_u16(), 400);
+}
+
+#[tokio::test]
+async fn a_hard_bounce_marks_the_subscriber_bounced_and_suppresses_the_address() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    let response = post_postmark_webhook(&app, &bounce(42, "HardBounce")).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(subscriber_status(&app, subscriber_id).await.0, "bounced");
+    assert_eq!(suppression_reason(&app).await.as_deref(), Some("hard_bounce"));
+    let event = sqlx::query!(
+        "SELECT source, details FROM subscription_events WHERE subscriber_id = $1 AND event_type = 'bounced'",
+        subscriber_id
+    )
+    .fetch_one(&app.db_pool)
+    .await
+    .unwrap();
+    assert_eq!(event.source, "system");
+    assert_eq!(event.details["postmark_id"], 42);
+}
+
+#[tokio::test]
+async fn a_spam_complaint_marks_the_subscriber_complained() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    let response = post_postmark_webhook(&app, &serde_json::json!({
+        "RecordType": "SpamComplaint",
+        "ID": 7,
+        "Type": "SpamComplaint",
+        "TypeCode": 512,
+        "Email": EMAIL
+    }))
+    .await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(subscriber_status(&app, subscriber_id).await.0, "complained");
+    assert_eq!(suppression_reason(&app).await.as_deref(), Some("spam_complaint"));
+}
+
+#[tokio::test]
+async fn soft_bounces_suppress_the_address_only_after_the_limit() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    let limit = app.webhook_settings.soft_bounce_limit as i64;
+
+    //Act - Part 1 - 한도 직전까지
+    for id in 1..limit {
+        post_postmark_webhook(&app, &bounce(id, "SoftBounce")).await;
+    }
+
+    //Assert - Part 1
+    assert_eq!(subscriber_status(&app, subscriber_id).await, ("confirmed".into(), limit as i32 - 1));
+    assert_eq!(suppression_reason(&app).await, None);
+
+    //Act - Part 2
+    post_postmark_webhook(&app, &bounce(limit, "SoftBounce")).await;
+
+    //Assert - Part 2
+    assert_eq!(subscriber_status(&app, subscriber_id).await.0, "bounced");
+    assert_eq!(suppression_reason(&app).await.as_deref(), Some("soft_bounce_limit"));
+}
+
+#[tokio::test]
+async fn a_redelivered_webhook_is_processed_only_once() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    post_postmark_webhook(&app, &bounce(1, "SoftBounce")).await;
+    let response = post_postmark_webhook(&app, &bounce(1, "SoftBounce")).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(subscriber_status(&app, subscriber_id).await.1, 1);
+}
+
+#[tokio::test]
+async fn notifications_that_are_not_bounces_are_acknowledged_and_ignored() {
+    //Arrange
+    let app = spawn_app().await;
+    let subscriber_id = app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+
+    //Act
+    let response = post_postmark_webhook(&app, &bounce(1, "AutoResponder")).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(subscriber_status(&app, subscriber_id).await, ("confirmed".into(), 0));
+    assert_eq!(suppression_reason(&app).await, None);
+}
+
+#[tokio::test]
+async fn suppressed_addresses_are_not_sent_issues_or_confirmation_emails() {
+    //Arrange
+    let app = spawn_app().await;
+    app.test_user.login(&app).await;
+    app.subscribe_and_confirm("name=le%20guin&email=ursula_le_guin%40gmail.com").await;
+    //상태와 관계없이 억제 목록만으로 제외되는지 확인하기 위해 구독자 상태는 그대로 둔다.
+    sqlx::query!(
+        "INSERT INTO suppressed_emails (email_hash, reason) VALUES ($1, 'hard_bounce')",
+        zero2prod::suppression::hash_email(EMAIL)
+    )
+    .execute(&app.db_pool)
+    .await
+    .unwrap();
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&app.email_server)
+        .await;
+
+    //Act
+    app.post_publish_newsletter(&serde_json::json!({
+        "title": "Newsletter title",
+        "text_content": "Newsletter body as plain text",
+        "html_content": "<p>Newsletter body as HTML</p>",
+        "idempotency_key": Uuid::new_v4().to_string()
+    }))
+    .await;
+    let response = app.post_subscriptions("name=le%20guin&email=ursula_le_guin%40gmail.com".into()).await;
+
+    //Assert
+    assert_eq!(response.status().as_u16(), 200);
+    let queued = sqlx::query!("SELECT COUNT(*) AS \"count!\" FROM issue_delivery_queue")
+        .fetch_one(&app.db_pool)
+        .await
+        .unwrap();
+    assert_eq!(queued.count, 0);
+    //Mock은 Drop 시점에 이메일을 보내지 않았는지 검증한다.
+}